@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 
+/// Operation messaging providers invoke on linked actors to deliver a [`SubMessage`]
+pub const HANDLE_MESSAGE_OPERATION: &str = "MessageSubscriber.HandleMessage";
+
 /// A message to be published
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct PubMessage {
@@ -63,3 +66,99 @@ pub struct SubMessage {
     #[serde(default)]
     pub body: Vec<u8>,
 }
+
+/// A message in the shape of `wasmcloud:messaging/types.broker-message`, which [`SubMessage`]s
+/// delivered by messaging providers are converted to and from for component actors
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BrokerMessage {
+    /// The subject, or topic, of the message
+    pub subject: String,
+    /// The message payload
+    pub body: Option<Vec<u8>>,
+    /// An optional topic on which the reply should be sent.
+    pub reply_to: Option<String>,
+}
+
+impl From<SubMessage> for BrokerMessage {
+    fn from(
+        SubMessage {
+            subject,
+            reply_to,
+            body,
+        }: SubMessage,
+    ) -> Self {
+        Self {
+            subject,
+            body: Some(body),
+            reply_to,
+        }
+    }
+}
+
+impl From<BrokerMessage> for SubMessage {
+    fn from(
+        BrokerMessage {
+            subject,
+            body,
+            reply_to,
+        }: BrokerMessage,
+    ) -> Self {
+        Self {
+            subject,
+            reply_to,
+            body: body.unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Message as encoded by messaging providers generated with `wasmcloud-provider-wit-bindgen`
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ProviderMessage {
+        subject: String,
+        reply_to: Option<String>,
+        body: Vec<u8>,
+    }
+
+    #[test]
+    fn sub_message_from_provider() -> anyhow::Result<()> {
+        let buf = rmp_serde::to_vec_named(&ProviderMessage {
+            subject: "test-subject".into(),
+            reply_to: Some("test-reply".into()),
+            body: b"test".to_vec(),
+        })?;
+        let msg: SubMessage = rmp_serde::from_slice(&buf)?;
+        assert_eq!(
+            BrokerMessage::from(msg),
+            BrokerMessage {
+                subject: "test-subject".into(),
+                body: Some(b"test".to_vec()),
+                reply_to: Some("test-reply".into()),
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn broker_message_roundtrip() {
+        let msg = SubMessage {
+            subject: "test-subject".into(),
+            reply_to: None,
+            body: b"test".to_vec(),
+        };
+        assert_eq!(SubMessage::from(BrokerMessage::from(msg.clone())), msg);
+
+        // A missing body is delivered as an empty one
+        let msg = SubMessage::from(BrokerMessage {
+            subject: "test-subject".into(),
+            body: None,
+            reply_to: Some("test-reply".into()),
+        });
+        assert_eq!(msg.body, Vec::<u8>::new());
+        assert_eq!(msg.reply_to.as_deref(), Some("test-reply"));
+    }
+}
@@ -17,7 +17,11 @@ use core::fmt::{self, Debug};
 use core::time::Duration;
 
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
+use async_nats::HeaderMap;
+use base64::Engine;
 use cloudevents::event::Event;
 use futures::{StreamExt, TryFutureExt};
 use serde::de::DeserializeOwned;
@@ -27,6 +31,44 @@ use tracing::{debug, error, instrument, trace};
 
 type Result<T> = ::std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
 
+/// Header containing the public key of the control interface caller, if the client was configured
+/// with a caller key
+pub const CTL_CALLER_HEADER: &str = "wasmcloud-ctl-caller";
+/// Header containing the base64-encoded signature of [`ctl_caller_signing_input`], made with the
+/// caller key identified by [`CTL_CALLER_HEADER`]
+pub const CTL_CALLER_SIGNATURE_HEADER: &str = "wasmcloud-ctl-caller-signature";
+/// Header containing the time the caller signed the request, in seconds since the Unix epoch
+pub const CTL_CALLER_ISSUED_AT_HEADER: &str = "wasmcloud-ctl-caller-issued-at";
+/// Header containing a unique value the caller generated for the request
+pub const CTL_CALLER_NONCE_HEADER: &str = "wasmcloud-ctl-caller-nonce";
+/// Maximum difference between the issue time of a signed request and the clock of the host
+/// receiving it. Hosts reject older (or newer) requests, and remember the nonces of accepted
+/// requests for this long so that a signed request can't be replayed
+pub const CTL_CALLER_MAX_AGE: Duration = Duration::from_secs(60);
+
+/// Returns the bytes a control interface caller signs to prove its identity for a request. The
+/// subject, issue time, nonce and payload are signed so that a signature can't be reused on a
+/// different subject or replayed later
+#[must_use]
+pub fn ctl_caller_signing_input(
+    subject: &str,
+    issued_at: u64,
+    nonce: &str,
+    payload: &[u8],
+) -> Vec<u8> {
+    let issued_at = issued_at.to_string();
+    let mut input =
+        Vec::with_capacity(subject.len() + issued_at.len() + nonce.len() + 3 + payload.len());
+    input.extend_from_slice(subject.as_bytes());
+    input.push(b'\n');
+    input.extend_from_slice(issued_at.as_bytes());
+    input.push(b'\n');
+    input.extend_from_slice(nonce.as_bytes());
+    input.push(b'\n');
+    input.extend_from_slice(payload);
+    input
+}
+
 /// Lattice control interface client
 #[derive(Clone)]
 pub struct Client {
@@ -36,6 +78,7 @@ pub struct Client {
     pub lattice: String,
     timeout: Duration,
     auction_timeout: Duration,
+    caller_key: Option<Arc<nkeys::KeyPair>>,
 }
 
 impl Debug for Client {
@@ -45,6 +88,10 @@ impl Debug for Client {
             .field("lattice", &self.lattice)
             .field("timeout", &self.timeout)
             .field("auction_timeout", &self.auction_timeout)
+            .field(
+                "caller",
+                &self.caller_key.as_ref().map(|key| key.public_key()),
+            )
             .finish_non_exhaustive()
     }
 }
@@ -57,6 +104,7 @@ pub struct ClientBuilder {
     lattice: String,
     timeout: Duration,
     auction_timeout: Duration,
+    caller_key: Option<Arc<nkeys::KeyPair>>,
 }
 
 impl ClientBuilder {
@@ -70,6 +118,7 @@ impl ClientBuilder {
             lattice: "default".to_string(),
             timeout: Duration::from_secs(2),
             auction_timeout: Duration::from_secs(5),
+            caller_key: None,
         }
     }
 
@@ -110,6 +159,16 @@ impl ClientBuilder {
         }
     }
 
+    /// Sets the key used to sign control interface requests, which identifies the caller to hosts
+    /// (e.g. for policy decisions). If not set, requests are sent without a caller identity
+    #[must_use]
+    pub fn caller_key(self, key: Arc<nkeys::KeyPair>) -> ClientBuilder {
+        ClientBuilder {
+            caller_key: Some(key),
+            ..self
+        }
+    }
+
     /// Constructs the client with the given configuration from the builder
     #[must_use]
     pub fn build(self) -> Client {
@@ -119,6 +178,7 @@ impl ClientBuilder {
             lattice: self.lattice,
             timeout: self.timeout,
             auction_timeout: self.auction_timeout,
+            caller_key: self.caller_key,
         }
     }
 }
@@ -131,6 +191,29 @@ impl Client {
         ClientBuilder::new(nc).build()
     }
 
+    /// Returns the headers for a request, including the trace context and, if the client has a
+    /// caller key, the caller identity
+    fn headers(&self, subject: &str, payload: &[u8]) -> Result<HeaderMap> {
+        let mut headers: HeaderMap = otel::HeaderInjector::default_with_span().into();
+        if let Some(key) = &self.caller_key {
+            let issued_at = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+            let nonce = nuid::next();
+            let signature = key.sign(&ctl_caller_signing_input(
+                subject, issued_at, &nonce, payload,
+            ))?;
+            headers.insert(CTL_CALLER_HEADER, key.public_key().as_str());
+            headers.insert(
+                CTL_CALLER_SIGNATURE_HEADER,
+                base64::engine::general_purpose::STANDARD
+                    .encode(signature)
+                    .as_str(),
+            );
+            headers.insert(CTL_CALLER_ISSUED_AT_HEADER, issued_at.to_string().as_str());
+            headers.insert(CTL_CALLER_NONCE_HEADER, nonce.as_str());
+        }
+        Ok(headers)
+    }
+
     #[instrument(level = "debug", skip_all)]
     pub(crate) async fn request_timeout(
         &self,
@@ -138,13 +221,11 @@ impl Client {
         payload: Vec<u8>,
         timeout: Duration,
     ) -> Result<async_nats::Message> {
+        let headers = self.headers(&subject, &payload)?;
         match tokio::time::timeout(
             timeout,
-            self.nc.request_with_headers(
-                subject,
-                otel::HeaderInjector::default_with_span().into(),
-                payload.into(),
-            ),
+            self.nc
+                .request_with_headers(subject, headers, payload.into()),
         )
         .await
         {
@@ -280,13 +361,10 @@ impl Client {
         let subject = broker::publish_registries(&self.topic_prefix, &self.lattice);
         debug!("put_registries:publish {}", &subject);
         let bytes = json_serialize(&registries)?;
+        let headers = self.headers(&subject, &bytes)?;
         let resp = self
             .nc
-            .publish_with_headers(
-                subject,
-                otel::HeaderInjector::default_with_span().into(),
-                bytes.into(),
-            )
+            .publish_with_headers(subject, headers, bytes.into())
             .await;
         if let Err(e) = resp {
             Err(format!("Failed to push registry credential map: {e}").into())
@@ -661,13 +739,9 @@ impl Client {
     ) -> Result<Vec<D>> {
         let reply = self.nc.new_inbox();
         let sub = self.nc.subscribe(reply.clone()).await?;
+        let headers = self.headers(&subject, &payload)?;
         self.nc
-            .publish_with_reply_and_headers(
-                subject.clone(),
-                reply,
-                otel::HeaderInjector::default_with_span().into(),
-                payload.into(),
-            )
+            .publish_with_reply_and_headers(subject.clone(), reply, headers, payload.into())
             .await?;
         let nc = self.nc.clone();
         tokio::spawn(async move {
@@ -16,12 +16,14 @@
 // I will always be chunkified ...
 
 use std::marker::Unpin;
+use std::pin::Pin;
+use std::task::{Context as TaskContext, Poll};
 use std::time::Duration;
 
 use anyhow::{anyhow, Context};
 use async_nats::jetstream;
 use async_nats::jetstream::object_store::{self, ObjectStore};
-use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::io::{AsyncRead, AsyncReadExt, ReadBuf};
 use tracing::{debug, error, instrument};
 
 /// Amount of time to add to rpc timeout if chunkifying
@@ -93,6 +95,35 @@ impl ChunkEndpoint {
         self.get_unchunkified(&format!("{inv_id}-r")).await
     }
 
+    /// open a stream of the message after de-chunking, to read it without loading it into memory.
+    /// The chunks are deleted once the stream is dropped.
+    #[instrument(level = "trace", skip(self))]
+    pub async fn get_unchunkified_stream(&self, inv_id: &str) -> anyhow::Result<ChunkedStream> {
+        let store = self
+            .create_or_reuse_store()
+            .await
+            .context("failed to get object store")?;
+        debug!(invocation_id = %inv_id, "chunkify starting to stream");
+        let object = store
+            .get(inv_id)
+            .await
+            .context("failed to receive chunked stream")?;
+        Ok(ChunkedStream {
+            object,
+            store,
+            name: inv_id.to_string(),
+        })
+    }
+
+    /// open a stream of a response after de-chunking
+    #[allow(clippy::missing_errors_doc)] // TODO: Document errors
+    pub async fn get_unchunkified_response_stream(
+        &self,
+        inv_id: &str,
+    ) -> anyhow::Result<ChunkedStream> {
+        self.get_unchunkified_stream(&format!("{inv_id}-r")).await
+    }
+
     /// chunkify a message
     #[instrument(level = "trace", skip(self, bytes))]
     pub async fn chunkify(
@@ -112,6 +143,17 @@ impl ChunkEndpoint {
         Ok(())
     }
 
+    /// delete a chunked message, e.g. one that was only partially written or will not be received
+    #[allow(clippy::missing_errors_doc)] // TODO: Document errors
+    pub async fn delete(&self, inv_id: &str) -> anyhow::Result<()> {
+        let store = self.create_or_reuse_store().await?;
+        store
+            .delete(inv_id)
+            .await
+            .map_err(|e| anyhow!(e))
+            .with_context(|| format!("failed to delete chunks of {inv_id}"))
+    }
+
     /// chunkify a portion of a response
     #[allow(clippy::missing_errors_doc)] // TODO: Document errors
     pub async fn chunkify_response(
@@ -139,3 +181,51 @@ impl ChunkEndpoint {
         Ok(store)
     }
 }
+
+/// Stream of a chunked message, returned by [`ChunkEndpoint::get_unchunkified_stream`].
+/// The chunks are deleted from the store when the stream is dropped.
+pub struct ChunkedStream {
+    object: object_store::Object<'static>,
+    store: ObjectStore,
+    name: String,
+}
+
+impl ChunkedStream {
+    /// size of the chunked message in bytes
+    #[must_use]
+    pub fn len(&self) -> u64 {
+        self.object.info.size as u64
+    }
+
+    /// returns true if the chunked message is empty
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl AsyncRead for ChunkedStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut TaskContext<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.object).poll_read(cx, buf)
+    }
+}
+
+impl Drop for ChunkedStream {
+    fn drop(&mut self) {
+        let store = self.store.clone();
+        let name = std::mem::take(&mut self.name);
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            handle.spawn(async move {
+                if let Err(err) = store.delete(&name).await {
+                    error!(invocation_id = %name, %err, "failed to delete chunks");
+                }
+            });
+        } else {
+            error!(invocation_id = %name, "failed to delete chunks: no runtime");
+        }
+    }
+}
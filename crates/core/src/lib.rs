@@ -89,11 +89,29 @@ pub type HostEnvValues = WitMap<String>;
 
 /// Configuration values for Open Telemetry
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[non_exhaustive]
 pub struct OtelConfig {
     /// OTEL_TRACES_EXPORTER https://opentelemetry.io/docs/concepts/sdk-configuration/general-sdk-configuration/#otel_traces_exporter
     pub traces_exporter: Option<String>,
     /// OTEL_EXPORTER_OTLP_ENDPOINT https://opentelemetry.io/docs/concepts/sdk-configuration/otlp-exporter-configuration/#otel_exporter_otlp_endpoint
     pub exporter_otlp_endpoint: Option<String>,
+    /// OTEL_METRICS_EXPORTER https://opentelemetry.io/docs/concepts/sdk-configuration/general-sdk-configuration/#otel_metrics_exporter
+    #[serde(default)]
+    pub metrics_exporter: Option<String>,
+    /// OTEL_EXPORTER_PROMETHEUS_HOST https://opentelemetry.io/docs/specs/otel/configuration/sdk-environment-variables/#prometheus-exporter
+    #[serde(default)]
+    pub exporter_prometheus_host: Option<String>,
+    /// OTEL_EXPORTER_PROMETHEUS_PORT https://opentelemetry.io/docs/specs/otel/configuration/sdk-environment-variables/#prometheus-exporter
+    #[serde(default)]
+    pub exporter_prometheus_port: Option<u16>,
+    /// OTEL_LOGS_EXPORTER https://opentelemetry.io/docs/concepts/sdk-configuration/general-sdk-configuration/#otel_logs_exporter
+    ///
+    /// Logs are exported using the traces exporter if this is not set
+    #[serde(default)]
+    pub logs_exporter: Option<String>,
+    /// OTEL_RESOURCE_ATTRIBUTES https://opentelemetry.io/docs/concepts/sdk-configuration/general-sdk-configuration/#otel_resource_attributes
+    #[serde(default)]
+    pub resource_attributes: HashMap<String, String>,
 }
 
 pub fn invocation_hash(
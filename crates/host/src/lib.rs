@@ -19,11 +19,14 @@ pub mod registry;
 /// Provider archive functionality
 mod par;
 
+/// OTEL metrics recorded by the host
+mod metrics;
+
 pub use oci::{Config as OciConfig, Fetcher as OciFetcher};
 pub use policy::{
     Action as PolicyAction, HostInfo as PolicyHostInfo, Manager as PolicyManager,
-    RequestSource as PolicyRequestSource, RequestTarget as PolicyRequestTarget,
-    Response as PolicyResponse,
+    RequestCaller as PolicyRequestCaller, RequestSource as PolicyRequestSource,
+    RequestTarget as PolicyRequestTarget, Response as PolicyResponse,
 };
 pub use registry::{Auth as RegistryAuth, Config as RegistryConfig, Type as RegistryType};
 pub use wasmbus::{Host as WasmbusHost, HostConfig as WasmbusHostConfig};
@@ -0,0 +1,332 @@
+use core::time::Duration;
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use opentelemetry::metrics::{Counter, Histogram, Meter, Unit, UpDownCounter};
+use opentelemetry::{global, KeyValue};
+
+use crate::PolicyAction;
+
+/// Name of the meter host metrics are recorded with
+const METER_NAME: &str = "wasmcloud-host";
+
+/// Instruments recording host metrics with the global meter provider, which discards them unless
+/// metrics export is configured
+#[derive(Clone, Debug)]
+pub(crate) struct HostMetrics {
+    invocations: Counter<u64>,
+    invocation_errors: Counter<u64>,
+    invocation_duration: Histogram<f64>,
+    active_instances: UpDownCounter<i64>,
+    max_instances: UpDownCounter<i64>,
+    chunked_payloads: Counter<u64>,
+    provider_starts: Counter<u64>,
+    provider_exits: Counter<u64>,
+    provider_restarts: Counter<u64>,
+    policy_decision_duration: Histogram<f64>,
+    /// Providers, by public key and link name, whose process exited since they were last started
+    exited_providers: Arc<Mutex<HashSet<(String, String)>>>,
+}
+
+impl Default for HostMetrics {
+    fn default() -> Self {
+        Self::new(&global::meter(METER_NAME))
+    }
+}
+
+/// Guard recording an actor instance as handling an invocation until it is dropped, so that
+/// instances are no longer recorded as active if the invocation is cancelled
+#[must_use]
+pub(crate) struct ActiveInstance {
+    active_instances: UpDownCounter<i64>,
+    attributes: [KeyValue; 1],
+}
+
+impl Drop for ActiveInstance {
+    fn drop(&mut self) {
+        self.active_instances.add(-1, &self.attributes);
+    }
+}
+
+impl HostMetrics {
+    /// Returns instruments recording host metrics with `meter`
+    pub(crate) fn new(meter: &Meter) -> Self {
+        Self {
+            invocations: meter
+                .u64_counter("wasmcloud_host.actor.invocations")
+                .with_description("Number of invocations handled by actors")
+                .init(),
+            invocation_errors: meter
+                .u64_counter("wasmcloud_host.actor.invocation.errors")
+                .with_description("Number of invocations handled by actors, which failed")
+                .init(),
+            invocation_duration: meter
+                .f64_histogram("wasmcloud_host.actor.invocation.duration")
+                .with_description("Duration of invocations handled by actors")
+                .with_unit(Unit::new("s"))
+                .init(),
+            active_instances: meter
+                .i64_up_down_counter("wasmcloud_host.actor.instances.active")
+                .with_description("Number of actor instances currently handling an invocation")
+                .init(),
+            max_instances: meter
+                .i64_up_down_counter("wasmcloud_host.actor.instances.max")
+                .with_description("Maximum number of concurrently running actor instances")
+                .init(),
+            chunked_payloads: meter
+                .u64_counter("wasmcloud_host.chunked_payloads")
+                .with_description("Number of invocation payloads exchanged using the object store")
+                .init(),
+            provider_starts: meter
+                .u64_counter("wasmcloud_host.provider.starts")
+                .with_description("Number of times capability providers were started")
+                .init(),
+            provider_exits: meter
+                .u64_counter("wasmcloud_host.provider.exits")
+                .with_description("Number of times capability provider processes exited")
+                .init(),
+            provider_restarts: meter
+                .u64_counter("wasmcloud_host.provider.restarts")
+                .with_description(
+                    "Number of times capability providers were started again after their process exited",
+                )
+                .init(),
+            policy_decision_duration: meter
+                .f64_histogram("wasmcloud_host.policy.decision.duration")
+                .with_description("Duration of policy decisions")
+                .with_unit(Unit::new("s"))
+                .init(),
+            exited_providers: Arc::default(),
+        }
+    }
+
+    /// Records an invocation of `operation` on the actor with public key `actor_id`
+    pub(crate) fn record_invocation(
+        &self,
+        actor_id: &str,
+        operation: &str,
+        duration: Duration,
+        failed: bool,
+    ) {
+        let attributes = [
+            KeyValue::new("actor.id", actor_id.to_string()),
+            KeyValue::new("operation", operation.to_string()),
+        ];
+        self.invocations.add(1, &attributes);
+        if failed {
+            self.invocation_errors.add(1, &attributes);
+        }
+        self.invocation_duration
+            .record(duration.as_secs_f64(), &attributes);
+    }
+
+    /// Records an instance of the actor with public key `actor_id` handling an invocation until
+    /// the returned guard is dropped
+    pub(crate) fn active_instance(&self, actor_id: &str) -> ActiveInstance {
+        let attributes = [KeyValue::new("actor.id", actor_id.to_string())];
+        self.active_instances.add(1, &attributes);
+        ActiveInstance {
+            active_instances: self.active_instances.clone(),
+            attributes,
+        }
+    }
+
+    /// Records a change of the maximum number of instances of the actor with public key `actor_id`
+    pub(crate) fn record_max_instances(&self, actor_id: &str, delta: i64) {
+        self.max_instances
+            .add(delta, &[KeyValue::new("actor.id", actor_id.to_string())]);
+    }
+
+    /// Records an invocation `request` or `response` payload exchanged using the object store
+    pub(crate) fn record_chunked_payload(&self, direction: &'static str) {
+        self.chunked_payloads
+            .add(1, &[KeyValue::new("direction", direction)]);
+    }
+
+    /// Records the provider with public key `provider_id` starting, which is a restart if its
+    /// process exited since it was last started
+    pub(crate) fn record_provider_start(&self, provider_id: &str, link_name: &str) {
+        let attributes = [
+            KeyValue::new("provider.id", provider_id.to_string()),
+            KeyValue::new("link_name", link_name.to_string()),
+        ];
+        self.provider_starts.add(1, &attributes);
+        let restarted = self
+            .exited_providers
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .remove(&(provider_id.to_string(), link_name.to_string()));
+        if restarted {
+            self.provider_restarts.add(1, &attributes);
+        }
+    }
+
+    /// Records the process of the provider with public key `provider_id` exiting
+    pub(crate) fn record_provider_exit(&self, provider_id: &str, link_name: &str, success: bool) {
+        self.exited_providers
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert((provider_id.to_string(), link_name.to_string()));
+        self.provider_exits.add(
+            1,
+            &[
+                KeyValue::new("provider.id", provider_id.to_string()),
+                KeyValue::new("link_name", link_name.to_string()),
+                KeyValue::new("success", success),
+            ],
+        );
+    }
+
+    /// Records a policy decision for `action`
+    pub(crate) fn record_policy_decision(
+        &self,
+        action: &PolicyAction,
+        cached: bool,
+        permitted: bool,
+        duration: Duration,
+    ) {
+        let action = serde_json::to_value(action)
+            .ok()
+            .and_then(|action| action.as_str().map(ToString::to_string))
+            .unwrap_or_default();
+        self.policy_decision_duration.record(
+            duration.as_secs_f64(),
+            &[
+                KeyValue::new("action", action),
+                KeyValue::new("cached", cached),
+                KeyValue::new("permitted", permitted),
+            ],
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use opentelemetry::metrics::MeterProvider as _;
+    use opentelemetry_sdk::metrics::MeterProvider;
+
+    /// Returns metrics recorded into a Prometheus registry, and the provider, which must be kept
+    /// alive for the metrics to be collected
+    fn metrics() -> (HostMetrics, prometheus::Registry, MeterProvider) {
+        let registry = prometheus::Registry::new();
+        let exporter = opentelemetry_prometheus::exporter()
+            .with_registry(registry.clone())
+            .without_scope_info()
+            .without_target_info()
+            .build()
+            .expect("failed to create Prometheus exporter");
+        let provider = MeterProvider::builder().with_reader(exporter).build();
+        let metrics = HostMetrics::new(&provider.meter(METER_NAME));
+        (metrics, registry, provider)
+    }
+
+    /// Returns the values of the counter or gauge `name`, by its labels formatted as
+    /// `name=value` and separated by commas
+    fn values(registry: &prometheus::Registry, name: &str) -> Vec<(String, f64)> {
+        let mut values: Vec<_> = registry
+            .gather()
+            .iter()
+            .filter(|family| family.get_name() == name)
+            .flat_map(|family| family.get_metric())
+            .map(|metric| {
+                let value = if metric.has_counter() {
+                    metric.get_counter().get_value()
+                } else {
+                    metric.get_gauge().get_value()
+                };
+                let mut labels: Vec<_> = metric
+                    .get_label()
+                    .iter()
+                    .map(|pair| format!("{}={}", pair.get_name(), pair.get_value()))
+                    .collect();
+                labels.sort();
+                (labels.join(","), value)
+            })
+            .collect();
+        values.sort_by(|(a, _), (b, _)| a.cmp(b));
+        values
+    }
+
+    #[test]
+    fn provider_restarts() {
+        let (metrics, registry, _provider) = metrics();
+        metrics.record_provider_start("provider-a", "default");
+        metrics.record_provider_start("provider-b", "default");
+        metrics.record_provider_exit("provider-a", "default", false);
+        // Starting another link of the provider is not a restart
+        metrics.record_provider_start("provider-a", "other");
+        metrics.record_provider_start("provider-a", "default");
+        metrics.record_provider_start("provider-b", "default");
+
+        assert_eq!(
+            values(&registry, "wasmcloud_host_provider_starts_total"),
+            [
+                ("link_name=default,provider_id=provider-a".into(), 2.0),
+                ("link_name=default,provider_id=provider-b".into(), 2.0),
+                ("link_name=other,provider_id=provider-a".into(), 1.0),
+            ]
+        );
+        assert_eq!(
+            values(&registry, "wasmcloud_host_provider_restarts_total"),
+            [("link_name=default,provider_id=provider-a".into(), 1.0)]
+        );
+        assert_eq!(
+            values(&registry, "wasmcloud_host_provider_exits_total"),
+            [(
+                "link_name=default,provider_id=provider-a,success=false".into(),
+                1.0
+            )]
+        );
+    }
+
+    #[test]
+    fn active_instances() {
+        let (metrics, registry, _provider) = metrics();
+        let first = metrics.active_instance("actor");
+        let second = metrics.active_instance("actor");
+        assert_eq!(
+            values(&registry, "wasmcloud_host_actor_instances_active"),
+            [("actor_id=actor".into(), 2.0)]
+        );
+        drop(first);
+        // Instances are no longer active once the invocation future is dropped, even if cancelled
+        let invocation = async move {
+            let _second = second;
+            futures::future::pending::<()>().await;
+        };
+        drop(invocation);
+        assert_eq!(
+            values(&registry, "wasmcloud_host_actor_instances_active"),
+            [("actor_id=actor".into(), 0.0)]
+        );
+    }
+
+    #[test]
+    fn invocations() {
+        let (metrics, registry, _provider) = metrics();
+        metrics.record_invocation("actor", "HttpServer.HandleRequest", Duration::ZERO, false);
+        metrics.record_invocation("actor", "HttpServer.HandleRequest", Duration::ZERO, true);
+        metrics.record_chunked_payload("request");
+        assert_eq!(
+            values(&registry, "wasmcloud_host_actor_invocations_total"),
+            [(
+                "actor_id=actor,operation=HttpServer.HandleRequest".into(),
+                2.0
+            )]
+        );
+        assert_eq!(
+            values(&registry, "wasmcloud_host_actor_invocation_errors_total"),
+            [(
+                "actor_id=actor,operation=HttpServer.HandleRequest".into(),
+                1.0
+            )]
+        );
+        assert_eq!(
+            values(&registry, "wasmcloud_host_chunked_payloads_total"),
+            [("direction=request".into(), 1.0)]
+        );
+    }
+}
@@ -2,22 +2,28 @@ use core::time::Duration;
 
 use std::collections::{hash_map, HashMap};
 use std::hash::Hash;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use anyhow::Context;
+use anyhow::{bail, Context};
+use cloudevents::{EventBuilder, EventBuilderV10};
 use futures::{
     stream::{AbortHandle, Abortable},
     StreamExt,
 };
 use serde::{Deserialize, Serialize, Serializer};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
 use tokio::spawn;
 use tokio::sync::RwLock;
-use tracing::{debug, error, instrument, trace, warn};
+use tokio::time::Instant;
+use tracing::{debug, error, instrument, trace, warn, Instrument};
 use ulid::Ulid;
 use uuid::Uuid;
 use wascap::jwt;
 
+use crate::metrics::HostMetrics;
+
 /// Relevant information about the actor or provider making an invocation. This struct is empty for
 /// policy decisions related to starting actors or providers. All fields are optional for backwards-compatibility
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Hash)]
@@ -62,6 +68,18 @@ pub struct RequestTarget {
     /// The link name of the provider, or None if the target is an actor
     #[serde(rename = "linkName")]
     pub link_name: Option<String>,
+    /// The config or label key being changed, or None if the action does not change a key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+}
+
+/// Relevant information about the control interface client requesting a lattice mutation
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Hash)]
+pub struct RequestCaller {
+    /// The public key the caller signed its request with. The signature is verified by the host
+    /// before a policy decision is requested
+    #[serde(rename = "publicKey")]
+    pub public_key: String,
 }
 
 /// Relevant information about the host that is receiving the invocation, or starting the actor or provider
@@ -81,7 +99,7 @@ pub struct HostInfo {
 }
 
 /// The action being requested
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Hash)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Hash)]
 pub enum Action {
     /// The host is checking whether it may invoke the target actor
     #[serde(rename = "perform_invocation")]
@@ -92,6 +110,27 @@ pub enum Action {
     /// The host is checking whether it may start the target provider
     #[serde(rename = "start_provider")]
     StartProvider,
+    /// The host is checking whether it may put a link definition for the target actor
+    #[serde(rename = "put_link_definition")]
+    PutLinkDefinition,
+    /// The host is checking whether it may delete a link definition of the target actor
+    #[serde(rename = "delete_link_definition")]
+    DeleteLinkDefinition,
+    /// The host is checking whether it may put a config key of the target actor or provider
+    #[serde(rename = "put_config")]
+    PutConfig,
+    /// The host is checking whether it may delete a config key of the target actor or provider
+    #[serde(rename = "delete_config")]
+    DeleteConfig,
+    /// The host is checking whether it may put a label on itself
+    #[serde(rename = "put_label")]
+    PutLabel,
+    /// The host is checking whether it may delete one of its labels
+    #[serde(rename = "delete_label")]
+    DeleteLabel,
+    /// The host is checking whether it may stop itself
+    #[serde(rename = "stop_host")]
+    StopHost,
 }
 
 /// A request for a policy decision
@@ -103,6 +142,9 @@ struct Request {
     // Use a custom serializer to handle the case where the source is None
     #[serde(serialize_with = "serialize_source")]
     source: Option<RequestSource>,
+    /// The control interface caller, only set for actions requested through the control interface
+    #[serde(skip_serializing_if = "Option::is_none")]
+    caller: Option<RequestCaller>,
     target: RequestTarget,
     host: HostInfo,
     action: Action,
@@ -111,6 +153,7 @@ struct Request {
 #[derive(Clone, Debug, Hash, Eq, PartialEq)]
 struct RequestKey {
     source: RequestSource,
+    caller: Option<RequestCaller>,
     target: RequestTarget,
     action: Action,
 }
@@ -127,6 +170,26 @@ pub struct Response {
     pub message: Option<String>,
 }
 
+/// A record of a single policy decision, published as the data of an audit event
+#[derive(Serialize)]
+struct Decision<'a> {
+    #[serde(rename = "requestId")]
+    request_id: &'a str,
+    source: &'a RequestSource,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    caller: Option<&'a RequestCaller>,
+    target: &'a RequestTarget,
+    host: &'a HostInfo,
+    action: &'a Action,
+    /// Whether the decision was served from the decision cache
+    cached: bool,
+    permitted: bool,
+    message: Option<&'a str>,
+    /// The time it took to reach the decision, in microseconds
+    #[serde(rename = "latencyUs")]
+    latency_us: u64,
+}
+
 /// Policy services expect a source on all requests, even though no data is relevant for the start
 /// actions. When source is None, we still serialize an (empty) object
 fn serialize_source<S>(source: &Option<RequestSource>, serializer: S) -> Result<S::Ok, S::Error>
@@ -184,6 +247,7 @@ impl From<jwt::Claims<jwt::Actor>> for RequestTarget {
             issuer: Some(claims.issuer),
             contract_id: None,
             link_name: None,
+            key: None,
         }
     }
 }
@@ -195,6 +259,201 @@ impl From<jwt::Claims<jwt::CapabilityProvider>> for RequestTarget {
             issuer: Some(claims.issuer),
             contract_id: claims.metadata.map(|m| m.capid),
             link_name: None, // Unfortunately, since claims don't include a link name, we can't populate this
+            key: None,
+        }
+    }
+}
+
+/// Whether a rule permits or denies the requests it matches
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Effect {
+    /// Permit matching requests
+    #[default]
+    Permit,
+    /// Deny matching requests
+    Deny,
+}
+
+/// Matches on the fields of a [`RequestSource`]. Empty fields match any value, and all non-empty
+/// fields must match for the matcher to match
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SourceMatcher {
+    /// The public keys of the actor or provider
+    pub public_key: Vec<String>,
+    /// The issuers of the source's claims
+    pub issuer: Vec<String>,
+    /// The contract IDs of the provider
+    pub contract_id: Vec<String>,
+    /// The link names of the provider
+    pub link_name: Vec<String>,
+    /// Capabilities of the actor. Matches if the actor claims any of the listed capabilities
+    pub capabilities: Vec<String>,
+    /// Whether the source's claims have expired
+    pub expired: Option<bool>,
+}
+
+/// Matches on the fields of a [`RequestTarget`]. Empty fields match any value, and all non-empty
+/// fields must match for the matcher to match
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TargetMatcher {
+    /// The public keys of the actor or provider
+    pub public_key: Vec<String>,
+    /// The issuers of the target's claims
+    pub issuer: Vec<String>,
+    /// The contract IDs of the provider
+    pub contract_id: Vec<String>,
+    /// The link names of the provider
+    pub link_name: Vec<String>,
+    /// The config or label keys being changed
+    pub key: Vec<String>,
+}
+
+/// Matches on a [`RequestCaller`]. Empty fields match any value, and all non-empty fields must
+/// match for the matcher to match
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CallerMatcher {
+    /// The public keys of the control interface caller. Never matches requests without a caller
+    pub public_key: Vec<String>,
+}
+
+/// A single rule of a [`RuleSet`]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rule {
+    /// Whether requests matching this rule are permitted or denied
+    pub effect: Effect,
+    /// The actions this rule applies to. Applies to all actions if empty
+    #[serde(default)]
+    pub actions: Vec<Action>,
+    /// Matches on the source of the request
+    #[serde(default)]
+    pub source: SourceMatcher,
+    /// Matches on the target of the request
+    #[serde(default)]
+    pub target: TargetMatcher,
+    /// Matches on the control interface caller of the request
+    #[serde(default)]
+    pub caller: CallerMatcher,
+    /// Labels the host must have (with the given values) for this rule to apply
+    #[serde(default)]
+    pub host_labels: HashMap<String, String>,
+    /// An optional message returned with the decision. Suitable for logging
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// A declarative policy evaluated in-process by the host, as an alternative to requesting
+/// decisions from an external policy service. Rules are evaluated in order and the first matching
+/// rule decides the request. If no rule matches, the default effect applies
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleSet {
+    /// The effect applied to requests that no rule matches, defaults to permit
+    #[serde(default)]
+    pub default_effect: Effect,
+    /// The ordered list of rules
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+}
+
+/// Returns true if `allowed` is empty or `value` is one of its elements
+fn matches_any(allowed: &[String], value: Option<&str>) -> bool {
+    allowed.is_empty() || value.is_some_and(|value| allowed.iter().any(|a| a == value))
+}
+
+impl SourceMatcher {
+    fn matches(&self, source: &RequestSource) -> bool {
+        matches_any(&self.public_key, source.public_key.as_deref())
+            && matches_any(&self.issuer, source.issuer.as_deref())
+            && matches_any(&self.contract_id, source.contract_id.as_deref())
+            && matches_any(&self.link_name, source.link_name.as_deref())
+            && (self.capabilities.is_empty()
+                || source
+                    .capabilities
+                    .iter()
+                    .any(|cap| self.capabilities.contains(cap)))
+            && self
+                .expired
+                .map_or(true, |expired| expired == source.expired)
+    }
+}
+
+impl TargetMatcher {
+    fn matches(&self, target: &RequestTarget) -> bool {
+        matches_any(&self.public_key, target.public_key.as_deref())
+            && matches_any(&self.issuer, target.issuer.as_deref())
+            && matches_any(&self.contract_id, target.contract_id.as_deref())
+            && matches_any(&self.link_name, target.link_name.as_deref())
+            && matches_any(&self.key, target.key.as_deref())
+    }
+}
+
+impl CallerMatcher {
+    fn matches(&self, caller: Option<&RequestCaller>) -> bool {
+        matches_any(
+            &self.public_key,
+            caller.map(|caller| caller.public_key.as_str()),
+        )
+    }
+}
+
+impl Rule {
+    fn matches(
+        &self,
+        source: &RequestSource,
+        caller: Option<&RequestCaller>,
+        target: &RequestTarget,
+        host: &HostInfo,
+        action: &Action,
+    ) -> bool {
+        (self.actions.is_empty() || self.actions.contains(action))
+            && self.source.matches(source)
+            && self.caller.matches(caller)
+            && self.target.matches(target)
+            && self
+                .host_labels
+                .iter()
+                .all(|(k, v)| host.labels.get(k) == Some(v))
+    }
+}
+
+impl RuleSet {
+    /// Load a rule set from a JSON rules file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not a valid rule set
+    pub async fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let buf = tokio::fs::read(path)
+            .await
+            .with_context(|| format!("failed to read policy rules file `{}`", path.display()))?;
+        serde_json::from_slice(&buf)
+            .with_context(|| format!("failed to parse policy rules file `{}`", path.display()))
+    }
+
+    /// Evaluate a request against the policy, returning whether it is permitted and the message of
+    /// the matching rule, if any
+    #[must_use]
+    pub fn evaluate(
+        &self,
+        source: &RequestSource,
+        caller: Option<&RequestCaller>,
+        target: &RequestTarget,
+        host: &HostInfo,
+        action: &Action,
+    ) -> (bool, Option<String>) {
+        match self
+            .rules
+            .iter()
+            .find(|rule| rule.matches(source, caller, target, host, action))
+        {
+            Some(rule) => (rule.effect == Effect::Permit, rule.message.clone()),
+            None => (self.default_effect == Effect::Permit, None),
         }
     }
 }
@@ -203,36 +462,51 @@ impl From<jwt::Claims<jwt::CapabilityProvider>> for RequestTarget {
 #[derive(Debug)]
 pub struct Manager {
     nats: async_nats::Client,
-    host_info: HostInfo,
+    host_info: RwLock<HostInfo>,
     policy_topic: Option<String>,
     policy_timeout: Duration,
+    rule_set: Option<RwLock<RuleSet>>,
     decision_cache: Arc<RwLock<HashMap<RequestKey, Response>>>,
     request_to_key: Arc<RwLock<HashMap<String, RequestKey>>>,
+    audit_subject: Option<String>,
+    metrics: HostMetrics,
     /// An abort handle for the policy changes subscription
     pub policy_changes: AbortHandle,
 }
 
 impl Manager {
-    /// Construct a new policy manager. Can fail if policy_changes_topic is set but we fail to subscribe to it
-    #[instrument(skip(nats))]
+    /// Construct a new policy manager. Decisions are requested on `policy_topic` or evaluated
+    /// in-process against `rule_set`, at most one of which may be set. If `audit_subject` is set,
+    /// every decision is published on it as a `com.wasmcloud.policy.decision` cloud event. Can
+    /// fail if policy_changes_topic is set but we fail to subscribe to it
+    #[instrument(skip(nats, rule_set))]
     pub async fn new(
         nats: async_nats::Client,
         host_info: HostInfo,
         policy_topic: Option<String>,
         policy_timeout: Option<Duration>,
         policy_changes_topic: Option<String>,
+        rule_set: Option<RuleSet>,
+        audit_subject: Option<String>,
     ) -> anyhow::Result<Arc<Self>> {
         const DEFAULT_POLICY_TIMEOUT: Duration = Duration::from_secs(1);
 
+        if policy_topic.is_some() && rule_set.is_some() {
+            bail!("a policy topic and a policy rule set cannot be configured at the same time");
+        }
+
         let (policy_changes_abort, policy_changes_abort_reg) = AbortHandle::new_pair();
 
         let manager = Manager {
             nats: nats.clone(),
-            host_info,
+            host_info: RwLock::new(host_info),
             policy_topic,
             policy_timeout: policy_timeout.unwrap_or(DEFAULT_POLICY_TIMEOUT),
+            rule_set: rule_set.map(RwLock::new),
             decision_cache: Arc::default(),
             request_to_key: Arc::default(),
+            audit_subject,
+            metrics: HostMetrics::default(),
             policy_changes: policy_changes_abort,
         };
         let manager = Arc::new(manager);
@@ -267,17 +541,70 @@ impl Manager {
         target: RequestTarget,
         action: Action,
     ) -> anyhow::Result<Response> {
+        self.evaluate(source, None, target, action).await
+    }
+
+    /// Requests a policy decision for an action requested through the control interface by the
+    /// given caller
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the policy service could not be reached or returned an invalid response
+    #[instrument(level = "trace", skip_all)]
+    pub async fn evaluate_ctl_action(
+        &self,
+        caller: Option<RequestCaller>,
+        target: RequestTarget,
+        action: Action,
+    ) -> anyhow::Result<Response> {
+        self.evaluate(None, caller, target, action).await
+    }
+
+    async fn evaluate(
+        &self,
+        source: Option<RequestSource>,
+        caller: Option<RequestCaller>,
+        target: RequestTarget,
+        action: Action,
+    ) -> anyhow::Result<Response> {
+        let start = Instant::now();
         let cache_key = RequestKey {
             source: source.clone().unwrap_or_default(),
+            caller: caller.clone(),
             target: target.clone(),
             action: action.clone(),
         };
+        let (decision, cached) = self
+            .decide(cache_key.clone(), source, caller, target, action)
+            .await?;
+        self.metrics.record_policy_decision(
+            &cache_key.action,
+            cached,
+            decision.permitted,
+            start.elapsed(),
+        );
+        if let Some(subject) = &self.audit_subject {
+            self.publish_decision(subject, &cache_key, &decision, cached, start.elapsed())
+                .await;
+        }
+        Ok(decision)
+    }
+
+    /// Returns the decision for a request, and whether it was served from the decision cache
+    async fn decide(
+        &self,
+        cache_key: RequestKey,
+        source: Option<RequestSource>,
+        caller: Option<RequestCaller>,
+        target: RequestTarget,
+        action: Action,
+    ) -> anyhow::Result<(Response, bool)> {
         let mut decision_cache = self.decision_cache.write().await;
         match decision_cache.entry(cache_key.clone()) {
             hash_map::Entry::Occupied(entry) => {
                 let entry = entry.get();
                 trace!(?cache_key, ?entry, "using cached policy decision");
-                Ok(entry.clone())
+                Ok((entry.clone(), true))
             }
             hash_map::Entry::Vacant(entry) => {
                 let request_id = Uuid::from_u128(Ulid::new().into()).to_string();
@@ -286,8 +613,9 @@ impl Manager {
                     let payload = serde_json::to_vec(&Request {
                         request_id: request_id.clone(),
                         source,
+                        caller,
                         target,
-                        host: self.host_info.clone(),
+                        host: self.host_info.read().await.clone(),
                         action,
                     })
                     .context("failed to serialize policy request")?;
@@ -301,6 +629,20 @@ impl Manager {
                         .context("policy request failed")?;
                     serde_json::from_slice::<Response>(&res.payload)
                         .context("failed to deserialize policy response")?
+                } else if let Some(rule_set) = &self.rule_set {
+                    trace!(?cache_key, "evaluating policy rule set");
+                    let (permitted, message) = rule_set.read().await.evaluate(
+                        &cache_key.source,
+                        cache_key.caller.as_ref(),
+                        &target,
+                        &*self.host_info.read().await,
+                        &action,
+                    );
+                    Response {
+                        request_id: request_id.clone(),
+                        permitted,
+                        message,
+                    }
                 } else {
                     trace!(
                         ?cache_key,
@@ -316,11 +658,106 @@ impl Manager {
                 entry.insert(decision.clone()); // cache policy decision
                 let mut request_to_key = self.request_to_key.write().await;
                 request_to_key.insert(request_id, cache_key); // cache request id -> decision key
-                Ok(decision)
+                Ok((decision, false))
             }
         }
     }
 
+    /// Publish an audit event for a decision in the background, so that evaluations don't wait
+    /// on the NATS client
+    async fn publish_decision(
+        &self,
+        subject: &str,
+        key: &RequestKey,
+        decision: &Response,
+        cached: bool,
+        latency: Duration,
+    ) {
+        let ev = match self.decision_event(key, decision, cached, latency).await {
+            Ok(ev) => ev,
+            Err(e) => {
+                warn!(?key, "failed to build policy decision audit event: {e:#}");
+                return;
+            }
+        };
+        let nats = self.nats.clone();
+        let subject = subject.to_string();
+        spawn(
+            async move {
+                if let Err(e) = nats.publish(subject, ev.into()).await {
+                    warn!("failed to publish policy decision audit event: {e}");
+                }
+            }
+            .in_current_span(),
+        );
+    }
+
+    /// Returns the serialized audit event for a decision
+    async fn decision_event(
+        &self,
+        key: &RequestKey,
+        decision: &Response,
+        cached: bool,
+        latency: Duration,
+    ) -> anyhow::Result<Vec<u8>> {
+        let host_info = self.host_info.read().await;
+        let data = serde_json::to_value(Decision {
+            request_id: &decision.request_id,
+            source: &key.source,
+            caller: key.caller.as_ref(),
+            target: &key.target,
+            host: &host_info,
+            action: &key.action,
+            cached,
+            permitted: decision.permitted,
+            message: decision.message.as_deref(),
+            latency_us: latency.as_micros().try_into().unwrap_or(u64::MAX),
+        })
+        .context("failed to serialize policy decision")?;
+        let now = OffsetDateTime::now_utc()
+            .format(&Rfc3339)
+            .context("failed to format current time")?;
+        let ev = EventBuilderV10::new()
+            .source(host_info.public_key.clone())
+            .ty("com.wasmcloud.policy.decision")
+            .id(Uuid::from_u128(Ulid::new().into()).to_string())
+            .time(now)
+            .data("application/json", data)
+            .build()
+            .context("failed to build cloud event")?;
+        serde_json::to_vec(&ev).context("failed to serialize event")
+    }
+
+    /// Replace the local rule set, invalidating all cached decisions
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the manager was not constructed with a rule set
+    #[instrument(level = "debug", skip_all)]
+    pub async fn update_rule_set(&self, rule_set: RuleSet) -> anyhow::Result<()> {
+        let current = self
+            .rule_set
+            .as_ref()
+            .context("policy manager is not configured with a rule set")?;
+        let mut decision_cache = self.decision_cache.write().await;
+        let mut request_to_key = self.request_to_key.write().await;
+        *current.write().await = rule_set;
+        decision_cache.clear();
+        request_to_key.clear();
+        Ok(())
+    }
+
+    /// Replace the labels of the host included in policy requests, invalidating all cached
+    /// decisions, since they may depend on the labels
+    #[instrument(level = "debug", skip_all)]
+    pub async fn update_labels(&self, labels: HashMap<String, String>) {
+        let mut decision_cache = self.decision_cache.write().await;
+        let mut request_to_key = self.request_to_key.write().await;
+        self.host_info.write().await.labels = labels;
+        decision_cache.clear();
+        request_to_key.clear();
+    }
+
     #[instrument(skip(self))]
     async fn override_decision(&self, msg: async_nats::Message) -> anyhow::Result<()> {
         let Response {
@@ -354,3 +791,154 @@ impl Manager {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::{Action, HostInfo, RequestCaller, RequestSource, RequestTarget, RuleSet};
+
+    const TRUSTED_ISSUER: &str = "ACOJJN6WUP4ODD75XEBKKTCCUJJCY5ZKQ56XVKYK4BEJWGVAOOQHZMCW";
+
+    fn host_info(labels: &[(&str, &str)]) -> HostInfo {
+        HostInfo {
+            public_key: "NAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA".to_string(),
+            lattice_id: "default".to_string(),
+            labels: labels
+                .iter()
+                .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+                .collect::<HashMap<_, _>>(),
+            cluster_issuers: vec![],
+        }
+    }
+
+    #[test]
+    fn rule_set_evaluates_rules_in_order() {
+        let policy: RuleSet = serde_json::from_value(serde_json::json!({
+            "defaultEffect": "deny",
+            "rules": [
+                {
+                    "effect": "deny",
+                    "actions": ["perform_invocation"],
+                    "source": { "capabilities": ["wasmcloud:httpserver"] },
+                    "hostLabels": { "zone": "edge" },
+                    "message": "no http on the edge"
+                },
+                {
+                    "effect": "permit",
+                    "target": { "issuer": [TRUSTED_ISSUER] }
+                }
+            ]
+        }))
+        .expect("failed to parse policy");
+
+        let trusted = RequestTarget {
+            issuer: Some(TRUSTED_ISSUER.to_string()),
+            ..Default::default()
+        };
+        let http_actor = RequestSource {
+            capabilities: vec!["wasmcloud:httpserver".to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            policy.evaluate(
+                &RequestSource::default(),
+                None,
+                &trusted,
+                &host_info(&[]),
+                &Action::StartActor
+            ),
+            (true, None)
+        );
+        assert_eq!(
+            policy.evaluate(
+                &RequestSource::default(),
+                None,
+                &RequestTarget::default(),
+                &host_info(&[]),
+                &Action::StartActor
+            ),
+            (false, None)
+        );
+        assert_eq!(
+            policy.evaluate(
+                &http_actor,
+                None,
+                &trusted,
+                &host_info(&[("zone", "edge")]),
+                &Action::PerformInvocation
+            ),
+            (false, Some("no http on the edge".to_string()))
+        );
+        assert_eq!(
+            policy.evaluate(
+                &http_actor,
+                None,
+                &trusted,
+                &host_info(&[("zone", "cloud")]),
+                &Action::PerformInvocation
+            ),
+            (true, None)
+        );
+    }
+
+    #[test]
+    fn rule_set_matches_ctl_callers() {
+        let policy: RuleSet = serde_json::from_value(serde_json::json!({
+            "defaultEffect": "deny",
+            "rules": [
+                {
+                    "effect": "deny",
+                    "actions": ["put_label"],
+                    "target": { "key": ["zone"] }
+                },
+                {
+                    "effect": "permit",
+                    "actions": ["put_label", "put_link_definition", "stop_host"],
+                    "caller": { "publicKey": ["UADMIN"] }
+                }
+            ]
+        }))
+        .expect("failed to parse policy");
+
+        let admin = RequestCaller {
+            public_key: "UADMIN".to_string(),
+        };
+        let label = |key: &str| RequestTarget {
+            key: Some(key.to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            policy.evaluate(
+                &RequestSource::default(),
+                Some(&admin),
+                &label("team"),
+                &host_info(&[]),
+                &Action::PutLabel
+            ),
+            (true, None)
+        );
+        assert_eq!(
+            policy.evaluate(
+                &RequestSource::default(),
+                Some(&admin),
+                &label("zone"),
+                &host_info(&[]),
+                &Action::PutLabel
+            ),
+            (false, None)
+        );
+        assert_eq!(
+            policy.evaluate(
+                &RequestSource::default(),
+                None,
+                &RequestTarget::default(),
+                &host_info(&[]),
+                &Action::StopHost
+            ),
+            (false, None)
+        );
+    }
+}
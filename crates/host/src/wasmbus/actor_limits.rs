@@ -0,0 +1,120 @@
+use core::num::NonZeroUsize;
+use core::str::FromStr;
+use core::time::Duration;
+
+use std::collections::BTreeMap;
+
+use anyhow::Context as _;
+
+use super::config::ActorLimits;
+
+const MAX_MEMORY_SIZE_KEY: &str = "wasmcloud.max_memory_size";
+const MAX_TABLE_ELEMENTS_KEY: &str = "wasmcloud.max_table_elements";
+const MAX_CORE_INSTANCES_KEY: &str = "wasmcloud.max_core_instances";
+const MAX_EXECUTION_TIME_KEY: &str = "wasmcloud.max_execution_time_ms";
+
+impl ActorLimits {
+    /// Returns the limits with settings overridden by actor `annotations`. Annotations may only
+    /// lower the limits of the host
+    pub(crate) fn with_annotations(
+        &self,
+        annotations: &BTreeMap<String, String>,
+    ) -> anyhow::Result<Self> {
+        fn parse<T: FromStr>(key: &str, value: &str) -> anyhow::Result<T>
+        where
+            T::Err: std::error::Error + Send + Sync + 'static,
+        {
+            value
+                .parse()
+                .with_context(|| format!("invalid actor annotation `{key}`: `{value}`"))
+        }
+
+        fn lower<T: Ord>(limit: Option<T>, value: T) -> T {
+            match limit {
+                Some(limit) => limit.min(value),
+                None => value,
+            }
+        }
+
+        let mut limits = self.clone();
+        for (key, value) in annotations {
+            match key.as_str() {
+                MAX_MEMORY_SIZE_KEY => {
+                    limits.max_memory_size = Some(lower(self.max_memory_size, parse(key, value)?));
+                }
+                MAX_TABLE_ELEMENTS_KEY => {
+                    limits.max_table_elements =
+                        Some(lower(self.max_table_elements, parse(key, value)?));
+                }
+                MAX_CORE_INSTANCES_KEY => {
+                    limits.max_core_instances =
+                        Some(lower(self.max_core_instances, parse(key, value)?));
+                }
+                MAX_EXECUTION_TIME_KEY => {
+                    limits.max_execution_time = Some(lower(
+                        self.max_execution_time,
+                        Duration::from_millis(parse(key, value)?),
+                    ));
+                }
+                _ => {}
+            }
+        }
+        Ok(limits)
+    }
+
+    /// Returns the configuration of actor instances enforcing these limits
+    pub(crate) fn actor_config(
+        &self,
+        instance_pool: bool,
+        instance_pool_size: Option<NonZeroUsize>,
+    ) -> wasmcloud_runtime::ActorConfig {
+        wasmcloud_runtime::ActorConfig {
+            require_signature: true,
+            max_memory_size: self.max_memory_size,
+            max_table_elements: self.max_table_elements,
+            max_instances: self.max_core_instances,
+            max_execution_time: self.max_execution_time,
+            instance_pool,
+            instance_pool_size,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn annotations() -> anyhow::Result<()> {
+        let host = ActorLimits {
+            max_memory_size: Some(1 << 20),
+            max_execution_time: Some(Duration::from_secs(1)),
+            ..Default::default()
+        };
+        let limits = host.with_annotations(&BTreeMap::from([
+            (MAX_MEMORY_SIZE_KEY.into(), "65536".into()),
+            (MAX_CORE_INSTANCES_KEY.into(), "4".into()),
+            (MAX_EXECUTION_TIME_KEY.into(), "5000".into()),
+            ("unrelated".into(), "value".into()),
+        ]))?;
+        assert_eq!(limits.max_memory_size, Some(1 << 16));
+        assert_eq!(limits.max_table_elements, None);
+        assert_eq!(limits.max_core_instances, Some(4));
+        // The limits of the host cannot be raised
+        assert_eq!(limits.max_execution_time, Some(Duration::from_secs(1)));
+
+        let config = limits.actor_config(true, NonZeroUsize::new(8));
+        assert!(config.require_signature);
+        assert!(config.instance_pool);
+        assert_eq!(config.instance_pool_size, NonZeroUsize::new(8));
+        assert_eq!(config.max_instances, Some(4));
+
+        assert!(host
+            .with_annotations(&BTreeMap::from([(
+                MAX_TABLE_ELEMENTS_KEY.into(),
+                "many".into()
+            )]))
+            .is_err());
+        Ok(())
+    }
+}
@@ -1,6 +1,9 @@
 use crate::OciConfig;
 
+use core::num::{NonZeroU32, NonZeroUsize};
+
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -50,6 +53,11 @@ pub struct Host {
     pub oci_opts: OciConfig,
     /// Whether to allow loading actor or provider components from the filesystem
     pub allow_file_load: bool,
+    /// Directory to persist compiled actors in, so that they do not need to be compiled again
+    /// after a host restart. Compiled actors are not persisted if unset
+    pub compilation_cache_dir: Option<PathBuf>,
+    /// Maximum total size of the compiled actors persisted in `compilation_cache_dir`, in bytes
+    pub compilation_cache_max_size: Option<u64>,
     /// Whether or not structured logging is enabled
     pub enable_structured_logging: bool,
     /// Log level to pass to capability providers to use. Should be parsed from a [`tracing::Level`]
@@ -60,6 +68,91 @@ pub struct Host {
     pub otel_config: OtelConfig,
     /// configuration for wasmCloud policy service
     pub policy_service_config: PolicyService,
+    /// Resource limits enforced for every actor instance, which can be set or lowered per actor
+    pub actor_limits: ActorLimits,
+    /// Whether to keep a pool of pre-instantiated instances of each actor, which is filled lazily
+    /// as the actor is invoked. Pooled instances handle a single invocation each, so no state is
+    /// retained between invocations
+    pub actor_instance_pool: bool,
+    /// Maximum number of idle instances kept in the pool of each actor. Pools hold up to the
+    /// maximum number of instances of the actor if not set
+    pub actor_instance_pool_size: Option<NonZeroUsize>,
+    /// Timeout, retry and circuit breaker policy for invocations of providers, which can be
+    /// overridden per link using link definition values
+    pub link_policy: LinkPolicy,
+    /// Whether to snapshot the actors and providers running on the host into the lattice data
+    /// bucket whenever they change
+    pub snapshot_state: bool,
+    /// Whether to restore the actors and providers recorded in the snapshot of a previous run of
+    /// the host on start. The snapshot is identified by the host public key, so the host key must
+    /// be stable across restarts
+    pub restore_state: bool,
+}
+
+/// Resource limits enforced for every actor instance.
+///
+/// Limits can be set or lowered per actor using the following actor annotations:
+/// - `wasmcloud.max_memory_size`
+/// - `wasmcloud.max_table_elements`
+/// - `wasmcloud.max_core_instances`
+/// - `wasmcloud.max_execution_time_ms`
+#[derive(Clone, Debug, Default)]
+pub struct ActorLimits {
+    /// Number of concurrently running actor instances to preallocate resources for using the
+    /// pooling allocator. Resources are allocated on demand if unset
+    pub pooled_instances: Option<u32>,
+    /// Maximum size of the linear memory of an actor instance, in bytes
+    pub max_memory_size: Option<usize>,
+    /// Maximum number of elements in a table of an actor instance
+    pub max_table_elements: Option<u32>,
+    /// Maximum number of core WebAssembly instances an actor instance may create
+    pub max_core_instances: Option<usize>,
+    /// Maximum time a single actor invocation may execute for
+    pub max_execution_time: Option<Duration>,
+}
+
+/// Timeout, retry and circuit breaker policy for invocations of providers over a link.
+///
+/// Every setting can be overridden per link using the following link definition values:
+/// - `wasmcloud.rpc_timeout_ms`
+/// - `wasmcloud.max_retries`
+/// - `wasmcloud.retry_backoff_ms`
+/// - `wasmcloud.circuit_breaker_threshold`, where `0` disables the circuit breaker
+/// - `wasmcloud.circuit_breaker_reset_ms`
+/// - `wasmcloud.idempotent_operations`, a comma-separated list of operations
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LinkPolicy {
+    /// Timeout of invocations, defaults to the RPC timeout of the host
+    pub timeout: Option<Duration>,
+    /// Maximum number of times an invocation of one of the `idempotent_operations`, which failed
+    /// to reach the provider, is retried
+    pub max_retries: u32,
+    /// Operations which are idempotent and may therefore be retried, either including the
+    /// contract, like `wasmcloud:keyvalue/KeyValue.Get`, or not, like `KeyValue.Get`. Invocations of
+    /// other operations are never retried
+    pub idempotent_operations: Vec<String>,
+    /// Delay before the first retry, which doubles with every subsequent retry
+    pub retry_backoff: Duration,
+    /// Number of consecutive invocations failing to reach the provider, after which the circuit
+    /// breaker opens and invocations of the provider over the link fail immediately. Circuit
+    /// breaking is disabled if unset
+    pub circuit_breaker_threshold: Option<NonZeroU32>,
+    /// Time after which an open circuit breaker lets a trial invocation through, which closes the
+    /// circuit breaker if it succeeds
+    pub circuit_breaker_reset: Duration,
+}
+
+impl Default for LinkPolicy {
+    fn default() -> Self {
+        Self {
+            timeout: None,
+            max_retries: 0,
+            idempotent_operations: Vec::default(),
+            retry_backoff: Duration::from_millis(100),
+            circuit_breaker_threshold: None,
+            circuit_breaker_reset: Duration::from_secs(30),
+        }
+    }
 }
 
 /// Configuration for wasmCloud policy service
@@ -71,6 +164,21 @@ pub struct PolicyService {
     pub policy_changes_topic: Option<String>,
     /// The timeout for policy requests
     pub policy_timeout_ms: Option<Duration>,
+    /// A JSON rules file to evaluate policy decisions in-process, instead of requesting them on
+    /// `policy_topic`. The rules are reloaded from the file by
+    /// [`Host::reload_policy_rules`](super::Host::reload_policy_rules)
+    pub policy_rules_file: Option<PathBuf>,
+    /// An optional subject to publish every policy decision on, as a cloud event
+    pub policy_audit_subject: Option<String>,
+    /// An optional `JetStream` stream to retain the decisions published on `policy_audit_subject`
+    /// in. The stream is created if it does not exist
+    pub policy_audit_stream: Option<String>,
+    /// The maximum age of the decisions retained in a created `policy_audit_stream`, 7 days if
+    /// not set
+    pub policy_audit_max_age: Option<Duration>,
+    /// The maximum size in bytes of a created `policy_audit_stream`, 1 GiB if not set. The oldest
+    /// decisions are discarded first
+    pub policy_audit_max_bytes: Option<i64>,
 }
 
 impl Default for Host {
@@ -97,11 +205,19 @@ impl Default for Host {
             provider_shutdown_delay: None,
             oci_opts: OciConfig::default(),
             allow_file_load: false,
+            compilation_cache_dir: None,
+            compilation_cache_max_size: None,
             enable_structured_logging: false,
             log_level: LogLevel::Info,
             config_service_enabled: false,
             otel_config: OtelConfig::default(),
             policy_service_config: PolicyService::default(),
+            actor_limits: ActorLimits::default(),
+            actor_instance_pool: false,
+            actor_instance_pool_size: None,
+            link_policy: LinkPolicy::default(),
+            snapshot_state: false,
+            restore_state: false,
         }
     }
 }
@@ -10,6 +10,7 @@ use tracing::instrument;
 use ulid::Ulid;
 use uuid::Uuid;
 use wascap::jwt;
+use wasmcloud_runtime::LimitExceeded;
 
 fn format_actor_claims(claims: &jwt::Claims<jwt::Actor>) -> serde_json::Value {
     let issuer = &claims.issuer;
@@ -125,6 +126,27 @@ pub fn actor_scale_failed(
     })
 }
 
+pub fn actor_limit_exceeded(
+    public_key: impl AsRef<str>,
+    host_id: impl AsRef<str>,
+    operation: impl AsRef<str>,
+    limit: LimitExceeded,
+) -> serde_json::Value {
+    let limit_name = match limit {
+        LimitExceeded::MemorySize(_) => "memory_size",
+        LimitExceeded::TableElements(_) => "table_elements",
+        LimitExceeded::Instances => "instances",
+        LimitExceeded::ExecutionTime => "execution_time",
+    };
+    json!({
+        "public_key": public_key.as_ref(),
+        "host_id": host_id.as_ref(),
+        "operation": operation.as_ref(),
+        "limit": limit_name,
+        "error": limit.to_string(),
+    })
+}
+
 pub fn linkdef_set(
     id: impl AsRef<str>,
     actor_id: impl AsRef<str>,
@@ -233,6 +255,22 @@ pub fn provider_health_check(
     })
 }
 
+pub fn circuit_breaker(
+    provider_id: impl AsRef<str>,
+    link_name: impl AsRef<str>,
+    contract_id: impl AsRef<str>,
+    host_id: impl AsRef<str>,
+    consecutive_failures: u32,
+) -> serde_json::Value {
+    json!({
+        "public_key": provider_id.as_ref(),
+        "link_name": link_name.as_ref(),
+        "contract_id": contract_id.as_ref(),
+        "host_id": host_id.as_ref(),
+        "consecutive_failures": consecutive_failures,
+    })
+}
+
 pub fn config_set(entity_id: impl AsRef<str>, key: impl AsRef<str>) -> serde_json::Value {
     json!({
         "entity_id": entity_id.as_ref(),
@@ -0,0 +1,346 @@
+use core::num::NonZeroU32;
+use core::time::Duration;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use anyhow::{bail, Context as _};
+use cloudevents::EventBuilderV10;
+use tokio::time::Instant;
+use tracing::{instrument, warn};
+use wasmcloud_core::WasmCloudEntity;
+
+use super::config::LinkPolicy;
+use super::event;
+
+const TIMEOUT_KEY: &str = "wasmcloud.rpc_timeout_ms";
+const MAX_RETRIES_KEY: &str = "wasmcloud.max_retries";
+const RETRY_BACKOFF_KEY: &str = "wasmcloud.retry_backoff_ms";
+const CIRCUIT_BREAKER_THRESHOLD_KEY: &str = "wasmcloud.circuit_breaker_threshold";
+const CIRCUIT_BREAKER_RESET_KEY: &str = "wasmcloud.circuit_breaker_reset_ms";
+const IDEMPOTENT_OPERATIONS_KEY: &str = "wasmcloud.idempotent_operations";
+
+impl LinkPolicy {
+    /// Returns the policy with settings overridden by link definition `values`
+    pub(crate) fn with_link_values(
+        &self,
+        values: &HashMap<String, String>,
+    ) -> anyhow::Result<Self> {
+        fn parse<T: core::str::FromStr>(key: &str, value: &str) -> anyhow::Result<T>
+        where
+            T::Err: std::error::Error + Send + Sync + 'static,
+        {
+            value
+                .parse()
+                .with_context(|| format!("invalid link definition value `{key}`: `{value}`"))
+        }
+
+        let mut policy = self.clone();
+        for (key, value) in values {
+            match key.as_str() {
+                TIMEOUT_KEY => {
+                    policy.timeout = Some(Duration::from_millis(parse(key, value)?));
+                }
+                MAX_RETRIES_KEY => policy.max_retries = parse(key, value)?,
+                RETRY_BACKOFF_KEY => {
+                    policy.retry_backoff = Duration::from_millis(parse(key, value)?);
+                }
+                CIRCUIT_BREAKER_THRESHOLD_KEY => {
+                    policy.circuit_breaker_threshold = NonZeroU32::new(parse(key, value)?);
+                }
+                CIRCUIT_BREAKER_RESET_KEY => {
+                    policy.circuit_breaker_reset = Duration::from_millis(parse(key, value)?);
+                }
+                IDEMPOTENT_OPERATIONS_KEY => {
+                    policy.idempotent_operations = value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|operation| !operation.is_empty())
+                        .map(String::from)
+                        .collect();
+                }
+                _ => {}
+            }
+        }
+        Ok(policy)
+    }
+
+    /// Returns the delay before retry number `attempt`, counted from 1
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        self.retry_backoff
+            .saturating_mul(2_u32.saturating_pow(attempt.saturating_sub(1)))
+    }
+
+    /// Returns whether `operation`, which is formatted as `<contract>/<Service>.<Method>`, is one
+    /// of the idempotent operations, which may therefore be retried
+    pub(crate) fn is_idempotent(&self, operation: &str) -> bool {
+        let method = operation
+            .rsplit_once('/')
+            .map_or(operation, |(_, method)| method);
+        self.idempotent_operations
+            .iter()
+            .any(|idempotent| idempotent == operation || idempotent == method)
+    }
+}
+
+/// Transition of a circuit breaker
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Transition {
+    Opened,
+    Closed,
+}
+
+/// State of a circuit breaker for a single provider link
+#[derive(Debug, Default)]
+struct Breaker {
+    consecutive_failures: u32,
+    /// Time until which invocations are short-circuited, if the breaker is open
+    open_until: Option<Instant>,
+}
+
+impl Breaker {
+    /// Returns whether an invocation may proceed at `now`. Once the reset period elapses, a single
+    /// trial invocation is let through and the breaker stays open for another `reset` period,
+    /// unless the trial succeeds
+    fn allow(&mut self, reset: Duration, now: Instant) -> bool {
+        match self.open_until {
+            Some(open_until) if now < open_until => false,
+            Some(_) => {
+                self.open_until = Some(now + reset);
+                true
+            }
+            None => true,
+        }
+    }
+
+    /// Records the outcome of an invocation at `now`
+    fn record(
+        &mut self,
+        success: bool,
+        threshold: NonZeroU32,
+        reset: Duration,
+        now: Instant,
+    ) -> Option<Transition> {
+        if success {
+            let tripped = self.consecutive_failures >= threshold.get();
+            self.consecutive_failures = 0;
+            self.open_until = None;
+            return tripped.then_some(Transition::Closed);
+        }
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        if self.consecutive_failures < threshold.get() {
+            return None;
+        }
+        // A failed trial invocation keeps a breaker open, which was already reported as open
+        let opened = self.open_until.is_none();
+        self.open_until = Some(now + reset);
+        opened.then_some(Transition::Opened)
+    }
+}
+
+/// Circuit breakers for provider links, which publish an event whenever a breaker opens or closes
+#[derive(Debug)]
+pub(crate) struct CircuitBreakers {
+    breakers: Mutex<HashMap<(String, String), Breaker>>,
+    ctl_nats: async_nats::Client,
+    event_builder: EventBuilderV10,
+    lattice: String,
+    host_id: String,
+}
+
+impl CircuitBreakers {
+    pub(crate) fn new(
+        ctl_nats: async_nats::Client,
+        event_builder: EventBuilderV10,
+        lattice: String,
+        host_id: String,
+    ) -> Self {
+        Self {
+            breakers: Mutex::default(),
+            ctl_nats,
+            event_builder,
+            lattice,
+            host_id,
+        }
+    }
+
+    /// Fails if the circuit breaker of the `target` link is open
+    pub(crate) fn check(
+        &self,
+        target: &WasmCloudEntity,
+        policy: &LinkPolicy,
+    ) -> anyhow::Result<()> {
+        if policy.circuit_breaker_threshold.is_none() {
+            return Ok(());
+        }
+        let Ok(mut breakers) = self.breakers.lock() else {
+            return Ok(());
+        };
+        let key = (target.public_key.clone(), target.link_name.clone());
+        if breakers.get_mut(&key).map_or(true, |breaker| {
+            breaker.allow(policy.circuit_breaker_reset, Instant::now())
+        }) {
+            Ok(())
+        } else {
+            bail!(
+                "circuit breaker for provider `{}` on link `{}` is open",
+                target.public_key,
+                target.link_name
+            )
+        }
+    }
+
+    /// Removes the circuit breaker of the link `link_name` to `provider_id`
+    pub(crate) fn remove(&self, provider_id: &str, link_name: &str) {
+        if let Ok(mut breakers) = self.breakers.lock() {
+            breakers.remove(&(provider_id.to_string(), link_name.to_string()));
+        }
+    }
+
+    /// Records whether an invocation of the `target` link reached the provider
+    #[instrument(level = "trace", skip(self, policy))]
+    pub(crate) async fn record(
+        &self,
+        target: &WasmCloudEntity,
+        policy: &LinkPolicy,
+        success: bool,
+    ) {
+        let Some(threshold) = policy.circuit_breaker_threshold else {
+            return;
+        };
+        let transition = {
+            let Ok(mut breakers) = self.breakers.lock() else {
+                return;
+            };
+            let key = (target.public_key.clone(), target.link_name.clone());
+            if success && !breakers.contains_key(&key) {
+                return;
+            }
+            let breaker = breakers.entry(key).or_default();
+            breaker
+                .record(
+                    success,
+                    threshold,
+                    policy.circuit_breaker_reset,
+                    Instant::now(),
+                )
+                .map(|transition| (transition, breaker.consecutive_failures))
+        };
+        let Some((transition, consecutive_failures)) = transition else {
+            return;
+        };
+        let name = match transition {
+            Transition::Opened => {
+                warn!(
+                    provider_id = target.public_key,
+                    link_name = target.link_name,
+                    "circuit breaker opened"
+                );
+                "circuit_breaker_opened"
+            }
+            Transition::Closed => "circuit_breaker_closed",
+        };
+        if let Err(e) = event::publish(
+            &self.event_builder,
+            &self.ctl_nats,
+            &self.lattice,
+            name,
+            event::circuit_breaker(
+                &target.public_key,
+                &target.link_name,
+                &target.contract_id,
+                &self.host_id,
+                consecutive_failures,
+            ),
+        )
+        .await
+        {
+            warn!(?e, "failed to publish `{name}` event");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn link_values() -> anyhow::Result<()> {
+        let policy = LinkPolicy::default().with_link_values(&HashMap::from([
+            (TIMEOUT_KEY.into(), "500".into()),
+            (MAX_RETRIES_KEY.into(), "3".into()),
+            (CIRCUIT_BREAKER_THRESHOLD_KEY.into(), "5".into()),
+            ("unrelated".into(), "value".into()),
+        ]))?;
+        assert_eq!(policy.timeout, Some(Duration::from_millis(500)));
+        assert_eq!(policy.max_retries, 3);
+        assert_eq!(policy.circuit_breaker_threshold, NonZeroU32::new(5));
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+
+        let policy = policy.with_link_values(&HashMap::from([(
+            CIRCUIT_BREAKER_THRESHOLD_KEY.into(),
+            "0".into(),
+        )]))?;
+        assert_eq!(policy.circuit_breaker_threshold, None);
+
+        assert!(LinkPolicy::default()
+            .with_link_values(&HashMap::from([(MAX_RETRIES_KEY.into(), "many".into())]))
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn idempotent_operations() -> anyhow::Result<()> {
+        assert!(!LinkPolicy::default().is_idempotent("wasmcloud:keyvalue/KeyValue.Get"));
+
+        let policy = LinkPolicy::default().with_link_values(&HashMap::from([(
+            IDEMPOTENT_OPERATIONS_KEY.into(),
+            "KeyValue.Get, wasmcloud:blobstore/Blobstore.ContainerExists,".into(),
+        )]))?;
+        assert_eq!(
+            policy.idempotent_operations,
+            [
+                "KeyValue.Get",
+                "wasmcloud:blobstore/Blobstore.ContainerExists"
+            ]
+        );
+        assert!(policy.is_idempotent("wasmcloud:keyvalue/KeyValue.Get"));
+        assert!(policy.is_idempotent("wasmcloud:blobstore/Blobstore.ContainerExists"));
+        assert!(!policy.is_idempotent("wasmcloud:keyvalue/KeyValue.GetMany"));
+        assert!(!policy.is_idempotent("wasmcloud:keyvalue/KeyValue.Set"));
+        assert!(!policy.is_idempotent("other:blobstore/Blobstore.ContainerExists"));
+        Ok(())
+    }
+
+    #[test]
+    fn breaker() {
+        let threshold = NonZeroU32::new(2).expect("threshold must be non-zero");
+        let reset = Duration::from_secs(1);
+        let now = Instant::now();
+        let mut breaker = Breaker::default();
+
+        assert_eq!(breaker.record(false, threshold, reset, now), None);
+        assert!(breaker.allow(reset, now));
+        assert_eq!(
+            breaker.record(false, threshold, reset, now),
+            Some(Transition::Opened)
+        );
+        assert!(!breaker.allow(reset, now));
+
+        // Only a single trial is let through, a failed trial keeps the breaker open
+        let now = now + reset;
+        assert!(breaker.allow(reset, now));
+        assert!(!breaker.allow(reset, now));
+        assert_eq!(breaker.record(false, threshold, reset, now), None);
+        assert!(!breaker.allow(reset, now));
+
+        let now = now + reset;
+        assert!(breaker.allow(reset, now));
+        assert_eq!(
+            breaker.record(true, threshold, reset, now),
+            Some(Transition::Closed)
+        );
+        assert!(breaker.allow(reset, now));
+    }
+}
@@ -12,6 +12,7 @@ use std::io::Cursor;
 use std::process::Stdio;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use anyhow::{anyhow, bail, ensure, Context as ErrContext};
 use async_nats::jetstream::kv::{Entry as KvEntry, Operation, Store};
@@ -27,7 +28,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 use sha2::{Digest, Sha256};
 use tokio::io::{empty, stderr, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
-use tokio::sync::{watch, RwLock};
+use tokio::sync::{watch, Mutex, RwLock};
 use tokio::task::JoinHandle;
 use tokio::time::{interval_at, Instant};
 use tokio::{process, select, spawn};
@@ -39,11 +40,11 @@ use uuid::Uuid;
 pub use config::Host as HostConfig;
 use wascap::{jwt, prelude::ClaimsBuilder};
 use wasmcloud_control_interface::{
-    ActorAuctionAck, ActorAuctionRequest, ActorDescription, GetClaimsResponse, HostInventory,
-    HostLabel, LinkDefinition, LinkDefinitionList, ProviderAuctionAck, ProviderAuctionRequest,
-    ProviderDescription, RegistryCredential, RegistryCredentialMap, RemoveLinkDefinitionRequest,
-    ScaleActorCommand, StartProviderCommand, StopActorCommand, StopHostCommand,
-    StopProviderCommand, UpdateActorCommand,
+    ActorAuctionAck, ActorAuctionRequest, ActorDescription, CtlOperationAck, GetClaimsResponse,
+    HostInventory, HostLabel, LinkDefinition, LinkDefinitionList, ProviderAuctionAck,
+    ProviderAuctionRequest, ProviderDescription, RegistryCredential, RegistryCredentialMap,
+    RemoveLinkDefinitionRequest, ScaleActorCommand, StartProviderCommand, StopActorCommand,
+    StopHostCommand, StopProviderCommand, UpdateActorCommand,
 };
 use wasmcloud_core::chunking::{ChunkEndpoint, CHUNK_RPC_EXTRA_TIME, CHUNK_THRESHOLD_BYTES};
 use wasmcloud_core::{
@@ -52,22 +53,30 @@ use wasmcloud_core::{
 use wasmcloud_runtime::capability::logging::logging;
 use wasmcloud_runtime::capability::{
     blobstore, guest_config, messaging, ActorIdentifier, Blobstore, Bus, IncomingHttp,
-    KeyValueAtomic, KeyValueReadWrite, Logging, Messaging, OutgoingHttp, OutgoingHttpRequest,
-    TargetEntity, TargetInterface,
+    KeyValueAtomic, KeyValueReadWrite, Logging, Messaging, MessagingHandler, OutgoingHttp,
+    OutgoingHttpRequest, TargetEntity, TargetInterface,
 };
-use wasmcloud_runtime::Runtime;
+use wasmcloud_runtime::{InstancePool, LimitExceeded, Runtime};
 use wasmcloud_tracing::context::TraceContextInjector;
 
+use crate::metrics::HostMetrics;
+use crate::policy::RuleSet;
 use crate::{
     fetch_actor, socket_pair, OciConfig, PolicyAction, PolicyHostInfo, PolicyManager,
-    PolicyRequestSource, PolicyRequestTarget, PolicyResponse, RegistryAuth, RegistryConfig,
-    RegistryType,
+    PolicyRequestCaller, PolicyRequestSource, PolicyRequestTarget, PolicyResponse, RegistryAuth,
+    RegistryConfig, RegistryType,
 };
 
 /// wasmCloud host configuration
 pub mod config;
 
+mod actor_limits;
 mod event;
+mod link_policy;
+mod state;
+
+use self::config::LinkPolicy;
+use self::link_policy::CircuitBreakers;
 
 const ACCEPTED: &str = r#"{"accepted":true,"error":""}"#;
 
@@ -253,7 +262,11 @@ impl Queue {
 #[derive(Debug)]
 struct ActorInstance {
     actor: wasmcloud_runtime::Actor,
+    /// Pool of pre-instantiated instances of the actor, if enabled
+    pool: Option<InstancePool>,
     nats: async_nats::Client,
+    ctl_nats: async_nats::Client,
+    event_builder: EventBuilderV10,
     id: Ulid,
     calls: AbortHandle,
     handler: Handler,
@@ -292,6 +305,11 @@ struct Handler {
     targets: Arc<RwLock<HashMap<TargetInterface, TargetEntity>>>,
     aliases: Arc<RwLock<HashMap<String, WasmCloudEntity>>>,
     chunk_endpoint: ChunkEndpoint,
+    metrics: HostMetrics,
+    // package -> target -> policy
+    link_policies: Arc<RwLock<HashMap<String, HashMap<String, LinkPolicy>>>>,
+    default_link_policy: LinkPolicy,
+    circuit_breakers: Arc<CircuitBreakers>,
 }
 
 #[instrument(level = "trace")]
@@ -358,6 +376,7 @@ impl Handler {
                 .chunkify(&invocation.id, Cursor::new(invocation.msg))
                 .await
                 .context("failed to chunk invocation")?;
+            self.metrics.record_chunked_payload("request");
             invocation.msg = vec![];
         }
 
@@ -374,16 +393,51 @@ impl Handler {
             ),
         };
 
-        let timeout = needs_chunking.then_some(CHUNK_RPC_EXTRA_TIME); // TODO: add rpc_nats timeout
-        let request = async_nats::Request::new()
-            .payload(payload.into())
-            .timeout(timeout)
-            .headers(headers); // TODO: remove headers once all providers are built off the new SDK, which parses the trace context in the invocation
-        let res = self
-            .nats
-            .send_request(topic, request)
-            .await
-            .context("failed to publish on NATS topic")?;
+        let policy = self.link_policy(&invocation.target).await;
+        let timeout = needs_chunking
+            .then_some(CHUNK_RPC_EXTRA_TIME)
+            .or(policy.timeout);
+        // Chunked requests cannot be retried, since the chunks are consumed by the receiver
+        let max_retries = if needs_chunking || !policy.is_idempotent(&invocation.operation) {
+            0
+        } else {
+            policy.max_retries
+        };
+        let payload = Bytes::from(payload);
+        let mut attempt = 0;
+        let res = loop {
+            self.circuit_breakers.check(&invocation.target, &policy)?;
+            let request = async_nats::Request::new()
+                .payload(payload.clone())
+                .timeout(timeout)
+                .headers(headers.clone()); // TODO: remove headers once all providers are built off the new SDK, which parses the trace context in the invocation
+            match self.nats.send_request(topic.clone(), request).await {
+                Ok(res) => {
+                    self.circuit_breakers
+                        .record(&invocation.target, &policy, true)
+                        .await;
+                    break res;
+                }
+                Err(err) => {
+                    self.circuit_breakers
+                        .record(&invocation.target, &policy, false)
+                        .await;
+                    if attempt >= max_retries {
+                        return Err(anyhow!(err).context("failed to publish on NATS topic"));
+                    }
+                    attempt += 1;
+                    let backoff = policy.backoff(attempt);
+                    warn!(
+                        ?err,
+                        operation = invocation.operation,
+                        attempt,
+                        ?backoff,
+                        "invocation failed, retrying"
+                    );
+                    tokio::time::sleep(backoff).await;
+                }
+            }
+        };
 
         let InvocationResponse {
             invocation_id,
@@ -402,6 +456,7 @@ impl Handler {
                 .get_unchunkified_response(&invocation_id)
                 .await
                 .context("failed to dechunk response")?;
+            self.metrics.record_chunked_payload("response");
         } else {
             ensure!(resp_length == msg.len(), "message size mismatch");
         }
@@ -413,6 +468,17 @@ impl Handler {
         }
     }
 
+    /// Returns the policy for invocations of `target`
+    async fn link_policy(&self, target: &WasmCloudEntity) -> LinkPolicy {
+        self.link_policies
+            .read()
+            .await
+            .get(&target.contract_id)
+            .and_then(|policies| policies.get(&target.link_name))
+            .unwrap_or(&self.default_link_policy)
+            .clone()
+    }
+
     #[instrument(level = "debug", skip(self, operation, request))]
     async fn call_operation(
         &self,
@@ -809,14 +875,7 @@ impl Bus for Handler {
         let (mut req_r, req_w) = socket_pair()?;
         let (res_r, mut res_w) = socket_pair()?;
 
-        let links = Arc::clone(&self.links);
-        let aliases = Arc::clone(&self.aliases);
-        let nats = self.nats.clone();
-        let chunk_endpoint = self.chunk_endpoint.clone();
-        let lattice = self.lattice.clone();
-        let origin = self.origin.clone();
-        let cluster_key = self.cluster_key.clone();
-        let host_key = self.host_key.clone();
+        let handler = self.clone();
         Ok((
             async move {
                 // TODO: Stream data
@@ -826,98 +885,17 @@ impl Bus for Handler {
                     .await
                     .context("failed to read request")
                     .map_err(|e| e.to_string())?;
-                let links = links.read().await;
-                let aliases = aliases.read().await;
-                let (package, _) = operation
-                    .rsplit_once('/')
-                    .context("failed to parse operation")
-                    .map_err(|e| e.to_string())?;
-                let inv_target = resolve_target(target.as_ref(), links.get(package), &aliases)
-                    .await
-                    .map_err(|e| e.to_string())?;
-                let needs_chunking = request.len() > CHUNK_THRESHOLD_BYTES;
-                let injector = TraceContextInjector::default_with_span();
-                let headers = injector_to_headers(&injector);
-                let mut invocation = Invocation::new(
-                    &cluster_key,
-                    &host_key,
-                    origin,
-                    inv_target,
-                    operation,
-                    request,
-                    injector.into(),
-                )
-                .map_err(|e| e.to_string())?;
-
-                if needs_chunking {
-                    chunk_endpoint
-                        .chunkify(&invocation.id, Cursor::new(invocation.msg))
-                        .await
-                        .context("failed to chunk invocation")
-                        .map_err(|e| e.to_string())?;
-                    invocation.msg = vec![];
-                }
-
-                let payload = rmp_serde::to_vec_named(&invocation)
-                    .context("failed to encode invocation")
-                    .map_err(|e| e.to_string())?;
-                let topic = match target {
-                    None | Some(TargetEntity::Link(_)) => format!(
-                        "wasmbus.rpc.{lattice}.{}.{}",
-                        invocation.target.public_key, invocation.target.link_name,
-                    ),
-                    Some(TargetEntity::Actor(_)) => {
-                        format!("wasmbus.rpc.{lattice}.{}", invocation.target.public_key)
-                    }
-                };
-
-                let timeout = needs_chunking.then_some(CHUNK_RPC_EXTRA_TIME); // TODO: add rpc_nats timeout
-                let request = async_nats::Request::new()
-                    .payload(payload.into())
-                    .timeout(timeout)
-                    .headers(headers); // TODO: remove headers once all providers are built off the new SDK, which parses the trace context in the invocation
-                let res = nats
-                    .send_request(topic, request)
+                let msg = handler
+                    .call_operation_with_payload(target, operation, request)
                     .await
                     .context("failed to call provider")
+                    .map_err(|e| e.to_string())??;
+                res_w
+                    .write_all(&msg)
+                    .await
+                    .context("failed to write reply")
                     .map_err(|e| e.to_string())?;
-
-                let InvocationResponse {
-                    invocation_id,
-                    mut msg,
-                    content_length,
-                    error,
-                    ..
-                } = rmp_serde::from_slice(&res.payload)
-                    .context("failed to decode invocation response")
-                    .map_err(|e| e.to_string())?;
-                if invocation_id != invocation.id {
-                    return Err("invocation ID mismatch".into());
-                }
-
-                let resp_length = usize::try_from(content_length)
-                    .context("content length does not fit in usize")
-                    .map_err(|e| e.to_string())?;
-                if resp_length > CHUNK_THRESHOLD_BYTES {
-                    msg = chunk_endpoint
-                        .get_unchunkified_response(&invocation_id)
-                        .await
-                        .context("failed to dechunk response")
-                        .map_err(|e| e.to_string())?;
-                } else if resp_length != msg.len() {
-                    return Err("message size mismatch".into());
-                }
-
-                if let Some(error) = error {
-                    Err(error)
-                } else {
-                    res_w
-                        .write_all(&msg)
-                        .await
-                        .context("failed to write reply")
-                        .map_err(|e| e.to_string())?;
-                    Ok(())
-                }
+                Ok(())
             }
             .boxed(),
             Box::new(req_w),
@@ -1248,6 +1226,10 @@ impl OutgoingHttp for Handler {
 }
 
 impl ActorInstance {
+    /// Handles an invocation of `operation` on the actor. HTTP requests and messages delivered by
+    /// messaging providers are dispatched to the typed exports of component actors, all other
+    /// operations are passed to the untyped guest call. `wasi:keyvalue/handle-watch` callbacks are
+    /// not dispatched, since no keyvalue provider delivers watch events to actors
     #[instrument(level = "debug", skip(self, msg))]
     async fn handle_invocation(
         &self,
@@ -1255,11 +1237,12 @@ impl ActorInstance {
         operation: &str,
         msg: Vec<u8>,
     ) -> anyhow::Result<Result<Vec<u8>, String>> {
-        let mut instance = self
-            .actor
-            .instantiate()
-            .await
-            .context("failed to instantiate actor")?;
+        let mut instance = if let Some(pool) = &self.pool {
+            pool.instance().await
+        } else {
+            self.actor.instantiate().await
+        }
+        .context("failed to instantiate actor")?;
         instance
             .stderr(stderr())
             .await
@@ -1271,7 +1254,6 @@ impl ActorInstance {
             .logging(Arc::new(self.handler.clone()))
             .messaging(Arc::new(self.handler.clone()))
             .outgoing_http(Arc::new(self.handler.clone()));
-        #[allow(clippy::single_match_else)] // TODO: Remove once more interfaces supported
         match (contract_id, operation) {
             ("wasmcloud:httpserver", "HttpServer.HandleRequest") => {
                 let req: wasmcloud_compat::HttpServerRequest =
@@ -1287,6 +1269,7 @@ impl ActorInstance {
                     .await
                 {
                     Ok(res) => res,
+                    Err(err) if LimitExceeded::from_error(&err).is_some() => return Err(err),
                     Err(err) => return Ok(Err(format!("{err:#}"))),
                 };
                 let res = wasmcloud_compat::HttpResponse::from_http(res)
@@ -1295,6 +1278,18 @@ impl ActorInstance {
                 let res = rmp_serde::to_vec_named(&res).context("failed to encode response")?;
                 Ok(Ok(res))
             }
+            ("wasmcloud:messaging", wasmcloud_compat::messaging::HANDLE_MESSAGE_OPERATION) => {
+                let msg: wasmcloud_compat::messaging::SubMessage =
+                    rmp_serde::from_slice(&msg).context("failed to decode message")?;
+                let msg = wasmcloud_compat::messaging::BrokerMessage::from(msg);
+                instance
+                    .into_messaging_handler()
+                    .await
+                    .context("failed to instantiate `wasmcloud:messaging/handler`")?
+                    .handle_message(msg.into())
+                    .await
+                    .map(|res| res.map(|()| Vec::default()))
+            }
             _ => {
                 let res = AsyncBytesMut::default();
                 match instance
@@ -1323,7 +1318,9 @@ impl ActorInstance {
             .context("failed to convert content_length to usize")?;
         let inv_msg = if content_length > CHUNK_THRESHOLD_BYTES {
             debug!(inv_id = invocation.id, "dechunking invocation");
-            self.chunk_endpoint.get_unchunkified(&invocation.id).await?
+            let msg = self.chunk_endpoint.get_unchunkified(&invocation.id).await?;
+            self.handler.metrics.record_chunked_payload("request");
+            msg
         } else {
             invocation.msg
         };
@@ -1400,6 +1397,7 @@ impl ActorInstance {
                         .chunkify_response(&invocation.id, Cursor::new(resp_msg))
                         .await
                         .context("failed to chunk invocation response")?;
+                    self.handler.metrics.record_chunked_payload("response");
                     vec![]
                 } else {
                     resp_msg
@@ -1415,6 +1413,28 @@ impl ActorInstance {
         }
     }
 
+    /// Publishes an event notifying that an invocation of `operation` exceeded a resource limit
+    #[instrument(level = "debug", skip(self))]
+    async fn publish_limit_exceeded(&self, operation: &str, limit: LimitExceeded) {
+        let data = event::actor_limit_exceeded(
+            &self.handler.claims.subject,
+            self.handler.host_key.public_key(),
+            operation,
+            limit,
+        );
+        if let Err(e) = event::publish(
+            &self.event_builder,
+            &self.ctl_nats,
+            &self.handler.lattice,
+            "actor_limit_exceeded",
+            data,
+        )
+        .await
+        {
+            error!(?e, "failed to publish actor limit exceeded event");
+        }
+    }
+
     #[instrument(level = "info", skip_all)] // NOTE: level needs to stay at info here to attach the incoming span context
     async fn handle_rpc_message(&self, message: async_nats::Message) {
         let async_nats::Message {
@@ -1443,7 +1463,13 @@ impl ActorInstance {
                 let target = invocation.target.clone();
                 let operation = invocation.operation.clone();
 
+                let metrics = &self.handler.metrics;
+                let actor_id = &self.handler.claims.subject;
+                let active = metrics.active_instance(actor_id);
+                let start = Instant::now();
                 let res = self.handle_call(invocation).await;
+                metrics.record_invocation(actor_id, &operation, start.elapsed(), res.is_err());
+                drop(active);
                 match res {
                     Ok((msg, content_length)) => InvocationResponse {
                         msg,
@@ -1461,9 +1487,15 @@ impl ActorInstance {
                             ?e,
                             "failed to handle request"
                         );
+                        let error = if let Some(limit) = LimitExceeded::from_error(&e) {
+                            self.publish_limit_exceeded(&operation, limit).await;
+                            format!("{e}: {limit}")
+                        } else {
+                            e.to_string()
+                        };
                         InvocationResponse {
                             invocation_id,
-                            error: Some(e.to_string()),
+                            error: Some(error),
                             trace_context,
                             ..Default::default()
                         }
@@ -1537,6 +1569,8 @@ struct ProviderInstance {
     child: JoinHandle<()>,
     id: Ulid,
     annotations: Annotations,
+    /// Configuration the provider was started with
+    configuration: Option<String>,
 }
 
 #[derive(Debug)]
@@ -1583,6 +1617,11 @@ pub struct Host {
     actor_claims: Arc<RwLock<HashMap<String, jwt::Claims<jwt::Actor>>>>, // TODO: use a single map once Claims is an enum
     provider_claims: Arc<RwLock<HashMap<String, jwt::Claims<jwt::CapabilityProvider>>>>,
     config_data_cache: Arc<RwLock<ConfigCache>>,
+    metrics: HostMetrics,
+    circuit_breakers: Arc<CircuitBreakers>,
+    /// Held while a snapshot of the host state is taken and stored
+    state_snapshot: Mutex<()>,
+    ctl_caller_nonces: CtlCallerNonces,
 }
 
 #[allow(clippy::large_enum_variant)] // Without this clippy complains actor is at least 0 bytes while provider is at least 280 bytes. That doesn't make sense
@@ -1690,6 +1729,39 @@ async fn create_bucket(
     }
 }
 
+#[instrument(level = "debug", skip_all)]
+async fn create_policy_audit_stream(
+    jetstream: &async_nats::jetstream::Context,
+    stream: &str,
+    subject: &str,
+    max_age: Option<Duration>,
+    max_bytes: Option<i64>,
+) -> anyhow::Result<()> {
+    const DEFAULT_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+    const DEFAULT_MAX_BYTES: i64 = 1024 * 1024 * 1024;
+
+    // Don't create the stream if it already exists
+    if jetstream.get_stream(stream).await.is_ok() {
+        info!(%stream, "policy audit stream already exists. Skipping creation.");
+        return Ok(());
+    }
+
+    jetstream
+        .create_stream(async_nats::jetstream::stream::Config {
+            name: stream.to_string(),
+            subjects: vec![subject.to_string()],
+            storage: async_nats::jetstream::stream::StorageType::File,
+            max_age: max_age.unwrap_or(DEFAULT_MAX_AGE),
+            max_bytes: max_bytes.unwrap_or(DEFAULT_MAX_BYTES),
+            discard: async_nats::jetstream::stream::DiscardPolicy::Old,
+            ..Default::default()
+        })
+        .await
+        .map_err(|err| anyhow!(err).context(format!("failed to create stream '{stream}'")))?;
+    info!(%stream, %subject, "created policy audit stream");
+    Ok(())
+}
+
 /// Given the NATS address, authentication jwt, seed, tls requirement and optional request timeout,
 /// attempt to establish connection.
 ///
@@ -1895,6 +1967,9 @@ impl Host {
             ensure!(host_key.key_pair_type() == KeyPairType::Server);
             Arc::clone(host_key)
         } else {
+            if config.snapshot_state || config.restore_state {
+                warn!("host state snapshots are enabled, but no host seed is set: snapshots are stored under a random host ID and cannot be restored after a restart");
+            }
             Arc::new(KeyPair::new(KeyPairType::Server))
         };
 
@@ -1969,12 +2044,28 @@ impl Host {
         let (stop_tx, stop_rx) = watch::channel(None);
 
         // TODO: Configure
-        let runtime = Runtime::builder()
-            .actor_config(wasmcloud_runtime::ActorConfig {
-                require_signature: true,
-            })
-            .build()
-            .context("failed to build runtime")?;
+        let limits = &config.actor_limits;
+        let mut runtime = Runtime::builder().actor_config(
+            limits.actor_config(config.actor_instance_pool, config.actor_instance_pool_size),
+        );
+        if let Some(max_instances) = limits.pooled_instances {
+            let defaults = wasmcloud_runtime::PoolingAllocatorConfig::default();
+            runtime = runtime.pooling_allocator(wasmcloud_runtime::PoolingAllocatorConfig {
+                max_instances,
+                max_memory_size: limits.max_memory_size.unwrap_or(defaults.max_memory_size),
+                max_table_elements: limits
+                    .max_table_elements
+                    .unwrap_or(defaults.max_table_elements),
+            });
+        }
+        if let Some(dir) = &config.compilation_cache_dir {
+            let mut compilation_cache = wasmcloud_runtime::CompilationCacheConfig::new(dir);
+            if let Some(max_size) = config.compilation_cache_max_size {
+                compilation_cache.max_size = max_size;
+            }
+            runtime = runtime.compilation_cache(compilation_cache);
+        }
+        let runtime = runtime.build().context("failed to build runtime")?;
         let event_builder = EventBuilderV10::new().source(host_key.public_key());
 
         let ctl_jetstream = if let Some(domain) = config.js_domain.as_ref() {
@@ -2008,6 +2099,25 @@ impl Host {
         let registry_config = RwLock::new(supplemental_config.registry_config.unwrap_or_default());
         merge_registry_config(&registry_config, config.oci_opts.clone()).await;
 
+        if let (Some(stream), Some(subject)) = (
+            &config.policy_service_config.policy_audit_stream,
+            &config.policy_service_config.policy_audit_subject,
+        ) {
+            create_policy_audit_stream(
+                &ctl_jetstream,
+                stream,
+                subject,
+                config.policy_service_config.policy_audit_max_age,
+                config.policy_service_config.policy_audit_max_bytes,
+            )
+            .await?;
+        }
+
+        let rule_set = if let Some(path) = &config.policy_service_config.policy_rules_file {
+            Some(RuleSet::from_file(path).await?)
+        } else {
+            None
+        };
         let policy_manager = PolicyManager::new(
             ctl_nats.clone(),
             PolicyHostInfo {
@@ -2019,9 +2129,18 @@ impl Host {
             config.policy_service_config.policy_topic.clone(),
             config.policy_service_config.policy_timeout_ms,
             config.policy_service_config.policy_changes_topic.clone(),
+            rule_set,
+            config.policy_service_config.policy_audit_subject.clone(),
         )
         .await?;
 
+        let circuit_breakers = Arc::new(CircuitBreakers::new(
+            ctl_nats.clone(),
+            event_builder.clone(),
+            config.lattice.clone(),
+            host_key.public_key(),
+        ));
+
         let host = Host {
             actors: RwLock::default(),
             chunk_endpoint,
@@ -2053,6 +2172,10 @@ impl Host {
             actor_claims: Arc::default(),
             provider_claims: Arc::default(),
             config_data_cache: Arc::default(),
+            metrics: HostMetrics::default(),
+            circuit_breakers,
+            state_snapshot: Mutex::default(),
+            ctl_caller_nonces: CtlCallerNonces::default(),
         };
 
         let host = Arc::new(host);
@@ -2231,6 +2354,16 @@ impl Host {
         host.publish_event("host_started", start_evt)
             .await
             .context("failed to publish start event")?;
+        if host.host_config.restore_state {
+            spawn({
+                let host = Arc::clone(&host);
+                async move {
+                    if let Err(err) = host.restore_state().await {
+                        error!(?err, "failed to restore host state snapshot");
+                    }
+                }
+            });
+        }
         info!(
             host_id = host.host_key.public_key(),
             "wasmCloud host started"
@@ -2260,6 +2393,25 @@ impl Host {
         }))
     }
 
+    /// Reloads the policy rules from the configured policy rules file, replacing the rules the
+    /// policy manager evaluates requests against and invalidating all cached policy decisions.
+    /// Does nothing if no policy rules file is configured
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the policy rules file cannot be read or is not a valid rule set
+    #[instrument(level = "debug", skip_all)]
+    pub async fn reload_policy_rules(&self) -> anyhow::Result<()> {
+        let Some(path) = &self.host_config.policy_service_config.policy_rules_file else {
+            debug!("no policy rules file configured, skipping reload");
+            return Ok(());
+        };
+        let rule_set = RuleSet::from_file(path).await?;
+        self.policy_manager.update_rule_set(rule_set).await?;
+        info!(path = %path.display(), "reloaded policy rules");
+        Ok(())
+    }
+
     /// Waits for host to be stopped via lattice commands and returns the shutdown deadline on
     /// success
     ///
@@ -2421,6 +2573,7 @@ impl Host {
         );
         let actor = actor.clone();
         let handler = handler.clone();
+        let pool = actor.instance_pool(max_instances);
         let instance = async move {
             let calls = self
                 .rpc_nats
@@ -2432,7 +2585,10 @@ impl Host {
             let id = Ulid::new();
             let instance = Arc::new(ActorInstance {
                 nats: self.rpc_nats.clone(),
+                ctl_nats: self.ctl_nats.clone(),
+                event_builder: self.event_builder.clone(),
                 actor,
+                pool,
                 id,
                 calls: calls_abort,
                 handler: handler.clone(),
@@ -2461,6 +2617,10 @@ impl Host {
         .await
         .context("failed to instantiate actor")?;
 
+        self.metrics.record_max_instances(
+            &claims.subject,
+            i64::try_from(max_instances.get()).unwrap_or(i64::MAX),
+        );
         Ok(instance)
     }
 
@@ -2474,6 +2634,10 @@ impl Host {
         debug!(subject = claims.subject, "uninstantiating actor instance");
 
         instance.calls.abort();
+        self.metrics.record_max_instances(
+            &claims.subject,
+            -i64::try_from(instance.max_instances.get()).unwrap_or(i64::MAX),
+        );
     }
 
     #[instrument(level = "debug", skip_all)]
@@ -2495,6 +2659,19 @@ impl Host {
             .context("failed to store claims")?;
 
         let links = self.links.read().await;
+        let link_policies = links
+            .values()
+            .filter(|ld| ld.actor_id == claims.subject)
+            .fold(
+                HashMap::<_, HashMap<_, _>>::default(),
+                |mut policies, ld| {
+                    policies
+                        .entry(ld.contract_id.clone())
+                        .or_default()
+                        .insert(ld.link_name.clone(), self.link_policy(ld));
+                    policies
+                },
+            );
         let links = links
             .values()
             .filter(|ld| ld.actor_id == claims.subject)
@@ -2534,6 +2711,10 @@ impl Host {
             targets: Arc::new(RwLock::default()),
             host_key: Arc::clone(&self.host_key),
             chunk_endpoint: self.chunk_endpoint.clone(),
+            metrics: self.metrics.clone(),
+            link_policies: Arc::new(RwLock::new(link_policies)),
+            default_link_policy: self.host_config.link_policy.clone(),
+            circuit_breakers: Arc::clone(&self.circuit_breakers),
         };
 
         let instance = self
@@ -2674,7 +2855,11 @@ impl Host {
     }
 
     #[instrument(level = "trace", skip_all)]
-    async fn fetch_actor(&self, actor_ref: &str) -> anyhow::Result<wasmcloud_runtime::Actor> {
+    async fn fetch_actor(
+        &self,
+        actor_ref: &str,
+        annotations: &Annotations,
+    ) -> anyhow::Result<wasmcloud_runtime::Actor> {
         let registry_config = self.registry_config.read().await;
         let actor = fetch_actor(
             actor_ref,
@@ -2683,11 +2868,35 @@ impl Host {
         )
         .await
         .context("failed to fetch actor")?;
-        let actor = wasmcloud_runtime::Actor::new(&self.runtime, actor)
-            .context("failed to initialize actor")?;
+        let actor = wasmcloud_runtime::Actor::new_with_config(
+            &self.runtime,
+            actor,
+            self.actor_config(annotations)?,
+        )
+        .context("failed to initialize actor")?;
         Ok(actor)
     }
 
+    /// Returns the configuration of an actor started with `annotations`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the annotations contain invalid resource limits
+    fn actor_config(
+        &self,
+        annotations: &Annotations,
+    ) -> anyhow::Result<wasmcloud_runtime::ActorConfig> {
+        let limits = self
+            .host_config
+            .actor_limits
+            .with_annotations(annotations)
+            .context("invalid actor resource limits")?;
+        Ok(limits.actor_config(
+            self.host_config.actor_instance_pool,
+            self.host_config.actor_instance_pool_size,
+        ))
+    }
+
     #[instrument(level = "trace", skip_all)]
     async fn store_actor_claims(&self, claims: jwt::Claims<jwt::Actor>) -> anyhow::Result<()> {
         if let Some(call_alias) = claims
@@ -2722,17 +2931,47 @@ impl Host {
         Ok(())
     }
 
+    /// Ensures that the policy permits the control interface caller to perform the action
+    async fn ensure_ctl_permitted(
+        &self,
+        caller: Option<PolicyRequestCaller>,
+        target: PolicyRequestTarget,
+        action: PolicyAction,
+    ) -> anyhow::Result<()> {
+        let PolicyResponse {
+            permitted,
+            request_id,
+            message,
+        } = self
+            .policy_manager
+            .evaluate_ctl_action(caller, target, action.clone())
+            .await?;
+        ensure!(
+            permitted,
+            "policy denied request to {action:?} `{request_id}`: `{message:?}`",
+        );
+        Ok(())
+    }
+
     #[instrument(level = "debug", skip_all)]
     async fn handle_stop_host(
         &self,
         payload: impl AsRef<[u8]>,
         _host_id: &str,
+        caller: Option<PolicyRequestCaller>,
     ) -> anyhow::Result<Bytes> {
         let StopHostCommand { timeout, .. } = serde_json::from_slice(payload.as_ref())
             .context("failed to deserialize stop command")?;
 
         debug!(?timeout, "handling stop host");
 
+        self.ensure_ctl_permitted(
+            caller,
+            PolicyRequestTarget::default(),
+            PolicyAction::StopHost,
+        )
+        .await?;
+
         self.heartbeat.abort();
         self.data_watch.abort();
         self.config_data_watch.abort();
@@ -2762,12 +3001,16 @@ impl Host {
 
         let host_id = host_id.to_string();
         let annotations: Annotations = annotations.unwrap_or_default().into_iter().collect();
+        // Reject invalid resource limits before the command is accepted
+        self.actor_config(&annotations)?;
         spawn(async move {
             if let Err(e) = self
                 .handle_scale_actor_task(&actor_ref, &host_id, max_instances, annotations)
                 .await
             {
                 error!(%actor_ref, err = ?e, "failed to scale actor");
+            } else {
+                self.snapshot_state().await;
             }
         });
         Ok(ACCEPTED.into())
@@ -2785,7 +3028,7 @@ impl Host {
     ) -> anyhow::Result<()> {
         trace!(actor_ref, max_instances, "scale actor task");
 
-        let actor = self.fetch_actor(actor_ref).await?;
+        let actor = self.fetch_actor(actor_ref, &annotations).await?;
         let claims = actor.claims().context("claims missing")?;
         let actor_id = claims.subject.clone();
         let resp = self
@@ -3025,7 +3268,9 @@ impl Host {
         let matching_instance = matching_instance(&all_instances, &annotations)
             .context("actor instance with matching annotations not found")?;
 
-        let new_actor = self.fetch_actor(&new_actor_ref).await?;
+        let new_actor = self
+            .fetch_actor(&new_actor_ref, &matching_instance.annotations)
+            .await?;
         let new_claims = new_actor
             .claims()
             .context("claims missing from new actor")?;
@@ -3163,10 +3408,24 @@ impl Host {
                     .try_into()
                     .context("failed to convert rpc_timeout to u64")?,
             );
-            let otel_config = OtelConfig {
-                traces_exporter: self.host_config.otel_config.traces_exporter.clone(),
-                exporter_otlp_endpoint: self.host_config.otel_config.exporter_otlp_endpoint.clone(),
-            };
+            let mut resource_attributes = self.host_config.otel_config.resource_attributes.clone();
+            resource_attributes.extend([
+                ("host.id".to_string(), self.host_key.public_key()),
+                ("provider.id".to_string(), claims.subject.clone()),
+                ("provider.link_name".to_string(), link_name.to_string()),
+            ]);
+            // Providers do not serve metrics and would contend for the Prometheus port
+            let mut otel_config = OtelConfig::default();
+            otel_config
+                .traces_exporter
+                .clone_from(&self.host_config.otel_config.traces_exporter);
+            otel_config
+                .exporter_otlp_endpoint
+                .clone_from(&self.host_config.otel_config.exporter_otlp_endpoint);
+            otel_config
+                .logs_exporter
+                .clone_from(&self.host_config.otel_config.logs_exporter);
+            otel_config.resource_attributes = resource_attributes;
             // TODO: set back to Some(self.host_config.log_level.clone()) once all providers can be
             // assumed to be built using the new SDK. Providers built using wasmbus-rpc <= 0.15
             // ignore RUST_LOG when log_level is set
@@ -3182,7 +3441,7 @@ impl Host {
                 instance_id: Uuid::from_u128(id.into()).to_string(),
                 provider_key: claims.subject.clone(),
                 link_definitions,
-                config_json: configuration,
+                config_json: configuration.clone(),
                 default_rpc_timeout_ms,
                 cluster_issuers: self.cluster_issuers.clone(),
                 invocation_seed,
@@ -3253,6 +3512,7 @@ impl Host {
             let rpc_nats = self.rpc_nats.clone();
             let ctl_nats = self.ctl_nats.clone();
             let event_builder = self.event_builder.clone();
+            let metrics = self.metrics.clone();
             // NOTE: health_ prefix here is to allow us to move the variables into the closure
             let health_lattice = self.host_config.lattice.clone();
             let health_provider_id = claims.subject.to_string();
@@ -3340,6 +3600,7 @@ impl Host {
                         exit_status = child.wait() => match exit_status {
                             Ok(status) => {
                                 debug!("`{}` exited with `{status:?}`", path.display());
+                                metrics.record_provider_exit(&health_provider_id, &health_link_name, status.success());
                                 break;
                             }
                             Err(e) => {
@@ -3351,6 +3612,8 @@ impl Host {
                 }
             });
             info!(provider_ref, link_name, "provider started");
+            self.metrics
+                .record_provider_start(&claims.subject, link_name);
             self.publish_event(
                 "provider_started",
                 event::provider_started(
@@ -3367,6 +3630,7 @@ impl Host {
                 child,
                 id,
                 annotations,
+                configuration,
             });
         } else {
             bail!("provider is already running")
@@ -3413,6 +3677,8 @@ impl Host {
                 {
                     error!(?err, "failed to publish provider_start_failed event");
                 }
+            } else {
+                self.snapshot_state().await;
             }
         });
         Ok(ACCEPTED.into())
@@ -3593,9 +3859,22 @@ impl Host {
     }
 
     #[instrument(level = "debug", skip_all)]
-    async fn handle_label_put(&self, payload: impl AsRef<[u8]>) -> anyhow::Result<Bytes> {
+    async fn handle_label_put(
+        &self,
+        payload: impl AsRef<[u8]>,
+        caller: Option<PolicyRequestCaller>,
+    ) -> anyhow::Result<Bytes> {
         let HostLabel { key, value } = serde_json::from_slice(payload.as_ref())
             .context("failed to deserialize put label request")?;
+        self.ensure_ctl_permitted(
+            caller,
+            PolicyRequestTarget {
+                key: Some(key.clone()),
+                ..Default::default()
+            },
+            PolicyAction::PutLabel,
+        )
+        .await?;
         let mut labels = self.labels.write().await;
         match labels.entry(key) {
             Entry::Occupied(mut entry) => {
@@ -3607,16 +3886,31 @@ impl Host {
                 entry.insert(value);
             }
         }
+        self.policy_manager.update_labels(labels.clone()).await;
         Ok(ACCEPTED.into())
     }
 
     #[instrument(level = "debug", skip_all)]
-    async fn handle_label_del(&self, payload: impl AsRef<[u8]>) -> anyhow::Result<Bytes> {
+    async fn handle_label_del(
+        &self,
+        payload: impl AsRef<[u8]>,
+        caller: Option<PolicyRequestCaller>,
+    ) -> anyhow::Result<Bytes> {
         let HostLabel { key, .. } = serde_json::from_slice(payload.as_ref())
             .context("failed to deserialize delete label request")?;
+        self.ensure_ctl_permitted(
+            caller,
+            PolicyRequestTarget {
+                key: Some(key.clone()),
+                ..Default::default()
+            },
+            PolicyAction::DeleteLabel,
+        )
+        .await?;
         let mut labels = self.labels.write().await;
         if labels.remove(&key).is_some() {
             info!(key, "removed label");
+            self.policy_manager.update_labels(labels.clone()).await;
         } else {
             warn!(key, "could not remove unset label");
         }
@@ -3624,7 +3918,11 @@ impl Host {
     }
 
     #[instrument(level = "debug", skip_all)]
-    async fn handle_linkdef_put(&self, payload: impl AsRef<[u8]>) -> anyhow::Result<Bytes> {
+    async fn handle_linkdef_put(
+        &self,
+        payload: impl AsRef<[u8]>,
+        caller: Option<PolicyRequestCaller>,
+    ) -> anyhow::Result<Bytes> {
         let payload = payload.as_ref();
         let LinkDefinition {
             actor_id,
@@ -3640,6 +3938,18 @@ impl Host {
             provider_id, link_name, contract_id, "handling put link definition"
         );
 
+        self.ensure_ctl_permitted(
+            caller,
+            PolicyRequestTarget {
+                public_key: Some(actor_id.clone()),
+                contract_id: Some(contract_id.clone()),
+                link_name: Some(link_name.clone()),
+                ..Default::default()
+            },
+            PolicyAction::PutLinkDefinition,
+        )
+        .await?;
+
         self.data
             .put(format!("LINKDEF_{id}"), Bytes::copy_from_slice(payload))
             .await
@@ -3648,7 +3958,11 @@ impl Host {
     }
 
     #[instrument(level = "debug", skip_all)]
-    async fn handle_linkdef_del(&self, payload: impl AsRef<[u8]>) -> anyhow::Result<Bytes> {
+    async fn handle_linkdef_del(
+        &self,
+        payload: impl AsRef<[u8]>,
+        caller: Option<PolicyRequestCaller>,
+    ) -> anyhow::Result<Bytes> {
         let RemoveLinkDefinitionRequest {
             actor_id,
             ref link_name,
@@ -3662,6 +3976,18 @@ impl Host {
             link_name, contract_id, "handling delete link definition"
         );
 
+        self.ensure_ctl_permitted(
+            caller,
+            PolicyRequestTarget {
+                public_key: Some(actor_id.clone()),
+                contract_id: Some(contract_id.clone()),
+                link_name: Some(link_name.clone()),
+                ..Default::default()
+            },
+            PolicyAction::DeleteLinkDefinition,
+        )
+        .await?;
+
         self.data
             .delete(format!("LINKDEF_{id}"))
             .await
@@ -3702,6 +4028,7 @@ impl Host {
         entity_id: &str,
         key: &str,
         data: Bytes,
+        caller: Option<PolicyRequestCaller>,
     ) -> anyhow::Result<Bytes> {
         debug!(%entity_id, %key, "handle config entry put");
         // Simple check for a valid entity key. A valid public nkey is 56 chars and it should start
@@ -3710,6 +4037,16 @@ impl Host {
         if entity_id.len() != 56 || (!entity_id.starts_with('M') && !entity_id.starts_with('V')) {
             bail!("Invalid entity ID. The entity ID must be a valid public nkey for an actor or provider");
         }
+        self.ensure_ctl_permitted(
+            caller,
+            PolicyRequestTarget {
+                public_key: Some(entity_id.to_string()),
+                key: Some(key.to_string()),
+                ..Default::default()
+            },
+            PolicyAction::PutConfig,
+        )
+        .await?;
         self.config_data
             .put(format!("{entity_id}_{key}"), data)
             .await
@@ -3724,9 +4061,25 @@ impl Host {
     }
 
     #[instrument(level = "debug", skip_all)]
-    async fn handle_config_delete(&self, entity_id: &str, key: &str) -> anyhow::Result<Bytes> {
+    async fn handle_config_delete(
+        &self,
+        entity_id: &str,
+        key: &str,
+        caller: Option<PolicyRequestCaller>,
+    ) -> anyhow::Result<Bytes> {
         debug!(%entity_id, %key, "handle config entry deletion");
 
+        self.ensure_ctl_permitted(
+            caller,
+            PolicyRequestTarget {
+                public_key: Some(entity_id.to_string()),
+                key: Some(key.to_string()),
+                ..Default::default()
+            },
+            PolicyAction::DeleteConfig,
+        )
+        .await?;
+
         self.config_data
             .purge(format!("{entity_id}_{key}"))
             .await
@@ -3739,7 +4092,11 @@ impl Host {
     }
 
     #[instrument(level = "debug", skip_all)]
-    async fn handle_config_clear(&self, entity_id: &str) -> anyhow::Result<Bytes> {
+    async fn handle_config_clear(
+        &self,
+        entity_id: &str,
+        caller: Option<PolicyRequestCaller>,
+    ) -> anyhow::Result<Bytes> {
         debug!(%entity_id, "handle config clear");
 
         // NOTE(thomastaylor312): I specifically made the decision not to key the current list of
@@ -3752,7 +4109,7 @@ impl Host {
         };
         let futs = all_data
             .keys()
-            .map(|key| self.handle_config_delete(entity_id, key));
+            .map(|key| self.handle_config_delete(entity_id, key, caller.clone()));
         futures::future::try_join_all(futs)
             .await
             .context("Unable to delete all config keys. Some keys may remain")?;
@@ -3802,6 +4159,39 @@ impl Host {
             .skip(1);
         trace!(%subject, "handling control interface request");
 
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|now| now.as_secs())
+            .unwrap_or_default();
+        let caller = match ctl_caller(
+            &subject,
+            message.headers.as_ref(),
+            &message.payload,
+            &self.ctl_caller_nonces,
+            now,
+        ) {
+            Ok(caller) => caller,
+            Err(err) => {
+                warn!(%subject, ?err, "rejecting control interface request with invalid caller identity");
+                if let Some(reply) = message.reply {
+                    let payload = match serde_json::to_vec(&CtlOperationAck {
+                        accepted: false,
+                        error: format!("{err:#}"),
+                    }) {
+                        Ok(payload) => payload,
+                        Err(err) => {
+                            error!(%subject, ?err, "failed to encode reply to control interface request");
+                            return;
+                        }
+                    };
+                    if let Err(err) = self.ctl_nats.publish(reply, payload.into()).await {
+                        error!(%subject, ?err, "failed to publish reply to control interface request");
+                    }
+                }
+                return;
+            }
+        };
+
         let res = match (parts.next(), parts.next(), parts.next(), parts.next()) {
             (Some("auction"), Some("actor"), None, None) => {
                 self.handle_auction_actor(message.payload).await.map(Some)
@@ -3813,26 +4203,35 @@ impl Host {
                 .handle_launch_provider(message.payload, host_id)
                 .await
                 .map(Some),
-            (Some("cmd"), Some(host_id), Some("sa"), None) => self
-                .handle_stop_actor(message.payload, host_id)
-                .await
-                .map(Some),
+            (Some("cmd"), Some(host_id), Some("sa"), None) => {
+                let res = self.handle_stop_actor(message.payload, host_id).await;
+                if res.is_ok() {
+                    self.snapshot_state().await;
+                }
+                res.map(Some)
+            }
             (Some("cmd"), Some(host_id), Some("scale"), None) => Arc::clone(&self)
                 .handle_scale_actor(message.payload, host_id)
                 .await
                 .map(Some),
-            (Some("cmd"), Some(host_id), Some("sp"), None) => self
-                .handle_stop_provider(message.payload, host_id)
-                .await
-                .map(Some),
+            (Some("cmd"), Some(host_id), Some("sp"), None) => {
+                let res = self.handle_stop_provider(message.payload, host_id).await;
+                if res.is_ok() {
+                    self.snapshot_state().await;
+                }
+                res.map(Some)
+            }
             (Some("cmd"), Some(host_id), Some("stop"), None) => self
-                .handle_stop_host(message.payload, host_id)
-                .await
-                .map(Some),
-            (Some("cmd"), Some(host_id), Some("upd"), None) => self
-                .handle_update_actor(message.payload, host_id)
+                .handle_stop_host(message.payload, host_id, caller)
                 .await
                 .map(Some),
+            (Some("cmd"), Some(host_id), Some("upd"), None) => {
+                let res = self.handle_update_actor(message.payload, host_id).await;
+                if res.is_ok() {
+                    self.snapshot_state().await;
+                }
+                res.map(Some)
+            }
             (Some("get"), Some(_host_id), Some("inv"), None) => {
                 self.handle_inventory().await.map(Some)
             }
@@ -3844,18 +4243,22 @@ impl Host {
             (Some("get"), Some("config"), Some(entity_id), None) => {
                 self.handle_config_get(entity_id).await.map(Some)
             }
-            (Some("labels"), Some(_host_id), Some("del"), None) => {
-                self.handle_label_del(message.payload).await.map(Some)
-            }
-            (Some("labels"), Some(_host_id), Some("put"), None) => {
-                self.handle_label_put(message.payload).await.map(Some)
-            }
-            (Some("linkdefs"), Some("put"), None, None) => {
-                self.handle_linkdef_put(message.payload).await.map(Some)
-            }
-            (Some("linkdefs"), Some("del"), None, None) => {
-                self.handle_linkdef_del(message.payload).await.map(Some)
-            }
+            (Some("labels"), Some(_host_id), Some("del"), None) => self
+                .handle_label_del(message.payload, caller)
+                .await
+                .map(Some),
+            (Some("labels"), Some(_host_id), Some("put"), None) => self
+                .handle_label_put(message.payload, caller)
+                .await
+                .map(Some),
+            (Some("linkdefs"), Some("put"), None, None) => self
+                .handle_linkdef_put(message.payload, caller)
+                .await
+                .map(Some),
+            (Some("linkdefs"), Some("del"), None, None) => self
+                .handle_linkdef_del(message.payload, caller)
+                .await
+                .map(Some),
             (Some("registries"), Some("put"), None, None) => {
                 self.handle_registries_put(message.payload).await.map(Some)
             }
@@ -3863,14 +4266,15 @@ impl Host {
                 self.handle_ping_hosts(message.payload).await.map(Some)
             }
             (Some("config"), Some("put"), Some(entity_id), Some(key)) => self
-                .handle_config_put(entity_id, key, message.payload)
+                .handle_config_put(entity_id, key, message.payload, caller)
+                .await
+                .map(Some),
+            (Some("config"), Some("del"), Some(entity_id), Some(key)) => self
+                .handle_config_delete(entity_id, key, caller)
                 .await
                 .map(Some),
-            (Some("config"), Some("del"), Some(entity_id), Some(key)) => {
-                self.handle_config_delete(entity_id, key).await.map(Some)
-            }
             (Some("config"), Some("clear"), Some(entity_id), None) => {
-                self.handle_config_clear(entity_id).await.map(Some)
+                self.handle_config_clear(entity_id, caller).await.map(Some)
             }
             _ => {
                 warn!(%subject, "received control interface request on unsupported subject");
@@ -3979,6 +4383,11 @@ impl Host {
                     public_key: ld.provider_id.clone(),
                 },
             );
+            let mut link_policies = actor.handler.link_policies.write().await;
+            link_policies
+                .entry(contract_id.clone())
+                .or_default()
+                .insert(ld.link_name.clone(), self.link_policy(ld));
         }
 
         if publish {
@@ -4002,6 +4411,23 @@ impl Host {
         Ok(())
     }
 
+    /// Returns the policy for invocations over the link `ld`, falling back to the host policy if
+    /// the link definition values are invalid
+    fn link_policy(&self, ld: &LinkDefinition) -> LinkPolicy {
+        self.host_config
+            .link_policy
+            .with_link_values(&ld.values)
+            .unwrap_or_else(|e| {
+                warn!(
+                    actor_id = ld.actor_id,
+                    provider_id = ld.provider_id,
+                    link_name = ld.link_name,
+                    "ignoring invalid link policy: {e:#}"
+                );
+                self.host_config.link_policy.clone()
+            })
+    }
+
     #[instrument(level = "debug", skip_all)]
     async fn process_linkdef_delete(
         &self,
@@ -4038,6 +4464,20 @@ impl Host {
             if let Some(links) = links.get_mut(contract_id) {
                 links.remove(link_name);
             }
+            let mut link_policies = actor.handler.link_policies.write().await;
+            if let Some(link_policies) = link_policies.get_mut(contract_id) {
+                link_policies.remove(link_name);
+            }
+        }
+        // Circuit breakers are shared by all links of actors to the provider with the same name
+        if !self
+            .links
+            .read()
+            .await
+            .values()
+            .any(|ld| ld.provider_id == *provider_id && ld.link_name == *link_name)
+        {
+            self.circuit_breakers.remove(provider_id, link_name);
         }
 
         if publish {
@@ -4150,6 +4590,10 @@ impl Host {
             (Operation::Delete, Some("CLAIMS"), Some(pubkey)) => {
                 self.process_claims_delete(pubkey, value).await
             }
+            (operation, Some(state::STATE_KEY_PREFIX), id) => {
+                trace!(?operation, id, "ignoring host state snapshot entry");
+                Ok(())
+            }
             (operation, Some("REFMAP"), id) => {
                 // TODO: process REFMAP entries
                 debug!(?operation, id, "ignoring REFMAP entry");
@@ -4529,6 +4973,88 @@ fn human_friendly_uptime(uptime: Duration) -> String {
     .to_string()
 }
 
+/// Nonces of the signed control interface requests accepted within
+/// [`CTL_CALLER_MAX_AGE`](wasmcloud_control_interface::CTL_CALLER_MAX_AGE), used to reject
+/// replayed requests
+#[derive(Default)]
+struct CtlCallerNonces(std::sync::Mutex<HashMap<String, u64>>);
+
+impl CtlCallerNonces {
+    /// Records the nonce of a request issued at `issued_at`, failing if the caller already used it.
+    /// Nonces of requests older than the maximum age are forgotten, since those requests are
+    /// rejected anyway
+    fn insert(
+        &self,
+        public_key: &str,
+        nonce: &str,
+        issued_at: u64,
+        now: u64,
+    ) -> anyhow::Result<()> {
+        let max_age = wasmcloud_control_interface::CTL_CALLER_MAX_AGE.as_secs();
+        let mut nonces = self
+            .0
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        nonces.retain(|_, issued_at| issued_at.saturating_add(max_age) >= now);
+        ensure!(
+            nonces
+                .insert(format!("{public_key}.{nonce}"), issued_at)
+                .is_none(),
+            "control interface request was already received"
+        );
+        Ok(())
+    }
+}
+
+/// Returns the verified identity of the caller of a control interface request, or None if the
+/// request is not signed. `now` is the current time in seconds since the Unix epoch
+fn ctl_caller(
+    subject: &str,
+    headers: Option<&async_nats::header::HeaderMap>,
+    payload: &[u8],
+    nonces: &CtlCallerNonces,
+    now: u64,
+) -> anyhow::Result<Option<PolicyRequestCaller>> {
+    let Some(headers) = headers else {
+        return Ok(None);
+    };
+    let (Some(public_key), Some(signature)) = (
+        headers.get(wasmcloud_control_interface::CTL_CALLER_HEADER),
+        headers.get(wasmcloud_control_interface::CTL_CALLER_SIGNATURE_HEADER),
+    ) else {
+        return Ok(None);
+    };
+    let public_key = public_key.to_string();
+    let issued_at: u64 = headers
+        .get(wasmcloud_control_interface::CTL_CALLER_ISSUED_AT_HEADER)
+        .context("missing control interface caller issue time")?
+        .as_str()
+        .parse()
+        .context("invalid control interface caller issue time")?;
+    let nonce = headers
+        .get(wasmcloud_control_interface::CTL_CALLER_NONCE_HEADER)
+        .context("missing control interface caller nonce")?
+        .as_str();
+    let signature = STANDARD
+        .decode(signature.as_str())
+        .context("failed to decode control interface caller signature")?;
+    KeyPair::from_public_key(&public_key)
+        .context("invalid control interface caller public key")?
+        .verify(
+            &wasmcloud_control_interface::ctl_caller_signing_input(
+                subject, issued_at, nonce, payload,
+            ),
+            &signature,
+        )
+        .context("invalid control interface caller signature")?;
+    ensure!(
+        now.abs_diff(issued_at) <= wasmcloud_control_interface::CTL_CALLER_MAX_AGE.as_secs(),
+        "control interface caller signature is expired or not yet valid"
+    );
+    nonces.insert(&public_key, nonce, issued_at, now)?;
+    Ok(Some(PolicyRequestCaller { public_key }))
+}
+
 fn injector_to_headers(injector: &TraceContextInjector) -> async_nats::header::HeaderMap {
     injector
         .iter()
@@ -4563,7 +5089,7 @@ mod test {
     use wasmcloud_core::{invocation_hash, WasmCloudEntity};
     use wasmcloud_tracing::context::TraceContextInjector;
 
-    use super::Invocation;
+    use super::{ctl_caller, CtlCallerNonces, Invocation};
 
     const CLUSTER_PUBKEY: &str = "CAQQHYABXBPDBZIGDZIT7E73HW66RPCFC3GGLQKSDDTVWUVOYZBYHUND";
     const CLUSTER_SEED: &str = "SCAIYCZTW775GJYX3MVWLURALVC3PULW43PTEKGH72JBMA3A7LOLGLQ2JA";
@@ -4573,6 +5099,87 @@ mod test {
     const PROVIDER_PUBKEY: &str = "VC3IJSRK3KIJUD5PQIEU2UNWT4PQCRYTAXFC4PDLTCMDX7L77YRUGCXW";
     const OUTSIDE_CLUSTER_PUBKEY: &str = "CAT4QMKWIUTIX5ZBNOT2ICJHCSVVHGHLOHSXDSS5P2MIWRXHYHANTJZQ";
 
+    fn signed_ctl_headers(
+        key: &KeyPair,
+        subject: &str,
+        issued_at: u64,
+        nonce: &str,
+        payload: &[u8],
+    ) -> async_nats::HeaderMap {
+        use base64::Engine;
+
+        let signature = key
+            .sign(&wasmcloud_control_interface::ctl_caller_signing_input(
+                subject, issued_at, nonce, payload,
+            ))
+            .expect("failed to sign request");
+        let mut headers = async_nats::HeaderMap::new();
+        headers.insert(
+            wasmcloud_control_interface::CTL_CALLER_HEADER,
+            key.public_key().as_str(),
+        );
+        headers.insert(
+            wasmcloud_control_interface::CTL_CALLER_SIGNATURE_HEADER,
+            base64::engine::general_purpose::STANDARD
+                .encode(signature)
+                .as_str(),
+        );
+        headers.insert(
+            wasmcloud_control_interface::CTL_CALLER_ISSUED_AT_HEADER,
+            issued_at.to_string().as_str(),
+        );
+        headers.insert(wasmcloud_control_interface::CTL_CALLER_NONCE_HEADER, nonce);
+        headers
+    }
+
+    #[test]
+    fn ctl_caller_rejects_replayed_requests() {
+        let key = KeyPair::new_user();
+        let nonces = CtlCallerNonces::default();
+        let subject = "wasmbus.ctl.v1.default.label.put";
+        let payload = br#"{"key":"zone","value":"edge"}"#;
+        let now = 1_700_000_000;
+
+        assert!(ctl_caller(subject, None, payload, &nonces, now)
+            .expect("unsigned requests are allowed")
+            .is_none());
+
+        let headers = signed_ctl_headers(&key, subject, now - 5, "nonce1", payload);
+        let caller = ctl_caller(subject, Some(&headers), payload, &nonces, now)
+            .expect("signed request should be accepted")
+            .expect("signed request should have a caller");
+        assert_eq!(caller.public_key, key.public_key());
+
+        // The same signed request can't be accepted twice
+        assert!(
+            ctl_caller(subject, Some(&headers), payload, &nonces, now + 1)
+                .is_err_and(|e| e.to_string().contains("already received"))
+        );
+
+        // Signatures are bound to the subject and payload
+        let headers = signed_ctl_headers(&key, subject, now, "nonce2", payload);
+        assert!(ctl_caller(
+            "wasmbus.ctl.v1.default.label.del",
+            Some(&headers),
+            payload,
+            &nonces,
+            now
+        )
+        .is_err());
+        assert!(ctl_caller(subject, Some(&headers), b"{}", &nonces, now).is_err());
+
+        // Old signatures are rejected, even with a new nonce
+        let headers = signed_ctl_headers(&key, subject, now - 120, "nonce3", payload);
+        assert!(ctl_caller(subject, Some(&headers), payload, &nonces, now)
+            .is_err_and(|e| e.to_string().contains("expired")));
+
+        // Nonces are only remembered while requests using them would be accepted
+        nonces
+            .insert(&key.public_key(), "nonce4", now + 120, now + 120)
+            .expect("new nonce should be accepted");
+        assert_eq!(nonces.0.lock().expect("nonces poisoned").len(), 1);
+    }
+
     #[test]
     #[allow(clippy::too_many_lines)]
     fn validate_antiforgery_catches_invalid_invocations() {
@@ -0,0 +1,206 @@
+use anyhow::{anyhow, Context as _};
+use bytes::Bytes;
+use futures::future::join_all;
+use futures::join;
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, instrument, warn};
+
+use super::{Annotations, Host, Provider, ProviderInstance};
+
+/// Prefix of the lattice data bucket key the state snapshot of a host is stored under, which is
+/// followed by the host public key
+pub(crate) const STATE_KEY_PREFIX: &str = "HOSTSTATE";
+
+/// Snapshot of the actors and providers running on a host
+#[derive(Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+struct HostState {
+    #[serde(default)]
+    actors: Vec<ActorState>,
+    #[serde(default)]
+    providers: Vec<ProviderState>,
+}
+
+/// Actor instance recorded in a [`HostState`]
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+struct ActorState {
+    image_ref: String,
+    max_instances: u32,
+    #[serde(default)]
+    annotations: Annotations,
+}
+
+/// Provider instance recorded in a [`HostState`]
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+struct ProviderState {
+    image_ref: String,
+    link_name: String,
+    #[serde(default)]
+    annotations: Annotations,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    configuration: Option<String>,
+}
+
+impl Host {
+    fn state_key(&self) -> String {
+        format!("{STATE_KEY_PREFIX}_{}", self.host_key.public_key())
+    }
+
+    /// Collects the actors and providers currently running on the host
+    async fn state(&self) -> HostState {
+        let mut actors = Vec::new();
+        for actor in self.actors.read().await.values() {
+            for (annotations, instance) in actor.instances.read().await.iter() {
+                actors.push(ActorState {
+                    image_ref: instance.image_reference.clone(),
+                    max_instances: instance.max_instances.get().try_into().unwrap_or(u32::MAX),
+                    annotations: annotations.clone(),
+                });
+            }
+        }
+        let providers = self
+            .providers
+            .read()
+            .await
+            .values()
+            .flat_map(
+                |Provider {
+                     image_ref,
+                     instances,
+                     ..
+                 }| {
+                    instances.iter().map(
+                        move |(
+                            link_name,
+                            ProviderInstance {
+                                annotations,
+                                configuration,
+                                ..
+                            },
+                        )| ProviderState {
+                            image_ref: image_ref.clone(),
+                            link_name: link_name.clone(),
+                            annotations: annotations.clone(),
+                            configuration: configuration.clone(),
+                        },
+                    )
+                },
+            )
+            .collect();
+        HostState { actors, providers }
+    }
+
+    /// Stores a snapshot of the actors and providers running on the host in the lattice data
+    /// bucket, if enabled. Failures are logged, since the snapshot is best-effort
+    #[instrument(level = "debug", skip_all)]
+    pub(crate) async fn snapshot_state(&self) {
+        if !self.host_config.snapshot_state {
+            return;
+        }
+        // Serialize snapshots, so that a stale snapshot cannot overwrite a more recent one
+        let _guard = self.state_snapshot.lock().await;
+        let state = self.state().await;
+        let res = match serde_json::to_vec(&state).context("failed to encode host state") {
+            Ok(buf) => self
+                .data
+                .put(self.state_key(), Bytes::from(buf))
+                .await
+                .map_err(|e| anyhow!(e).context("failed to store host state")),
+            Err(err) => Err(err),
+        };
+        if let Err(err) = res {
+            warn!(?err, "failed to snapshot host state");
+        }
+    }
+
+    /// Starts the actors and providers recorded in the snapshot of a previous run of the host.
+    /// Failures to start individual actors or providers are logged and do not fail the restore
+    #[instrument(level = "debug", skip_all)]
+    pub(crate) async fn restore_state(&self) -> anyhow::Result<()> {
+        let Some(buf) = self
+            .data
+            .get(self.state_key())
+            .await
+            .map_err(|e| anyhow!(e).context("failed to get host state snapshot"))?
+        else {
+            info!("no host state snapshot to restore");
+            return Ok(());
+        };
+        let HostState { actors, providers } =
+            serde_json::from_slice(&buf).context("failed to decode host state snapshot")?;
+        info!(
+            actors = actors.len(),
+            providers = providers.len(),
+            "restoring host state snapshot"
+        );
+        let host_id = self.host_key.public_key();
+        let host_id = host_id.as_str();
+        join!(
+            join_all(actors.into_iter().map(
+                |ActorState {
+                     image_ref,
+                     max_instances,
+                     annotations,
+                 }| async move {
+                    if let Err(err) = self
+                        .handle_scale_actor_task(&image_ref, host_id, max_instances, annotations)
+                        .await
+                    {
+                        error!(image_ref, ?err, "failed to restore actor");
+                    }
+                }
+            )),
+            join_all(providers.into_iter().map(
+                |ProviderState {
+                     image_ref,
+                     link_name,
+                     annotations,
+                     configuration,
+                 }| async move {
+                    if let Err(err) = self
+                        .handle_launch_provider_task(
+                            configuration,
+                            &link_name,
+                            &image_ref,
+                            annotations.into_iter().collect(),
+                            host_id,
+                        )
+                        .await
+                    {
+                        error!(image_ref, link_name, ?err, "failed to restore provider");
+                    }
+                }
+            )),
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn host_state_roundtrip() -> anyhow::Result<()> {
+        let state = HostState {
+            actors: vec![ActorState {
+                image_ref: "wasmcloud.azurecr.io/echo:0.3.8".into(),
+                max_instances: 10,
+                annotations: Annotations::from([("app".into(), "echo".into())]),
+            }],
+            providers: vec![ProviderState {
+                image_ref: "wasmcloud.azurecr.io/httpserver:0.19.1".into(),
+                link_name: "default".into(),
+                annotations: Annotations::default(),
+                configuration: Some(r#"{"port":8080}"#.into()),
+            }],
+        };
+        let buf = serde_json::to_vec(&state)?;
+        assert_eq!(serde_json::from_slice::<HostState>(&buf)?, state);
+
+        let state: HostState =
+            serde_json::from_str(r#"{"actors":[{"image_ref":"echo.wasm","max_instances":1}]}"#)?;
+        assert_eq!(state.actors[0].annotations, Annotations::default());
+        assert!(state.providers.is_empty());
+        Ok(())
+    }
+}
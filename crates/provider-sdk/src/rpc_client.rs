@@ -3,11 +3,18 @@ use crate::{
     rpc_topic,
 };
 
-use std::{fmt, sync::Arc, time::Duration};
+use std::{
+    fmt,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
 
 use async_nats::{Client, Subject};
 use futures::TryFutureExt;
 use sha2::Digest;
+use tokio::io::{AsyncRead, AsyncReadExt, ReadBuf};
 use tracing::{
     debug, error,
     field::{display, Empty},
@@ -16,7 +23,7 @@ use tracing::{
 use uuid::Uuid;
 use wascap::{jwt, prelude::Claims};
 use wasmcloud_core::{
-    chunking::{ChunkEndpoint, CHUNK_RPC_EXTRA_TIME, CHUNK_THRESHOLD_BYTES},
+    chunking::{ChunkEndpoint, ChunkedStream, CHUNK_RPC_EXTRA_TIME, CHUNK_THRESHOLD_BYTES},
     Invocation, InvocationResponse, WasmCloudEntity,
 };
 #[cfg(feature = "otel")]
@@ -134,25 +141,7 @@ impl RpcClient {
         let target_url = crate::url(&target, Some(&method));
         let topic = rpc_topic(&target, &self.lattice);
 
-        // Record all of the fields on the span. To avoid extra allocations, we are only going to
-        // record here after we generate/derive the values
-        let span = tracing::span::Span::current();
-        span.record("method", display(&method));
-        span.record("subject", &display(&subject));
-        span.record("issuer", &display(&issuer));
-        span.record("topic", &display(&topic));
-        if !origin.public_key.is_empty() {
-            span.record("sender_key", &display(&origin.public_key));
-        }
-        if !target.contract_id.is_empty() {
-            span.record("contract_id", &display(&target.contract_id));
-        }
-        if !target.link_name.is_empty() {
-            span.record("link_name", &display(&target.link_name));
-        }
-        if !target.public_key.is_empty() {
-            span.record("target_key", &display(&target.public_key));
-        }
+        record_rpc_fields(&method, &subject, &issuer, &topic, &origin, &target);
 
         let claims = Claims::<jwt::Invocation>::new(
             issuer.clone(),
@@ -234,6 +223,126 @@ impl RpcClient {
         Ok(inv_response)
     }
 
+    /// Send a wasmbus rpc message with a payload of `content_length` bytes read from `data`.
+    ///
+    /// Unlike [`send`](Self::send), a payload larger than [`CHUNK_THRESHOLD_BYTES`] is written to
+    /// the chunking store as it is read, without holding the whole payload in memory, and a
+    /// chunked response is returned as a stream instead of being read into the response `msg`.
+    /// The timeout, if any, overrides the configured timeout for this client.
+    #[instrument(level = "debug", skip(self, origin, target, method, data, timeout), fields( lattice_id = %self.lattice, method = Empty, subject = Empty, issuer = Empty, sender_key = Empty, contract_id = Empty, link_name = Empty, target_key = Empty, topic = Empty ))]
+    pub async fn send_stream(
+        &self,
+        origin: WasmCloudEntity,
+        target: WasmCloudEntity,
+        method: impl Into<String>,
+        data: impl AsyncRead + Unpin,
+        content_length: u64,
+        timeout: Option<Duration>,
+    ) -> InvocationResult<StreamingResponse> {
+        let method = method.into();
+        let origin_url = crate::url(&origin, None);
+        let subject = make_uuid();
+        let issuer = self.key.public_key();
+        let target_url = crate::url(&target, Some(&method));
+        let topic = rpc_topic(&target, &self.lattice);
+        record_rpc_fields(&method, &subject, &issuer, &topic, &origin, &target);
+
+        let mut data = HashingReader {
+            inner: data.take(content_length),
+            hasher: invocation_hasher(&target_url, &origin_url, &method),
+            len: 0,
+        };
+        let needs_chunking = content_length > CHUNK_THRESHOLD_BYTES as u64;
+        let msg = if needs_chunking {
+            debug!(invocation_id = %subject, %content_length, "chunkifying invocation stream");
+            if let Err(err) = self.chonky.chunkify(&subject, &mut data).await {
+                error!(%err, "chunking error");
+                return Err(InvocationError::Chunking(err.to_string()));
+            }
+            Vec::new()
+        } else {
+            // cap the allocation, rather than trusting the announced length of the payload
+            let mut msg = Vec::with_capacity(
+                usize::try_from(content_length)
+                    .unwrap_or(CHUNK_THRESHOLD_BYTES)
+                    .min(CHUNK_THRESHOLD_BYTES),
+            );
+            data.read_to_end(&mut msg)
+                .await
+                .map_err(|e| InvocationError::Chunking(e.to_string()))?;
+            msg
+        };
+        if data.len != content_length {
+            // the object of a short payload is never received, so nothing else would delete it
+            if needs_chunking {
+                self.delete_chunks(&subject).await;
+            }
+            return Err(InvocationError::Malformed(format!(
+                "invocation payload has {} bytes, expected {content_length}",
+                data.len
+            )));
+        }
+
+        let claims = Claims::<jwt::Invocation>::new(
+            issuer,
+            subject.clone(),
+            &target_url,
+            &origin_url,
+            &data_encoding::HEXUPPER.encode(data.hasher.finalize().as_slice()),
+        );
+        let mut invocation = Invocation {
+            origin,
+            target,
+            operation: method,
+            id: subject,
+            encoded_claims: claims.encode(&self.key).unwrap_or_default(),
+            host_id: self.host_id.clone(),
+            content_length,
+            #[cfg(feature = "otel")]
+            trace_context: TraceContextInjector::default_with_span().into(),
+            ..Default::default()
+        };
+        invocation.msg = msg;
+        let nats_body = crate::serialize(&invocation)?;
+
+        let timeout = timeout.or(self.timeout);
+        let timeout = if needs_chunking {
+            timeout.map(|t| t + CHUNK_RPC_EXTRA_TIME)
+        } else {
+            timeout
+        };
+
+        let payload = self
+            .request_timeout(topic, nats_body, timeout)
+            .await
+            .map_err(|err| {
+                error!(%err, "sending request");
+                err
+            })?;
+
+        let response = crate::deserialize::<InvocationResponse>(&payload)?;
+        let body =
+            if response.error.is_none() && response.content_length > response.msg.len() as u64 {
+                Some(
+                    self.chonky
+                        .get_unchunkified_response_stream(&response.invocation_id)
+                        .await
+                        .map_err(|e| InvocationError::Chunking(e.to_string()))?,
+                )
+            } else {
+                None
+            };
+
+        Ok(StreamingResponse { response, body })
+    }
+
+    /// Deletes the chunks of an invocation that will not be sent, logging any failure
+    async fn delete_chunks(&self, invocation_id: &str) {
+        if let Err(err) = self.chonky.delete(invocation_id).await {
+            error!(invocation_id, %err, "failed to delete chunks");
+        }
+    }
+
     /// Send a nats message and wait for the response.
     /// This can be used for general nats messages, not just wasmbus actor/provider messages.
     /// If this client has a default timeout, and a response is not received within
@@ -384,21 +493,90 @@ impl RpcClient {
     }
 }
 
+/// Response to [`RpcClient::send_stream`]
+pub struct StreamingResponse {
+    /// The invocation response. If the response was chunked, its `msg` is empty and the
+    /// message is read from `body` instead
+    pub response: InvocationResponse,
+    /// Stream of the chunked response message, with `response.content_length` bytes
+    pub body: Option<ChunkedStream>,
+}
+
+/// Records the fields of an rpc on the current span. To avoid extra allocations, this is only
+/// called after the values are generated/derived
+fn record_rpc_fields(
+    method: &str,
+    subject: &str,
+    issuer: &str,
+    topic: &str,
+    origin: &WasmCloudEntity,
+    target: &WasmCloudEntity,
+) {
+    let span = tracing::span::Span::current();
+    span.record("method", display(method));
+    span.record("subject", &display(subject));
+    span.record("issuer", &display(issuer));
+    span.record("topic", &display(topic));
+    if !origin.public_key.is_empty() {
+        span.record("sender_key", &display(&origin.public_key));
+    }
+    if !target.contract_id.is_empty() {
+        span.record("contract_id", &display(&target.contract_id));
+    }
+    if !target.link_name.is_empty() {
+        span.record("link_name", &display(&target.link_name));
+    }
+    if !target.public_key.is_empty() {
+        span.record("target_key", &display(&target.public_key));
+    }
+}
+
+/// Hasher for the invocation hash, to which the invocation args are added
+fn invocation_hasher(target_url: &str, origin_url: &str, method: &str) -> sha2::Sha256 {
+    let mut hasher = sha2::Sha256::new();
+    hasher.update(origin_url.as_bytes());
+    hasher.update(target_url.as_bytes());
+    hasher.update(method.as_bytes());
+    hasher
+}
+
 pub(crate) fn invocation_hash(
     target_url: &str,
     origin_url: &str,
     method: &str,
     args: &[u8],
 ) -> String {
-    let mut hasher = sha2::Sha256::new();
-    hasher.update(origin_url.as_bytes());
-    hasher.update(target_url.as_bytes());
-    hasher.update(method.as_bytes());
+    let mut hasher = invocation_hasher(target_url, origin_url, method);
     hasher.update(args);
     let digest = hasher.finalize();
     data_encoding::HEXUPPER.encode(digest.as_slice())
 }
 
+/// Reader that adds the bytes read to the invocation hash
+struct HashingReader<R> {
+    inner: R,
+    hasher: sha2::Sha256,
+    /// number of bytes read
+    len: u64,
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for HashingReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let start = buf.filled().len();
+        let poll = Pin::new(&mut self.inner).poll_read(cx, buf);
+        if let Poll::Ready(Ok(())) = poll {
+            let read = &buf.filled()[start..];
+            self.hasher.update(read);
+            self.len += read.len() as u64;
+        }
+        poll
+    }
+}
+
 /// Create a new random uuid for invocations.
 pub(crate) fn make_uuid() -> String {
     // uuid uses getrandom, which uses the operating system's RNG
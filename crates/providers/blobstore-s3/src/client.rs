@@ -2,16 +2,17 @@ use std::collections::HashMap;
 use std::num::{NonZeroU64, NonZeroUsize};
 use std::sync::{Arc, OnceLock};
 
-use anyhow::bail;
+use anyhow::{bail, Context as _};
 use aws_sdk_s3::error::SdkError;
 use aws_sdk_s3::operation::create_bucket::CreateBucketOutput;
+use aws_sdk_s3::operation::create_multipart_upload::CreateMultipartUploadOutput;
 use aws_sdk_s3::operation::head_bucket::HeadBucketError;
 use aws_sdk_s3::operation::head_object::{HeadObjectError, HeadObjectOutput};
 use aws_sdk_s3::operation::list_buckets::ListBucketsOutput;
-use aws_sdk_s3::types::ObjectIdentifier;
+use aws_sdk_s3::types::{CompletedMultipartUpload, CompletedPart, ObjectIdentifier};
 use aws_sdk_s3::Client as S3Client;
 use bytes::Bytes;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 use tracing::{debug, error, instrument, warn, Instrument};
 
 use wasmcloud_provider_wit_bindgen::deps::{
@@ -35,11 +36,31 @@ const DEFAULT_MAX_ITEMS: i32 = 1000;
 /// maximum size of message (in bytes) that we'll return from s3 (500MB)
 const DEFAULT_MAX_CHUNK_SIZE_BYTES: usize = 500 * 1024 * 1024;
 
+/// minimum size (in bytes) of all but the last part of a multipart upload, as required by s3 (5MiB)
+const MIN_PART_SIZE_BYTES: usize = 5 * 1024 * 1024;
+
 #[derive(Clone)]
 pub struct StorageClient {
     s3_client: S3Client,
     ld: Arc<LinkDefinition>,
     aliases: Arc<HashMap<String, String>>,
+    /// multipart uploads in progress, keyed by the stream ID returned from `put_object`
+    uploads: Arc<RwLock<HashMap<String, Arc<Mutex<MultipartUpload>>>>>,
+}
+
+/// State of a multipart upload started by a `put_object` request that is not the last chunk
+struct MultipartUpload {
+    bucket_id: String,
+    object_id: String,
+    upload_id: String,
+    /// parts uploaded so far
+    parts: Vec<CompletedPart>,
+    /// bytes received but not uploaded yet, since parts must be at least MIN_PART_SIZE_BYTES
+    buffer: Vec<u8>,
+    /// object offset of the next chunk expected
+    next_offset: u64,
+    /// set once the upload is completed or aborted
+    finished: bool,
 }
 
 /// Atomic that is used to change the max chunk size bytes
@@ -98,6 +119,7 @@ impl StorageClient {
             s3_client,
             ld: Arc::new(ld),
             aliases: Arc::new(aliases),
+            uploads: Arc::default(),
         }
     }
 
@@ -134,8 +156,13 @@ impl StorageClient {
     /// Perform any cleanup necessary for a link + s3 connection
     pub async fn close(&self) {
         debug!(actor_id = %self.ld.actor_id, "blobstore-s3 dropping linkdef");
-        // If there were any https clients, caches, or other link-specific data,
-        // we would delete those here
+        // Abort multipart uploads that will not receive further chunks, so that
+        // s3 does not keep (and charge for) their parts
+        let uploads: Vec<_> = self.uploads.write().await.drain().collect();
+        for (_, upload) in uploads {
+            let mut upload = upload.lock().await;
+            self.abort_multipart_upload(&mut upload).await;
+        }
     }
 
     /// Check whether a container exists
@@ -531,14 +558,13 @@ impl StorageClient {
     )]
     pub async fn put_object(&self, ctx: &Context, arg: &PutObjectRequest) -> PutObjectResponse {
         let bucket_id = self.unalias(&arg.chunk.container_id);
-        if !arg.chunk.is_last {
-            error!("put_object for multi-part upload: not implemented!");
-            return PutObjectResponse { stream_id: None };
-        }
         if arg.chunk.offset != 0 {
             error!("put_object with initial offset non-zero: not implemented!");
             return PutObjectResponse { stream_id: None };
         }
+        if !arg.chunk.is_last {
+            return self.start_multipart_upload(bucket_id, arg).await;
+        }
         if arg.chunk.bytes.is_empty() {
             error!("put_object with zero bytes");
             return PutObjectResponse { stream_id: None };
@@ -739,8 +765,198 @@ impl StorageClient {
         }
     }
 
-    pub async fn put_chunk(&self, _ctx: &Context, _arg: &PutChunkRequest) {
-        error!("put_chunk is unimplemented");
+    /// Starts a multipart upload for a `put_object` request with more chunks to follow,
+    /// returning the stream ID to send the remaining chunks with
+    async fn start_multipart_upload(
+        &self,
+        bucket_id: &str,
+        arg: &PutObjectRequest,
+    ) -> PutObjectResponse {
+        let upload_id = match self
+            .s3_client
+            .create_multipart_upload()
+            .bucket(bucket_id)
+            .key(&arg.chunk.object_id)
+            .set_content_type(arg.content_type.clone())
+            .set_content_encoding(arg.content_encoding.clone())
+            .send()
+            .await
+        {
+            Ok(CreateMultipartUploadOutput {
+                upload_id: Some(upload_id),
+                ..
+            }) => upload_id,
+            Ok(_) => {
+                error!("multipart upload created without upload ID");
+                return PutObjectResponse { stream_id: None };
+            }
+            Err(e) => {
+                error!(err = %e, "Error starting multipart upload");
+                return PutObjectResponse { stream_id: None };
+            }
+        };
+        let mut upload = MultipartUpload {
+            bucket_id: bucket_id.to_string(),
+            object_id: arg.chunk.object_id.clone(),
+            upload_id: upload_id.clone(),
+            parts: Vec::new(),
+            buffer: Vec::new(),
+            next_offset: 0,
+            finished: false,
+        };
+        if let Err(e) = self.write_chunk(&mut upload, &arg.chunk.bytes, 0).await {
+            error!(err = ?e, "Error uploading initial chunk");
+            self.abort_multipart_upload(&mut upload).await;
+            return PutObjectResponse { stream_id: None };
+        }
+        // The upload ID is unique, so it doubles as the stream ID
+        self.uploads
+            .write()
+            .await
+            .insert(upload_id.clone(), Arc::new(Mutex::new(upload)));
+        PutObjectResponse {
+            stream_id: Some(upload_id),
+        }
+    }
+
+    /// Uploads a chunk of a multipart upload started by `put_object`. The upload is completed
+    /// with the last chunk, or aborted if `cancel_and_remove` is set or uploading fails.
+    #[instrument(
+        level = "debug",
+        skip(self, ctx, arg),
+        fields(actor_id = ?ctx.actor, stream_id = ?arg.stream_id, offset = %arg.chunk.offset, is_last = %arg.chunk.is_last)
+    )]
+    pub async fn put_chunk(&self, ctx: &Context, arg: &PutChunkRequest) {
+        let Some(stream_id) = &arg.stream_id else {
+            error!("put_chunk without stream_id");
+            return;
+        };
+        let Some(upload) = self.uploads.read().await.get(stream_id).cloned() else {
+            error!("put_chunk for unknown stream [{stream_id}]");
+            return;
+        };
+        let mut upload = upload.lock().await;
+        if upload.finished {
+            error!("put_chunk for finished stream [{stream_id}]");
+            return;
+        }
+
+        if arg.cancel_and_remove {
+            debug!("cancelling multipart upload");
+            self.uploads.write().await.remove(stream_id);
+            self.abort_multipart_upload(&mut upload).await;
+            return;
+        }
+
+        let res = match self
+            .write_chunk(&mut upload, &arg.chunk.bytes, arg.chunk.offset)
+            .await
+        {
+            Ok(()) if arg.chunk.is_last => self.complete_multipart_upload(&mut upload).await,
+            res => res,
+        };
+        match res {
+            Ok(()) if !upload.finished => {}
+            Ok(()) => {
+                debug!("multipart upload completed");
+                self.uploads.write().await.remove(stream_id);
+            }
+            Err(e) => {
+                error!(err = ?e, "Error uploading chunk, aborting multipart upload");
+                self.uploads.write().await.remove(stream_id);
+                self.abort_multipart_upload(&mut upload).await;
+            }
+        }
+    }
+
+    /// Buffers the bytes of a chunk at `offset`, uploading them as a part once enough bytes
+    /// have been received
+    async fn write_chunk(
+        &self,
+        upload: &mut MultipartUpload,
+        bytes: &[u8],
+        offset: u64,
+    ) -> anyhow::Result<()> {
+        if offset != upload.next_offset {
+            bail!(
+                "expected chunk at offset {}, received chunk at offset {offset}",
+                upload.next_offset
+            );
+        }
+        upload.buffer.extend_from_slice(bytes);
+        upload.next_offset += bytes.len() as u64;
+        if upload.buffer.len() >= MIN_PART_SIZE_BYTES {
+            self.upload_part(upload).await?;
+        }
+        Ok(())
+    }
+
+    /// Uploads the buffered bytes as the next part
+    async fn upload_part(&self, upload: &mut MultipartUpload) -> anyhow::Result<()> {
+        let part_number =
+            i32::try_from(upload.parts.len() + 1).context("too many parts in upload")?;
+        let body = std::mem::take(&mut upload.buffer);
+        let output = self
+            .s3_client
+            .upload_part()
+            .bucket(&upload.bucket_id)
+            .key(&upload.object_id)
+            .upload_id(&upload.upload_id)
+            .part_number(part_number)
+            .body(ByteStream::from(body))
+            .send()
+            .await
+            .with_context(|| format!("failed to upload part {part_number}"))?;
+        upload.parts.push(
+            CompletedPart::builder()
+                .set_e_tag(output.e_tag)
+                .part_number(part_number)
+                .build(),
+        );
+        Ok(())
+    }
+
+    /// Uploads the remaining buffered bytes as the last part and completes the upload
+    async fn complete_multipart_upload(&self, upload: &mut MultipartUpload) -> anyhow::Result<()> {
+        if !upload.buffer.is_empty() || upload.parts.is_empty() {
+            self.upload_part(upload).await?;
+        }
+        self.s3_client
+            .complete_multipart_upload()
+            .bucket(&upload.bucket_id)
+            .key(&upload.object_id)
+            .upload_id(&upload.upload_id)
+            .multipart_upload(
+                CompletedMultipartUpload::builder()
+                    .set_parts(Some(std::mem::take(&mut upload.parts)))
+                    .build(),
+            )
+            .send()
+            .await
+            .context("failed to complete multipart upload")?;
+        upload.finished = true;
+        Ok(())
+    }
+
+    /// Aborts the upload, removing any parts uploaded so far
+    async fn abort_multipart_upload(&self, upload: &mut MultipartUpload) {
+        upload.finished = true;
+        if let Err(e) = self
+            .s3_client
+            .abort_multipart_upload()
+            .bucket(&upload.bucket_id)
+            .key(&upload.object_id)
+            .upload_id(&upload.upload_id)
+            .send()
+            .await
+        {
+            error!(
+                err = %e,
+                bucket_id = %upload.bucket_id,
+                object_id = %upload.object_id,
+                "Error aborting multipart upload"
+            );
+        }
     }
 
     /// Retrieves metadata about the object
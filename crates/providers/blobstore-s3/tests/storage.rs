@@ -32,8 +32,8 @@ use std::collections::HashMap;
 use std::env;
 
 use wasmcloud_provider_blobstore_s3::{
-    Chunk, ContainerObjectSelector, GetObjectRequest, ListObjectsRequest, PutObjectRequest,
-    RemoveObjectsRequest, StorageClient, StorageConfig,
+    Chunk, ContainerObjectSelector, GetObjectRequest, ListObjectsRequest, PutChunkRequest,
+    PutObjectRequest, RemoveObjectsRequest, StorageClient, StorageConfig,
 };
 use wasmcloud_provider_wit_bindgen::deps::wasmcloud_provider_sdk::Context;
 
@@ -410,3 +410,178 @@ async fn test_get_object_chunks() {
         .await;
     assert_eq!(obj.initial_chunk.unwrap().bytes.len(), 300);
 }
+
+/// Tests
+/// - put_object with multiple chunks
+/// - put_chunk
+#[tokio::test]
+async fn test_multipart_upload() {
+    let s3 = test_client().await;
+    let ctx = Context::default();
+    let num = rand::random::<u64>();
+    let bucket = format!("test.multipart.{}", num);
+
+    s3.create_container(&ctx, &bucket).await;
+
+    // chunks add up to more than the minimum part size of 5MiB, so that the object
+    // is uploaded in two parts
+    let chunks = [
+        vec![b'a'; 3 * 1024 * 1024],
+        vec![b'b'; 3 * 1024 * 1024],
+        vec![b'c'; 1024],
+    ];
+    let resp = s3
+        .put_object(
+            &ctx,
+            &PutObjectRequest {
+                chunk: Chunk {
+                    bytes: chunks[0].clone(),
+                    container_id: bucket.clone(),
+                    is_last: false,
+                    object_id: "object.multipart".to_string(),
+                    offset: 0,
+                },
+                content_encoding: None,
+                content_type: Some("application/octet-stream".to_string()),
+            },
+        )
+        .await;
+    let stream_id = resp
+        .stream_id
+        .expect("multipart upload should return a stream ID");
+
+    let mut offset = chunks[0].len() as u64;
+    for (i, bytes) in chunks.iter().enumerate().skip(1) {
+        s3.put_chunk(
+            &ctx,
+            &PutChunkRequest {
+                chunk: Chunk {
+                    bytes: bytes.clone(),
+                    container_id: bucket.clone(),
+                    is_last: i == chunks.len() - 1,
+                    object_id: "object.multipart".to_string(),
+                    offset,
+                },
+                stream_id: Some(stream_id.clone()),
+                cancel_and_remove: false,
+            },
+        )
+        .await;
+        offset += bytes.len() as u64;
+    }
+
+    let info = s3
+        .get_object_info(
+            &ctx,
+            &ContainerObjectSelector {
+                container_id: bucket.clone(),
+                object_id: "object.multipart".to_string(),
+            },
+        )
+        .await;
+    assert_eq!(
+        info.content_length, offset,
+        "uploaded object has all chunks"
+    );
+    assert_eq!(
+        info.content_type.as_deref(),
+        Some("application/octet-stream")
+    );
+
+    let obj = s3
+        .get_object(
+            &ctx,
+            &GetObjectRequest {
+                container_id: bucket.clone(),
+                object_id: "object.multipart".to_string(),
+                range_start: Some(offset - 1025),
+                range_end: Some(offset - 1),
+            },
+        )
+        .await;
+    let mut expected = vec![b'b'];
+    expected.extend_from_slice(&chunks[2]);
+    assert_eq!(obj.initial_chunk.unwrap().bytes, expected);
+
+    s3.remove_objects(
+        &ctx,
+        &RemoveObjectsRequest {
+            container_id: bucket.clone(),
+            objects: vec!["object.multipart".to_string()],
+        },
+    )
+    .await;
+    assert!(
+        s3.remove_containers(&ctx, &vec![bucket])
+            .await
+            .iter()
+            .all(|result| result.success),
+        "no errors while removing containers",
+    )
+}
+
+/// Tests
+/// - put_chunk with cancel_and_remove
+#[tokio::test]
+async fn test_cancel_multipart_upload() {
+    let s3 = test_client().await;
+    let ctx = Context::default();
+    let num = rand::random::<u64>();
+    let bucket = format!("test.cancel.{}", num);
+
+    s3.create_container(&ctx, &bucket).await;
+
+    let stream_id = s3
+        .put_object(
+            &ctx,
+            &PutObjectRequest {
+                chunk: Chunk {
+                    bytes: b"hello-".to_vec(),
+                    container_id: bucket.clone(),
+                    is_last: false,
+                    object_id: "object.cancelled".to_string(),
+                    offset: 0,
+                },
+                content_encoding: None,
+                content_type: None,
+            },
+        )
+        .await
+        .stream_id
+        .expect("multipart upload should return a stream ID");
+
+    s3.put_chunk(
+        &ctx,
+        &PutChunkRequest {
+            chunk: Chunk {
+                bytes: b"world!".to_vec(),
+                container_id: bucket.clone(),
+                is_last: true,
+                object_id: "object.cancelled".to_string(),
+                offset: 6,
+            },
+            stream_id: Some(stream_id),
+            cancel_and_remove: true,
+        },
+    )
+    .await;
+
+    assert!(
+        !s3.object_exists(
+            &ctx,
+            &ContainerObjectSelector {
+                container_id: bucket.clone(),
+                object_id: "object.cancelled".to_string(),
+            },
+        )
+        .await,
+        "cancelled object should not exist"
+    );
+    assert!(
+        s3.remove_containers(&ctx, &vec![bucket])
+            .await
+            .iter()
+            .all(|result| result.success),
+        "no errors while removing containers",
+    )
+}
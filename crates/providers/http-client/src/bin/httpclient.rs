@@ -7,7 +7,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // listens to lattice rpcs, handles actor links,
     // and returns only when it receives a shutdown message
     wasmcloud_provider_sdk::start_provider(
-        HttpClientProvider {},
+        HttpClientProvider::default(),
         Some("http-client-provider".to_string()),
     )?;
 
@@ -1,16 +1,22 @@
 use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::Arc;
 
 use anyhow::Context as _;
 use anyhow::Result;
 use http::{HeaderMap, HeaderName, HeaderValue};
-use tracing::{error, instrument, trace, warn};
+use reqwest::Url;
+use tokio::sync::RwLock;
+use tracing::{error, info, instrument, trace, warn};
 
 use wasmcloud_provider_wit_bindgen::deps::{
     async_trait::async_trait, wasmcloud_provider_sdk::core::LinkDefinition,
     wasmcloud_provider_sdk::Context,
 };
 
+mod config;
+pub use config::{HostPattern, LinkConfig, UrlPattern};
+
 wasmcloud_provider_wit_bindgen::generate!({
     impl_struct: HttpClientProvider,
     contract: "wasmcloud:httpclient",
@@ -20,7 +26,32 @@ wasmcloud_provider_wit_bindgen::generate!({
 
 /// HTTP client capability provider implementation struct
 #[derive(Default, Clone)]
-pub struct HttpClientProvider;
+pub struct HttpClientProvider {
+    /// Per-actor http clients and outbound policies
+    actors: Arc<RwLock<HashMap<String, LinkClient>>>,
+}
+
+/// Http client of a link, configured with its settings
+#[derive(Clone)]
+struct LinkClient {
+    config: Arc<LinkConfig>,
+    client: reqwest::Client,
+}
+
+impl HttpClientProvider {
+    /// Retrieve the per-actor [`LinkClient`] for a given link context
+    async fn client(&self, ctx: &Context) -> Result<LinkClient> {
+        let actor_id = ctx.actor.as_ref().context("no actor in request")?;
+        let client = self
+            .actors
+            .read()
+            .await
+            .get(actor_id)
+            .with_context(|| format!("actor not linked: {actor_id}"))?
+            .clone();
+        Ok(client)
+    }
+}
 
 /// Implement the [httpclient contract](https://github.com/wasmCloud/interfaces/blob/main/httpclient)
 /// represented by the WIT interface @ `wit/provider-httpclient.wit`
@@ -32,17 +63,36 @@ impl WasmcloudHttpclientHttpClient for HttpClientProvider {
     /// error sending the request. If the remote server returned an http
     /// error (status other than 2xx), returns Ok with the status code and
     /// body returned from the remote server.
-    #[instrument(level = "debug", skip(self, _ctx, req), fields(actor_id = ?_ctx.actor, method = %req.method, url = %req.url))]
-    async fn request(&self, _ctx: Context, req: HttpRequest) -> HttpResponse {
+    ///
+    /// Requests to URLs that are not allowed by the outbound policy of the
+    /// calling actor's link are refused with status 403, without being sent.
+    #[instrument(level = "debug", skip(self, ctx, req), fields(actor_id = ?ctx.actor, method = %req.method, url = %req.url))]
+    async fn request(&self, ctx: Context, req: HttpRequest) -> HttpResponse {
+        let LinkClient { config, client } = match self.client(&ctx).await {
+            Ok(client) => client,
+            Err(e) => {
+                error!("failed to retrieve client: {e}");
+                return error_response(500);
+            }
+        };
+
+        let url = match Url::parse(&req.url) {
+            Ok(url) => url,
+            Err(e) => {
+                error!("failed to parse url: {}:{e}", &req.url);
+                return error_response(500);
+            }
+        };
+        if !config.allows(&url) {
+            warn!("request denied by the outbound policy of the link");
+            return error_response(403);
+        }
+
         let headers: HeaderMap = match build_http_header_map(&req.headers) {
             Ok(headers) => headers,
             Err(e) => {
                 error!("failed to build header map from request headers: {e}");
-                return HttpResponse {
-                    body: Vec::new(),
-                    header: HashMap::new(),
-                    status_code: 500,
-                };
+                return error_response(500);
             }
         };
 
@@ -50,18 +100,14 @@ impl WasmcloudHttpclientHttpClient for HttpClientProvider {
             Ok(method) => method,
             Err(e) => {
                 error!("failed to convert method: {}:{e}", &req.method);
-                return HttpResponse {
-                    body: Vec::new(),
-                    header: HashMap::new(),
-                    status_code: 500,
-                };
+                return error_response(500);
             }
         };
 
         trace!("forwarding {} request to {}", &req.method, &req.url);
         // Perform request to upstream server that was requested by the actor
-        let response = match reqwest::Client::new()
-            .request(method, &req.url)
+        let response = match client
+            .request(method, url)
             .headers(headers)
             .body(req.body)
             .send()
@@ -78,11 +124,7 @@ impl WasmcloudHttpclientHttpClient for HttpClientProvider {
                     error = %e,
                     "httpclient network error attempting to send"
                 );
-                return HttpResponse {
-                    body: Vec::new(),
-                    header: HashMap::new(),
-                    status_code: 500,
-                };
+                return error_response(500);
             }
         };
 
@@ -93,11 +135,7 @@ impl WasmcloudHttpclientHttpClient for HttpClientProvider {
             Ok(resp_body) => resp_body,
             Err(e) => {
                 error!("failed reading response body bytes: {e}");
-                return HttpResponse {
-                    body: Vec::new(),
-                    header: HashMap::new(),
-                    status_code: 500,
-                };
+                return error_response(500);
             }
         };
 
@@ -130,20 +168,52 @@ impl WasmcloudCapabilityProvider for HttpClientProvider {
     /// If the link is allowed, return true, otherwise return false to deny the link.
     #[instrument(level = "debug", skip(self, ld), fields(actor_id = %ld.actor_id))]
     async fn put_link(&self, ld: &LinkDefinition) -> bool {
-        // Accept all links that are put without saving any information
+        let config = match LinkConfig::from_values(&HashMap::from_iter(ld.values.iter().cloned())) {
+            Ok(config) => Arc::new(config),
+            Err(e) => {
+                error!(error = %e, "failed to read link config");
+                return false;
+            }
+        };
+        let client = match config.build_client() {
+            Ok(client) => client,
+            Err(e) => {
+                error!(error = %e, "failed to create http client");
+                return false;
+            }
+        };
+        info!(
+            allowed_hosts = config.allowed_hosts.len(),
+            allowed_urls = config.allowed_urls.len(),
+            "httpclient configured link"
+        );
+
+        self.actors
+            .write()
+            .await
+            .insert(ld.actor_id.to_string(), LinkClient { config, client });
         true
     }
 
     /// Handle notification that a link is dropped - close the connection
     #[instrument(level = "info", skip(self))]
     async fn delete_link(&self, actor_id: &str) {
-        // Deleting links is a no-op since no link information was saved
+        self.actors.write().await.remove(actor_id);
     }
 
     /// Handle shutdown request by closing all connections
     #[instrument(level = "debug", skip(self))]
     async fn shutdown(&self) {
-        // Shutting down is a no-op since no link information was saved
+        self.actors.write().await.clear();
+    }
+}
+
+/// Response returned to the actor when the request could not be sent
+fn error_response(status_code: u16) -> HttpResponse {
+    HttpResponse {
+        body: Vec::new(),
+        header: HashMap::new(),
+        status_code,
     }
 }
 
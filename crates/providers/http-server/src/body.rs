@@ -0,0 +1,287 @@
+//! Streaming of request and response bodies that are too large to send inside an invocation.
+//!
+//! Bodies of up to [`CHUNK_THRESHOLD_BYTES`] are buffered and sent in the invocation message.
+//! Larger request bodies with a known length are written from the connection to the lattice's
+//! chunking store while they are received, and chunked actor responses are read from the
+//! chunking store while they are sent to the client, so the provider holds neither in memory.
+
+use std::fmt;
+use std::io::Cursor;
+use std::pin::Pin;
+
+use bytes::Buf;
+use futures::{Stream, TryStreamExt};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio_util::io::StreamReader;
+
+use wasmcloud_provider_wit_bindgen::deps::{
+    wasmcloud_provider_sdk, wasmcloud_provider_sdk::core::chunking::CHUNK_THRESHOLD_BYTES,
+    wasmcloud_provider_sdk::error::InvocationError,
+};
+
+use crate::{HttpRequest, HttpResponse};
+
+/// Serialized empty binary, the value of an empty body in a serialized request or response
+const EMPTY_BIN: [u8; 2] = [0xc4, 0x00];
+
+/// Number of bytes read at a time from a chunked response until its head is parsed
+const HEAD_READ_BYTES: u64 = 64 * 1024;
+
+/// Body of a request or response that is streamed instead of being held in memory
+pub struct BodyStream {
+    /// length of the body in bytes
+    pub len: u64,
+    pub reader: Pin<Box<dyn AsyncRead + Send>>,
+}
+
+impl BodyStream {
+    /// Create a body that reads `len` bytes from a stream of request body data
+    pub(crate) fn from_stream<S, B>(len: u64, stream: S) -> Self
+    where
+        S: Stream<Item = Result<B, warp::Error>> + Send + 'static,
+        B: Buf + Send + 'static,
+    {
+        let stream = stream.map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e));
+        Self {
+            len,
+            reader: Box::pin(StreamReader::new(Box::pin(stream)).take(len)),
+        }
+    }
+}
+
+impl fmt::Debug for BodyStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BodyStream")
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+/// Returns true if a request body of `len` bytes is streamed, rather than sent in the invocation
+pub(crate) fn is_streamed(len: u64) -> bool {
+    len > CHUNK_THRESHOLD_BYTES as u64
+}
+
+/// Reads a request body of unknown length into memory.
+/// Returns None if the body is larger than `limit` bytes.
+pub(crate) async fn collect_body<S, B>(
+    stream: S,
+    limit: u64,
+) -> Result<Option<Vec<u8>>, warp::Error>
+where
+    S: Stream<Item = Result<B, warp::Error>>,
+    B: Buf,
+{
+    let mut stream = Box::pin(stream);
+    let mut body = Vec::new();
+    while let Some(mut buf) = stream.try_next().await? {
+        if body.len() as u64 + buf.remaining() as u64 > limit {
+            return Ok(None);
+        }
+        while buf.has_remaining() {
+            let chunk = buf.chunk();
+            body.extend_from_slice(chunk);
+            let len = chunk.len();
+            buf.advance(len);
+        }
+    }
+    Ok(Some(body))
+}
+
+/// Serializes a request without its body, followed by the header of a body of `body_len` bytes,
+/// so that the head followed by the body is the serialized request with that body.
+/// This relies on the body being the last field of [`HttpRequest`].
+pub(crate) fn request_head(req: &HttpRequest, body_len: u64) -> Result<Vec<u8>, InvocationError> {
+    let mut head = wasmcloud_provider_sdk::serialize(req)?;
+    if !req.body.is_empty() || !head.ends_with(&EMPTY_BIN) {
+        return Err(InvocationError::Malformed(
+            "request to stream must have an empty body".to_string(),
+        ));
+    }
+    head.truncate(head.len() - EMPTY_BIN.len());
+    let body_len = u32::try_from(body_len).map_err(|_| {
+        InvocationError::Malformed(format!("request body of {body_len} bytes is too large"))
+    })?;
+    rmp::encode::write_bin_len(&mut head, body_len)
+        .map_err(|e| InvocationError::Malformed(e.to_string()))?;
+    Ok(head)
+}
+
+/// Result of parsing the beginning of a serialized response
+#[derive(Debug)]
+enum ResponseHead {
+    /// The response fields before the body were parsed. The body of `body_len` bytes starts at
+    /// `body_offset` and ends the message.
+    Complete {
+        response: HttpResponse,
+        body_offset: usize,
+        body_len: u64,
+    },
+    /// More of the message is needed to parse the head
+    Incomplete,
+    /// The body is not the last field of the message, so the message must be read entirely
+    Unsupported,
+}
+
+/// Parses the fields preceding the body of a serialized [`HttpResponse`] of `msg_len` bytes,
+/// of which `buf` holds the beginning
+fn parse_response_head(buf: &[u8], msg_len: u64) -> ResponseHead {
+    let incomplete = || {
+        if (buf.len() as u64) < msg_len {
+            ResponseHead::Incomplete
+        } else {
+            ResponseHead::Unsupported
+        }
+    };
+    let mut rd = buf;
+    let Ok(fields) = rmp::decode::read_map_len(&mut rd) else {
+        return incomplete();
+    };
+    let mut response = HttpResponse {
+        status_code: 0,
+        header: Default::default(),
+        body: Vec::new(),
+    };
+    for field in 0..fields {
+        let Ok(key_len) = rmp::decode::read_str_len(&mut rd) else {
+            return incomplete();
+        };
+        let Some(key) = rd.get(..key_len as usize) else {
+            return incomplete();
+        };
+        rd = &rd[key_len as usize..];
+        match key {
+            b"statusCode" => match rmp_serde::from_read(&mut rd) {
+                Ok(status_code) => response.status_code = status_code,
+                Err(_) => return incomplete(),
+            },
+            b"header" => match rmp_serde::from_read(&mut rd) {
+                Ok(header) => response.header = header,
+                Err(_) => return incomplete(),
+            },
+            b"body" if field + 1 == fields => {
+                let Ok(body_len) = rmp::decode::read_bin_len(&mut rd) else {
+                    return incomplete();
+                };
+                let body_offset = buf.len() - rd.len();
+                if body_offset as u64 + body_len as u64 != msg_len {
+                    return ResponseHead::Unsupported;
+                }
+                return ResponseHead::Complete {
+                    response,
+                    body_offset,
+                    body_len: body_len.into(),
+                };
+            }
+            _ => return ResponseHead::Unsupported,
+        }
+    }
+    ResponseHead::Unsupported
+}
+
+/// Reads a chunked response, returning its body as a stream if the message format allows it,
+/// or the whole response otherwise
+pub(crate) async fn read_response(
+    mut stream: impl AsyncRead + Send + Unpin + 'static,
+    msg_len: u64,
+) -> Result<(HttpResponse, Option<BodyStream>), InvocationError> {
+    let chunking_error = |e: std::io::Error| InvocationError::Chunking(e.to_string());
+    let mut buf = Vec::new();
+    loop {
+        match parse_response_head(&buf, msg_len) {
+            ResponseHead::Complete {
+                response,
+                body_offset,
+                body_len,
+            } => {
+                let rest = buf.split_off(body_offset);
+                let body = BodyStream {
+                    len: body_len,
+                    reader: Box::pin(AsyncReadExt::chain(Cursor::new(rest), stream).take(body_len)),
+                };
+                return Ok((response, Some(body)));
+            }
+            ResponseHead::Unsupported => break,
+            ResponseHead::Incomplete => {}
+        }
+        let read = (&mut stream)
+            .take(HEAD_READ_BYTES)
+            .read_to_end(&mut buf)
+            .await
+            .map_err(chunking_error)?;
+        if read == 0 {
+            break;
+        }
+    }
+    stream.read_to_end(&mut buf).await.map_err(chunking_error)?;
+    Ok((wasmcloud_provider_sdk::deserialize(&buf)?, None))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn request(body: &[u8]) -> HttpRequest {
+        HttpRequest {
+            method: "PUT".to_string(),
+            path: "/upload".to_string(),
+            query_string: "name=file".to_string(),
+            header: [("content-type".to_string(), vec!["text/plain".to_string()])].into(),
+            body: body.to_vec(),
+        }
+    }
+
+    #[test]
+    fn streamed_request() {
+        for body in [&b"hello"[..], &[7u8; 300], &[1u8; 70_000]] {
+            let mut msg = request_head(&request(&[]), body.len() as u64).unwrap();
+            msg.extend_from_slice(body);
+            assert_eq!(
+                msg,
+                wasmcloud_provider_sdk::serialize(&request(body)).unwrap()
+            );
+        }
+        assert!(request_head(&request(b"body"), 4).is_err());
+    }
+
+    #[test]
+    fn response_head() {
+        let body = vec![42u8; 1000];
+        let msg = wasmcloud_provider_sdk::serialize(&HttpResponse {
+            status_code: 206,
+            header: [("etag".to_string(), vec!["\"abc\"".to_string()])].into(),
+            body: body.clone(),
+        })
+        .unwrap();
+        let msg_len = msg.len() as u64;
+
+        assert!(matches!(
+            parse_response_head(&msg[..10], msg_len),
+            ResponseHead::Incomplete
+        ));
+        let ResponseHead::Complete {
+            response,
+            body_offset,
+            body_len,
+        } = parse_response_head(&msg[..60], msg_len)
+        else {
+            panic!("response head not parsed");
+        };
+        assert_eq!(response.status_code, 206);
+        assert_eq!(response.header["etag"], vec!["\"abc\"".to_string()]);
+        assert_eq!(body_len, 1000);
+        assert_eq!(&msg[body_offset..], &body[..]);
+
+        // the body must end the message, and be its last field
+        assert!(matches!(
+            parse_response_head(&msg, msg_len + 1),
+            ResponseHead::Unsupported
+        ));
+        let mut reordered = vec![0x83];
+        reordered.extend_from_slice(&msg[msg.len() - 1000 - 8..]);
+        assert!(matches!(
+            parse_response_head(&reordered, reordered.len() as u64),
+            ResponseHead::Unsupported
+        ));
+    }
+}
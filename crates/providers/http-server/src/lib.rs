@@ -8,6 +8,7 @@
 //!
 //! - HTTP/1 and HTTP/2
 //! - TLS
+//! - Streaming of large request and response bodies through the lattice's chunking store
 //! - CORS support (select allowed_origins, allowed_methods,
 //!   allowed_headers.) Cors has sensible defaults so it should
 //!   work as-is for development purposes, and may need refinement
@@ -32,18 +33,26 @@
 //! by the all of the server green threads.
 //!
 
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::net::SocketAddr;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::time::Duration;
 
-use bytes::Bytes;
+use bytes::Buf;
 use flume::{bounded, Receiver, Sender};
-use futures::Future;
+use futures::{Future, Stream};
+use http::uri::Authority;
 use http::HeaderMap;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
+use tokio_util::io::ReaderStream;
 use tracing::{debug, error, info, instrument, trace, warn, Instrument};
+use warp::filters::BoxedFilter;
 use warp::path::FullPath;
-use warp::Filter;
+use warp::{Filter, Reply};
 
 use wasmcloud_provider_wit_bindgen::deps::{
     async_trait::async_trait,
@@ -51,11 +60,18 @@ use wasmcloud_provider_wit_bindgen::deps::{
     wasmcloud_provider_sdk,
     wasmcloud_provider_sdk::core::{LinkDefinition, WasmCloudEntity},
     wasmcloud_provider_sdk::error::InvocationError,
+    wasmcloud_provider_sdk::rpc_client::StreamingResponse,
 };
 
+mod body;
+pub use body::BodyStream;
+
 mod hashmap_ci;
 pub(crate) use hashmap_ci::make_case_insensitive;
 
+mod router;
+use router::{Route, Router};
+
 mod settings;
 pub use settings::{load_settings, ServiceSettings, CONTENT_LEN_LIMIT, DEFAULT_MAX_CONTENT_LEN};
 
@@ -78,6 +94,119 @@ wasmcloud_provider_wit_bindgen::generate!({
 pub struct HttpServerProvider {
     // map to store http server (and its link parameters) for each linked actor
     actors: Arc<dashmap::DashMap<String, HttpServerCore>>,
+    // listeners shared by actors with routing settings, by bind address
+    shared: Arc<Mutex<HashMap<SocketAddr, SharedListener>>>,
+}
+
+/// Listener shared by the actors linked with routing settings for its address.
+/// The listener is shut down when dropped
+struct SharedListener {
+    server: HttpServerCore,
+    router: Arc<Router>,
+}
+
+impl SharedListener {
+    /// Returns true if the listener-level settings of `settings` match those of the listener
+    fn accepts(&self, settings: &ServiceSettings) -> bool {
+        self.server.settings.tls == settings.tls && self.server.settings.cors == settings.cors
+    }
+}
+
+impl Drop for SharedListener {
+    fn drop(&mut self) {
+        self.server.begin_shutdown();
+    }
+}
+
+impl HttpServerProvider {
+    /// Add a route for an actor with routing settings to the shared listener for its address,
+    /// starting the listener if this is the first route on it.
+    /// Returns false if the route conflicts with existing routes or listeners, in which case
+    /// the current listener or route of the actor is kept.
+    async fn put_routed_link(&self, ld: &LinkDefinition, settings: ServiceSettings) -> bool {
+        // validated by load_settings
+        let Some(addr) = settings.address else {
+            return false;
+        };
+        if self
+            .actors
+            .iter()
+            .any(|server| server.key() != &ld.actor_id && server.settings.address == Some(addr))
+        {
+            error!(%addr, actor_id = %ld.actor_id, "httpserver address is used by the listener of another actor");
+            return false;
+        }
+
+        let route = Route::new(ld.clone(), settings.clone());
+        let mut shared = self.shared.lock().await;
+        // The listener-level settings of a shared listener must match those of every actor
+        // routed on it. If they differ and the actor is the only one on the listener,
+        // the listener is restarted with the new settings
+        let mut restart = false;
+        if let Some(listener) = shared.get(&addr) {
+            if let Err(e) = listener.router.check(&route) {
+                error!(%e, actor_id = %ld.actor_id, "httpserver failed to add route for actor");
+                return false;
+            }
+            if !listener.accepts(&settings) {
+                if listener.router.has_other_actors(&ld.actor_id) {
+                    error!(%addr, actor_id = %ld.actor_id, "httpserver tls or cors settings differ from the shared listener");
+                    return false;
+                }
+                restart = true;
+            }
+        }
+
+        if let Some((_, server)) = self.actors.remove(&ld.actor_id) {
+            server.begin_shutdown();
+        }
+        // the actor may have been routed on a different address before
+        for (_, listener) in shared.iter().filter(|(other, _)| **other != addr) {
+            listener.router.remove(&ld.actor_id);
+        }
+        shared.retain(|other, listener| *other == addr || !listener.router.is_empty());
+        if restart {
+            info!(%addr, "httpserver restarting shared listener with new settings");
+            shared.remove(&addr);
+        }
+
+        info!(%addr, actor_id = %ld.actor_id, %route, "httpserver adding route for actor");
+        if let Some(listener) = shared.get(&addr) {
+            if let Err(e) = listener.router.add(route) {
+                error!(%e, actor_id = %ld.actor_id, "httpserver failed to add route for actor");
+                return false;
+            }
+        } else {
+            let router = Arc::new(Router::default());
+            if let Err(e) = router.add(route) {
+                error!(%e, actor_id = %ld.actor_id, "httpserver failed to add route for actor");
+                return false;
+            }
+            let server = HttpServerCore::new(settings, call_actor);
+            if let Err(e) = server.start_routed(router.clone()).await {
+                error!(%e, ?ld, "httpserver failed to start shared listener");
+                return false;
+            }
+            shared.insert(addr, SharedListener { server, router });
+        }
+        true
+    }
+
+    /// Remove the route of an actor, stopping shared listeners that have no routes left
+    async fn remove_route(&self, actor_id: &str) {
+        let mut shared = self.shared.lock().await;
+        shared.retain(|addr, listener| {
+            if listener.router.remove(actor_id) {
+                info!(%actor_id, %addr, "httpserver removing route for actor");
+            }
+            if listener.router.is_empty() {
+                info!(%addr, "httpserver stopping shared listener");
+                false
+            } else {
+                true
+            }
+        });
+    }
 }
 
 /// Your provider can handle any of these methods
@@ -98,6 +227,23 @@ impl WasmcloudCapabilityProvider for HttpServerProvider {
             }
         };
 
+        if settings.is_routed() {
+            return self.put_routed_link(ld, settings).await;
+        }
+        if let Some(addr) = settings.address {
+            if self
+                .shared
+                .lock()
+                .await
+                .get(&addr)
+                .is_some_and(|listener| listener.router.has_other_actors(&ld.actor_id))
+            {
+                error!(%addr, ?ld, "httpserver address is used by a shared listener, set routing settings to share it");
+                return false;
+            }
+        }
+        self.remove_route(&ld.actor_id).await;
+
         // Start a server instance that calls the given actor
         let http_server = HttpServerCore::new(settings.clone(), call_actor);
         if let Err(e) = http_server.start(ld).await {
@@ -117,12 +263,14 @@ impl WasmcloudCapabilityProvider for HttpServerProvider {
             info!(%actor_id, "httpserver stopping listener for actor");
             entry.1.begin_shutdown();
         }
+        self.remove_route(actor_id).await;
     }
 
     /// Handle shutdown request by shutting down all the http server threads
     async fn shutdown(&self) {
         // empty the actor link data and stop all servers
         self.actors.clear();
+        self.shared.lock().await.clear();
     }
 }
 
@@ -164,10 +312,8 @@ impl<'a> Server<'a> {
         Self { ld, timeout }
     }
 
-    pub async fn handle_request(&self, req: HttpRequest) -> Result<HttpResponse, InvocationError> {
-        let connection = wasmcloud_provider_sdk::provider_main::get_connection();
-
-        let client = connection.get_rpc_client();
+    /// Returns the origin and target of invocations to the actor
+    fn entities(&self) -> (WasmCloudEntity, WasmCloudEntity) {
         let origin = WasmCloudEntity {
             public_key: self.ld.provider_id.clone(),
             link_name: self.ld.link_name.clone(),
@@ -177,6 +323,14 @@ impl<'a> Server<'a> {
             public_key: self.ld.actor_id.clone(),
             ..Default::default()
         };
+        (origin, target)
+    }
+
+    pub async fn handle_request(&self, req: HttpRequest) -> Result<HttpResponse, InvocationError> {
+        let connection = wasmcloud_provider_sdk::provider_main::get_connection();
+
+        let client = connection.get_rpc_client();
+        let (origin, target) = self.entities();
 
         let data = wasmcloud_provider_sdk::serialize(&req)?;
 
@@ -198,6 +352,56 @@ impl<'a> Server<'a> {
 
         Ok(response)
     }
+
+    /// Forward a request to the actor, streaming its body from `body` instead of `req.body` if
+    /// set. Request bodies and responses larger than the chunking threshold are sent through the
+    /// lattice's chunking store, and the body of a chunked response is returned as a stream.
+    pub async fn handle_request_stream(
+        &self,
+        req: HttpRequest,
+        body: Option<BodyStream>,
+    ) -> Result<(HttpResponse, Option<BodyStream>), InvocationError> {
+        let connection = wasmcloud_provider_sdk::provider_main::get_connection();
+
+        let client = connection.get_rpc_client();
+        let (origin, target) = self.entities();
+
+        let (data, len): (Pin<Box<dyn AsyncRead + Send>>, u64) = match body {
+            Some(body) => {
+                let head = body::request_head(&req, body.len)?;
+                let len = head.len() as u64 + body.len;
+                (
+                    Box::pin(AsyncReadExt::chain(Cursor::new(head), body.reader)),
+                    len,
+                )
+            }
+            None => {
+                let data = wasmcloud_provider_sdk::serialize(&req)?;
+                let len = data.len() as u64;
+                (Box::pin(Cursor::new(data)), len)
+            }
+        };
+
+        let StreamingResponse { response, body } = client
+            .send_stream(
+                origin,
+                target,
+                HANDLE_REQUEST_METHOD,
+                data,
+                len,
+                self.timeout,
+            )
+            .await?;
+
+        if let Some(e) = response.error {
+            return Err(InvocationError::Unexpected(e));
+        }
+
+        match body {
+            Some(body) => body::read_response(body, response.content_length).await,
+            None => Ok((wasmcloud_provider_sdk::deserialize(&response.msg)?, None)),
+        }
+    }
 }
 
 /// Forward a [`Request`] to an Actor.
@@ -205,27 +409,32 @@ impl<'a> Server<'a> {
 async fn call_actor(
     ld: Arc<LinkDefinition>,
     req: HttpRequest,
+    body: Option<BodyStream>,
     timeout: Option<std::time::Duration>,
-) -> Result<HttpResponse, InvocationError> {
+) -> Result<(HttpResponse, Option<BodyStream>), InvocationError> {
     let sender = Server::new(&ld, timeout);
 
-    let rc = sender.handle_request(req).await;
+    let rc = sender.handle_request_stream(req, body).await;
     match rc {
         Err(InvocationError::Timeout) => {
             error!("actor request timed out: returning 503",);
-            Ok(HttpResponse {
-                status_code: 503,
-                body: Default::default(),
-                header: Default::default(),
-            })
+            Ok((
+                HttpResponse {
+                    status_code: 503,
+                    body: Default::default(),
+                    header: Default::default(),
+                },
+                None,
+            ))
         }
 
-        Ok(resp) => {
+        Ok((resp, body)) => {
             trace!(
                 status_code = %resp.status_code,
+                streamed_body_len = ?body.as_ref().map(|body| body.len),
                 "http response received from actor"
             );
-            Ok(resp)
+            Ok((resp, body))
         }
         Err(e) => {
             warn!(
@@ -260,14 +469,17 @@ pub enum HttpServerError {
     SettingsToml(toml::de::Error),
 }
 
-/// Alias for functions that trigger an actor
+/// Result of calling an actor: the response, and its body if it is streamed
+pub type CallActorResult = Result<(HttpResponse, Option<BodyStream>), InvocationError>;
+
+/// Alias for functions that trigger an actor, with the request and its body if it is streamed
 pub type AsyncCallActorFn = Box<
     dyn Fn(
             Arc<LinkDefinition>,
             HttpRequest,
+            Option<BodyStream>,
             Option<Duration>,
-        )
-            -> Pin<Box<dyn Future<Output = Result<HttpResponse, InvocationError>> + Send + 'static>>
+        ) -> Pin<Box<dyn Future<Output = CallActorResult> + Send + 'static>>
         + Send
         + Sync,
 >;
@@ -280,9 +492,10 @@ impl CallActorFn {
         &self,
         ld: Arc<LinkDefinition>,
         req: HttpRequest,
+        body: Option<BodyStream>,
         timeout: Option<Duration>,
-    ) -> Pin<Box<dyn Future<Output = Result<HttpResponse, InvocationError>> + Send + 'static>> {
-        Box::pin((self.0.as_ref())(ld, req, timeout))
+    ) -> Pin<Box<dyn Future<Output = CallActorResult> + Send + 'static>> {
+        Box::pin((self.0.as_ref())(ld, req, body, timeout))
     }
 }
 
@@ -319,8 +532,11 @@ impl HttpServerCore {
     /// Initializes server with settings
     pub fn new<F, Fut>(settings: ServiceSettings, call_actor_fn: F) -> Self
     where
-        F: Fn(Arc<LinkDefinition>, HttpRequest, Option<Duration>) -> Fut + Send + Sync + 'static,
-        Fut: Future<Output = Result<HttpResponse, InvocationError>> + 'static + Send,
+        F: Fn(Arc<LinkDefinition>, HttpRequest, Option<BodyStream>, Option<Duration>) -> Fut
+            + Send
+            + Sync
+            + 'static,
+        Fut: Future<Output = CallActorResult> + 'static + Send,
     {
         let (shutdown_tx, shutdown_rx) = bounded(1);
         let call_actor_fn = Arc::new(call_actor_fn);
@@ -330,9 +546,12 @@ impl HttpServerCore {
                 shutdown_tx,
                 shutdown_rx,
                 call_actor: CallActorFn(Box::new(
-                    move |ld: Arc<LinkDefinition>, req: HttpRequest, timeout: Option<Duration>| {
+                    move |ld: Arc<LinkDefinition>,
+                          req: HttpRequest,
+                          body: Option<BodyStream>,
+                          timeout: Option<Duration>| {
                         let call_actor_fn = call_actor_fn.clone();
-                        Box::pin(call_actor_fn(ld, req, timeout))
+                        Box::pin(call_actor_fn(ld, req, body, timeout))
                     },
                 )),
             }),
@@ -352,12 +571,6 @@ impl HttpServerCore {
     ///    let _ = server.start().await?;
     /// ```
     pub async fn start(&self, ld: &LinkDefinition) -> Result<JoinHandle<()>, HttpServerError> {
-        let timeout = self
-            .inner
-            .settings
-            .timeout_ms
-            .map(std::time::Duration::from_millis);
-
         let ld = Arc::new(ld.clone());
         let linkdefs = ld.clone();
         let trace_ld = ld.clone();
@@ -365,78 +578,22 @@ impl HttpServerCore {
         let route = warp::any()
             .and(warp::header::headers_cloned())
             .and(warp::method())
-            .and(warp::body::bytes())
+            .and(warp::body::stream())
             .and(warp::path::full())
             .and(opt_raw_query())
             .and_then(
                 move |
                       headers: HeaderMap,
                       method: http::method::Method,
-                      body: Bytes,
+                      body,
                       path: FullPath,
                       query: String| {
                     let span = tracing::debug_span!("http request", %method, path = %path.as_str(), %query);
                     let ld = linkdefs.clone();
                     let arc_inner = arc_inner.clone();
-                    async move{
-                        if let Some(readonly_mode) = arc_inner.settings.readonly_mode{
-                            if readonly_mode && method!= http::method::Method::GET && method!= http::method::Method::HEAD {
-                                debug!("Cannot use other methods in Read Only Mode");
-                                // If this fails it is developer error, so unwrap is okay
-                                let resp = http::Response::builder().status(http::StatusCode::METHOD_NOT_ALLOWED).body(Vec::with_capacity(0)).unwrap();
-                                return Ok::<_, warp::Rejection>(resp)
-                            }
-                        }
-                        let hmap = convert_request_headers(&headers);
-                        let req = HttpRequest {
-                            body: Vec::from(body),
-                            header: hmap,
-                            method: method.as_str().to_ascii_uppercase(),
-                            path: path.as_str().to_string(),
-                            query_string: query,
-                        };
-                        trace!(
-                            ?req,
-                            "httpserver calling actor"
-                        );
-                        let response = match arc_inner.call_actor.call(ld.clone(), req, timeout).in_current_span().await {
-                            Ok(resp) => resp,
-                            Err(e) => {
-                                error!(
-                                    error = %e,
-                                    "Error sending Request to actor"
-                                );
-                                HttpResponse {
-                                    status_code: http::StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
-                                    body: Default::default(),
-                                    header: Default::default(),
-                                }
-                            }
-                        };
-                        let status = match http::StatusCode::from_u16(response.status_code) {
-                            Ok(status_code) => status_code,
-                            Err(e) => {
-                                error!(
-                                    status_code = %response.status_code,
-                                    error = %e,
-                                    "invalid response status code, changing to 500"
-                                );
-                                http::StatusCode::INTERNAL_SERVER_ERROR
-                            }
-                        };
-                        let http_builder = http::Response::builder()
-                        .status(status);
-                        let http_builder = if let Some(cache_control_header) = arc_inner.settings.cache_control.as_ref(){
-                            let mut builder = http_builder;
-                            builder = builder.header("Cache-Control",cache_control_header);
-                            builder
-                        }else{
-                            http_builder
-                        };
-                        // Unwrapping here because validation takes place for the linkdef
-                        let mut http_response = http_builder.body(response.body).unwrap();
-                        convert_response_headers(response.header, http_response.headers_mut());
-                        Ok::<_, warp::Rejection>(http_response)
+                    async move {
+                        let response = arc_inner.handle_request(&arc_inner.settings, ld, headers, method, body, path, query).await;
+                        Ok::<_, warp::Rejection>(response)
                     }.instrument(span)
                 },
             ).with(warp::trace(move |req_info| {
@@ -447,14 +604,80 @@ impl HttpServerCore {
                 }
 
                 span
-            }));
+            }))
+            .map(warp::Reply::into_response)
+            .boxed();
 
-        let addr = self.settings.address.unwrap();
         info!(
-            %addr,
+            addr = ?self.settings.address,
             actor_id = %ld.actor_id,
             "httpserver starting listener for actor",
         );
+        self.serve(route)
+    }
+
+    /// Start a server shared by the actors in `router`, forwarding each request
+    /// to the actor with the most specific matching route.
+    /// Address, TLS and CORS are taken from the settings of this server, which must match those
+    /// of every routed actor, while the remaining settings are those of the matching actor.
+    pub(crate) async fn start_routed(
+        &self,
+        router: Arc<Router>,
+    ) -> Result<JoinHandle<()>, HttpServerError> {
+        let arc_inner = self.inner.clone();
+        let route = warp::any()
+            .and(warp::host::optional())
+            .and(warp::header::headers_cloned())
+            .and(warp::method())
+            .and(warp::body::stream())
+            .and(warp::path::full())
+            .and(opt_raw_query())
+            .and_then(
+                move |
+                      authority: Option<Authority>,
+                      headers: HeaderMap,
+                      method: http::method::Method,
+                      body,
+                      path: FullPath,
+                      query: String| {
+                    let span = tracing::debug_span!("http request", %method, path = %path.as_str(), %query, actor_id = tracing::field::Empty);
+                    let router = router.clone();
+                    let arc_inner = arc_inner.clone();
+                    async move {
+                        let Some(route) = router.find(authority.as_ref().map(Authority::host), path.as_str()) else {
+                            debug!(host = ?authority, "no route for request");
+                            return Ok::<_, warp::Rejection>(empty_response(http::StatusCode::NOT_FOUND))
+                        };
+                        tracing::Span::current().record("actor_id", &tracing::field::display(&route.ld.actor_id));
+                        let response = arc_inner.handle_request(&route.settings, route.ld.clone(), headers, method, body, path, query).await;
+                        Ok::<_, warp::Rejection>(response)
+                    }.instrument(span)
+                },
+            ).with(warp::trace(|req_info| {
+                let span = tracing::debug_span!("request", method = %req_info.method(), path = %req_info.path(), query = tracing::field::Empty);
+                if let Some(remote_addr) = req_info.remote_addr() {
+                    span.record("remote_addr", &tracing::field::display(remote_addr));
+                }
+
+                span
+            }))
+            .map(warp::Reply::into_response)
+            .boxed();
+
+        info!(
+            addr = ?self.settings.address,
+            "httpserver starting shared listener",
+        );
+        self.serve(route)
+    }
+
+    /// Bind the listener at the configured address and serve `route` on it in a new task,
+    /// until the server is shut down
+    fn serve(
+        &self,
+        route: BoxedFilter<(warp::reply::Response,)>,
+    ) -> Result<JoinHandle<()>, HttpServerError> {
+        let addr = self.settings.address.unwrap();
 
         // add Cors configuration, if enabled, and spawn either TlsServer or Server
         let cors = cors_filter(&self.settings)?;
@@ -497,9 +720,221 @@ impl HttpServerCore {
     }
 }
 
+impl Inner {
+    /// Forward a request to the actor of `ld` and convert its response,
+    /// applying the per-actor `settings`
+    #[allow(clippy::too_many_arguments)]
+    async fn handle_request<S, B>(
+        &self,
+        settings: &ServiceSettings,
+        ld: Arc<LinkDefinition>,
+        headers: HeaderMap,
+        method: http::method::Method,
+        body: S,
+        path: FullPath,
+        query: String,
+    ) -> warp::reply::Response
+    where
+        S: Stream<Item = Result<B, warp::Error>> + Send + 'static,
+        B: Buf + Send + 'static,
+    {
+        if let Some(readonly_mode) = settings.readonly_mode {
+            if readonly_mode
+                && method != http::method::Method::GET
+                && method != http::method::Method::HEAD
+            {
+                debug!("Cannot use other methods in Read Only Mode");
+                return empty_response(http::StatusCode::METHOD_NOT_ALLOWED);
+            }
+        }
+        // Bodies with a known length above the chunking threshold are streamed to the actor,
+        // other bodies are read into memory
+        let max_content_len = settings.max_content_len_bytes();
+        let content_length = headers
+            .get(http::header::CONTENT_LENGTH)
+            .and_then(|len| len.to_str().ok())
+            .and_then(|len| len.parse::<u64>().ok());
+        let (req_body, body) = match content_length {
+            Some(len) if len > max_content_len => {
+                debug!(%len, %max_content_len, "request body exceeds max content length");
+                return empty_response(http::StatusCode::PAYLOAD_TOO_LARGE);
+            }
+            Some(len) if body::is_streamed(len) => {
+                (Vec::new(), Some(BodyStream::from_stream(len, body)))
+            }
+            _ => match body::collect_body(body, max_content_len).await {
+                Ok(Some(body)) => (body, None),
+                Ok(None) => {
+                    debug!(%max_content_len, "request body exceeds max content length");
+                    return empty_response(http::StatusCode::PAYLOAD_TOO_LARGE);
+                }
+                Err(e) => {
+                    debug!(error = %e, "failed to read request body");
+                    return empty_response(http::StatusCode::BAD_REQUEST);
+                }
+            },
+        };
+        let timeout = settings.timeout_ms.map(std::time::Duration::from_millis);
+        let hmap = convert_request_headers(&headers);
+        let req = HttpRequest {
+            body: req_body,
+            header: hmap,
+            method: method.as_str().to_ascii_uppercase(),
+            path: path.as_str().to_string(),
+            query_string: query,
+        };
+        trace!(?req, ?body, "httpserver calling actor");
+        let (response, body) = match self
+            .call_actor
+            .call(ld, req, body, timeout)
+            .in_current_span()
+            .await
+        {
+            Ok(resp) => resp,
+            Err(e) => {
+                error!(error = %e, "Error sending Request to actor");
+                (
+                    HttpResponse {
+                        status_code: http::StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        body: Default::default(),
+                        header: Default::default(),
+                    },
+                    None,
+                )
+            }
+        };
+        let status = match http::StatusCode::from_u16(response.status_code) {
+            Ok(status_code) => status_code,
+            Err(e) => {
+                error!(
+                    status_code = %response.status_code,
+                    error = %e,
+                    "invalid response status code, changing to 500"
+                );
+                http::StatusCode::INTERNAL_SERVER_ERROR
+            }
+        };
+        let http_builder = http::Response::builder().status(status);
+        let http_builder = if let Some(cache_control_header) = settings.cache_control.as_ref() {
+            let mut builder = http_builder;
+            builder = builder.header("Cache-Control", cache_control_header);
+            builder
+        } else {
+            http_builder
+        };
+        let body_len = body.as_ref().map(|body| body.len);
+        let http_body = match body {
+            Some(body) => warp::hyper::Body::wrap_stream(ReaderStream::new(body.reader)),
+            None => warp::hyper::Body::from(response.body),
+        };
+        // Unwrapping here because validation takes place for the linkdef
+        let mut http_response = http_builder.body(http_body).unwrap();
+        convert_response_headers(response.header, http_response.headers_mut());
+        if let Some(len) = body_len {
+            http_response
+                .headers_mut()
+                .insert(http::header::CONTENT_LENGTH, len.into());
+        }
+        http_response
+    }
+}
+
+/// Response with an empty body
+fn empty_response(status: http::StatusCode) -> warp::reply::Response {
+    warp::reply::with_status(warp::reply(), status).into_response()
+}
+
 impl Drop for HttpServerCore {
     /// Drop the client connection. Does not block or fail if the client has already been closed.
     fn drop(&mut self) {
         let _ = self.shutdown_tx.try_send(true);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::{TcpListener, TcpStream};
+
+    use wasmcloud_provider_sdk::core::chunking::CHUNK_THRESHOLD_BYTES;
+
+    /// Stands in for the rpc client and the actor: reads the serialized request as it would be
+    /// streamed to the chunking store, and returns a chunked response echoing the request body
+    async fn echo_actor(
+        _ld: Arc<LinkDefinition>,
+        req: HttpRequest,
+        body: Option<BodyStream>,
+        _timeout: Option<Duration>,
+    ) -> CallActorResult {
+        let mut body = body.expect("request body was not streamed");
+        let mut msg = body::request_head(&req, body.len)?;
+        body.reader
+            .read_to_end(&mut msg)
+            .await
+            .map_err(|e| InvocationError::Chunking(e.to_string()))?;
+        let req: HttpRequest = wasmcloud_provider_sdk::deserialize(&msg)?;
+        let msg = wasmcloud_provider_sdk::serialize(&HttpResponse {
+            status_code: 200,
+            header: [("x-method".to_string(), vec![req.method])].into(),
+            body: req.body,
+        })?;
+        let len = msg.len() as u64;
+        body::read_response(Cursor::new(msg), len).await
+    }
+
+    #[tokio::test]
+    async fn stream_large_body() {
+        let address = TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let server = HttpServerCore::new(
+            ServiceSettings {
+                address: Some(address),
+                ..Default::default()
+            },
+            echo_actor,
+        );
+        let ld = LinkDefinition {
+            actor_id: "actor".to_string(),
+            ..Default::default()
+        };
+        server.start(&ld).await.expect("failed to start server");
+
+        let body: Vec<u8> = (0..CHUNK_THRESHOLD_BYTES + 100_000)
+            .map(|i| (i % 251) as u8)
+            .collect();
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        stream
+            .write_all(
+                format!(
+                    "POST /upload HTTP/1.1\r\nhost: {address}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+                    body.len()
+                )
+                .as_bytes(),
+            )
+            .await
+            .unwrap();
+        stream.write_all(&body).await.unwrap();
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await.unwrap();
+
+        let head_len = response
+            .windows(4)
+            .position(|w| w == b"\r\n\r\n")
+            .expect("response head is not terminated")
+            + 4;
+        let head = String::from_utf8_lossy(&response[..head_len]).to_ascii_lowercase();
+        assert!(
+            head.starts_with("http/1.1 200"),
+            "unexpected response {head}"
+        );
+        assert!(head.contains(&format!("content-length: {}\r\n", body.len())));
+        assert!(head.contains("x-method: post\r\n"));
+        assert_eq!(response.len() - head_len, body.len());
+        assert!(response[head_len..] == body[..], "response body differs");
+    }
+}
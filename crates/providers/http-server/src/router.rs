@@ -0,0 +1,249 @@
+//! Routing of requests on a listener shared by several actor links.
+//!
+//! A link that declares a host and/or path prefix in its [`Routing`](crate::settings::Routing)
+//! settings does not get its own listener. Instead, all such links with the same bind address
+//! share one listener, which forwards each request to the actor with the most specific matching
+//! route: routes matching the `Host` header take precedence over routes for any host, and
+//! among those, the route with the longest matching path prefix wins.
+
+use std::sync::{Arc, RwLock};
+
+use wasmcloud_provider_wit_bindgen::deps::wasmcloud_provider_sdk::core::LinkDefinition;
+
+use crate::settings::ServiceSettings;
+use crate::HttpServerError;
+
+/// Route of a linked actor on a shared listener
+#[derive(Clone)]
+pub(crate) struct Route {
+    /// lowercase host name without port, or None to match any host
+    host: Option<String>,
+    /// path prefix, starting with '/' and without trailing '/' (except for the root "/")
+    path_prefix: String,
+    pub(crate) ld: Arc<LinkDefinition>,
+    pub(crate) settings: Arc<ServiceSettings>,
+}
+
+impl Route {
+    pub(crate) fn new(ld: LinkDefinition, settings: ServiceSettings) -> Self {
+        let host = settings.routing.host.as_deref().map(normalize_host);
+        let path_prefix = normalize_path_prefix(settings.routing.path_prefix.as_deref());
+        Self {
+            host,
+            path_prefix,
+            ld: Arc::new(ld),
+            settings: Arc::new(settings),
+        }
+    }
+
+    /// Returns true if a request with `path` falls under the path prefix of this route
+    fn matches_path(&self, path: &str) -> bool {
+        self.path_prefix == "/"
+            || path
+                .strip_prefix(&self.path_prefix)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    }
+}
+
+impl std::fmt::Display for Route {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{}",
+            self.host.as_deref().unwrap_or("*"),
+            self.path_prefix
+        )
+    }
+}
+
+/// Lowercase the host name and remove any port
+fn normalize_host(host: &str) -> String {
+    let host = host.trim().to_ascii_lowercase();
+    // Keep bracketed IPv6 addresses intact
+    match host.rsplit_once(':') {
+        Some((name, port)) if !name.ends_with(':') && port.chars().all(|c| c.is_ascii_digit()) => {
+            name.to_string()
+        }
+        _ => host,
+    }
+}
+
+fn normalize_path_prefix(prefix: Option<&str>) -> String {
+    match prefix.map(|p| p.trim().trim_end_matches('/')) {
+        None | Some("") => "/".to_string(),
+        Some(p) => p.to_string(),
+    }
+}
+
+/// Routes of the actors linked on one shared listener
+#[derive(Default)]
+pub(crate) struct Router {
+    routes: RwLock<Vec<Route>>,
+}
+
+impl Router {
+    /// Adds the route of an actor, replacing any previous route of the same actor.
+    /// Fails if another actor already has the same host and path prefix
+    pub(crate) fn add(&self, route: Route) -> Result<(), HttpServerError> {
+        let mut routes = self.routes.write().unwrap_or_else(|e| e.into_inner());
+        check_conflicts(&routes, &route)?;
+        routes.retain(|r| r.ld.actor_id != route.ld.actor_id);
+        routes.push(route);
+        Ok(())
+    }
+
+    /// Checks that the route of an actor could be added without conflicting with the routes of
+    /// other actors, without adding it
+    pub(crate) fn check(&self, route: &Route) -> Result<(), HttpServerError> {
+        check_conflicts(
+            &self.routes.read().unwrap_or_else(|e| e.into_inner()),
+            route,
+        )
+    }
+
+    /// Removes the route of an actor, returning true if the actor had a route
+    pub(crate) fn remove(&self, actor_id: &str) -> bool {
+        let mut routes = self.routes.write().unwrap_or_else(|e| e.into_inner());
+        let len = routes.len();
+        routes.retain(|r| r.ld.actor_id != actor_id);
+        routes.len() != len
+    }
+
+    /// Returns true if actors other than `actor_id` have a route
+    pub(crate) fn has_other_actors(&self, actor_id: &str) -> bool {
+        self.routes
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .any(|r| r.ld.actor_id != actor_id)
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.routes
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_empty()
+    }
+
+    /// Finds the most specific route for a request to `host` and `path`
+    pub(crate) fn find(&self, host: Option<&str>, path: &str) -> Option<Route> {
+        let host = host.map(normalize_host);
+        self.routes
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|r| r.host.is_none() || r.host == host)
+            .filter(|r| r.matches_path(path))
+            .max_by_key(|r| (r.host.is_some(), r.path_prefix.len()))
+            .cloned()
+    }
+}
+
+/// Fails if an actor other than the one of `route` already has the same host and path prefix
+fn check_conflicts(routes: &[Route], route: &Route) -> Result<(), HttpServerError> {
+    match routes.iter().find(|r| {
+        r.ld.actor_id != route.ld.actor_id
+            && r.host == route.host
+            && r.path_prefix == route.path_prefix
+    }) {
+        Some(existing) => Err(HttpServerError::InvalidParameter(format!(
+            "route {route} is already used by actor {}",
+            existing.ld.actor_id
+        ))),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::settings::Routing;
+
+    fn route(actor_id: &str, host: Option<&str>, path_prefix: Option<&str>) -> Route {
+        let ld = LinkDefinition {
+            actor_id: actor_id.to_string(),
+            ..Default::default()
+        };
+        let mut settings = ServiceSettings::default();
+        settings.routing = Routing {
+            host: host.map(String::from),
+            path_prefix: path_prefix.map(String::from),
+        };
+        Route::new(ld, settings)
+    }
+
+    fn find(router: &Router, host: Option<&str>, path: &str) -> Option<String> {
+        router.find(host, path).map(|r| r.ld.actor_id.clone())
+    }
+
+    #[test]
+    fn route_matching() {
+        let router = Router::default();
+        router.add(route("root", None, None)).unwrap();
+        router.add(route("api", None, Some("/api/"))).unwrap();
+        router.add(route("api_v2", None, Some("/api/v2"))).unwrap();
+        router
+            .add(route("example", Some("Example.com"), None))
+            .unwrap();
+        router
+            .add(route("example_api", Some("example.com"), Some("/api")))
+            .unwrap();
+
+        assert_eq!(find(&router, None, "/"), Some("root".into()));
+        assert_eq!(find(&router, None, "/apix"), Some("root".into()));
+        assert_eq!(find(&router, None, "/api"), Some("api".into()));
+        assert_eq!(find(&router, None, "/api/v1/x"), Some("api".into()));
+        assert_eq!(find(&router, None, "/api/v2/x"), Some("api_v2".into()));
+        assert_eq!(find(&router, Some("other.com"), "/api"), Some("api".into()));
+        assert_eq!(
+            find(&router, Some("EXAMPLE.com:8080"), "/api/v2"),
+            Some("example_api".into())
+        );
+        assert_eq!(
+            find(&router, Some("example.com"), "/index.html"),
+            Some("example".into())
+        );
+
+        assert!(router.remove("root"));
+        assert!(!router.remove("root"));
+        assert_eq!(find(&router, None, "/index.html"), None);
+    }
+
+    #[test]
+    fn route_conflicts() {
+        let router = Router::default();
+        router
+            .add(route("a", Some("example.com"), Some("/api")))
+            .unwrap();
+        assert!(router
+            .add(route("b", Some("example.com:80"), Some("/api/")))
+            .is_err());
+        assert!(router
+            .check(&route("b", Some("example.com"), Some("/api")))
+            .is_err());
+        assert!(router
+            .check(&route("a", Some("example.com"), Some("/api")))
+            .is_ok());
+        assert!(!router.has_other_actors("a"));
+        router.add(route("b", None, Some("/api"))).unwrap();
+        assert!(router.has_other_actors("a"));
+        // an actor may replace its own route
+        router
+            .add(route("a", Some("example.com"), Some("/api")))
+            .unwrap();
+        router.add(route("a", Some("example.org"), None)).unwrap();
+        assert_eq!(find(&router, Some("example.com"), "/api"), Some("b".into()));
+        assert!(!router.is_empty());
+        router.remove("a");
+        router.remove("b");
+        assert!(router.is_empty());
+    }
+
+    #[test]
+    fn host_normalization() {
+        assert_eq!(normalize_host("Example.COM"), "example.com");
+        assert_eq!(normalize_host("example.com:8080"), "example.com");
+        assert_eq!(normalize_host("[::1]:8080"), "[::1]");
+        assert_eq!(normalize_host("[::1]"), "[::1]");
+    }
+}
@@ -25,6 +25,7 @@ use wasmcloud_provider_wit_bindgen::deps::serde::{
 };
 use wasmcloud_provider_wit_bindgen::deps::serde_json;
 
+use crate::warp_util::convert_human_size;
 use crate::HttpServerError;
 
 const DEFAULT_ADDR: &str = "127.0.0.1:8000";
@@ -63,6 +64,10 @@ pub struct ServiceSettings {
     #[serde(default)]
     pub cors: Cors,
 
+    /// routing on a listener shared with other actors
+    #[serde(default)]
+    pub routing: Routing,
+
     /// logging
     #[serde(default)]
     pub log: Log,
@@ -101,6 +106,7 @@ impl Default for ServiceSettings {
             address: Some(SocketAddr::from_str(DEFAULT_ADDR).unwrap()),
             tls: Tls::default(),
             cors: Cors::default(),
+            routing: Routing::default(),
             log: Log::default(),
             timeout_ms: None,
             cache_control: None,
@@ -158,12 +164,33 @@ impl ServiceSettings {
 
     /// Merge settings from other into self
     fn merge(&mut self, other: ServiceSettings) {
-        merge!(self, other, address, cache_control, readonly_mode);
+        merge!(
+            self,
+            other,
+            address,
+            cache_control,
+            readonly_mode,
+            max_content_len
+        );
         self.tls.merge(other.tls);
         self.cors.merge(other.cors);
+        self.routing.merge(other.routing);
         self.log.merge(other.log);
     }
 
+    /// Returns true if requests are routed to the actor on a listener shared with other actors
+    pub fn is_routed(&self) -> bool {
+        self.routing.is_set()
+    }
+
+    /// Returns the max content length in bytes, or the default if the setting is missing or invalid
+    pub fn max_content_len_bytes(&self) -> u64 {
+        self.max_content_len
+            .as_deref()
+            .and_then(|value| convert_human_size(value).ok())
+            .unwrap_or(DEFAULT_MAX_CONTENT_LEN)
+    }
+
     /// perform additional validation checks on settings.
     /// Several checks have already been done during deserialization.
     /// All errors found are combined into a single error message
@@ -203,6 +230,24 @@ impl ServiceSettings {
                 }
             }
         }
+        if let Some(host) = self.routing.host.as_ref() {
+            if host.trim().is_empty() || host.contains('/') {
+                errors.push(format!("invalid routing host: '{}'", host));
+            }
+        }
+        if let Some(path_prefix) = self.routing.path_prefix.as_ref() {
+            if !path_prefix.starts_with('/') {
+                errors.push(format!(
+                    "routing path_prefix must begin with '/': '{}'",
+                    path_prefix
+                ));
+            }
+        }
+        if let Some(max_content_len) = self.max_content_len.as_ref() {
+            if let Err(e) = convert_human_size(max_content_len) {
+                errors.push(e.to_string());
+            }
+        }
         if let Some(cache_control) = self.cache_control.as_ref() {
             if http::HeaderValue::from_str(cache_control).is_err() {
                 errors.push(format!(
@@ -281,6 +326,19 @@ pub fn load_settings(values: &[(String, String)]) -> Result<ServiceSettings, Htt
         settings.cache_control = Some(cache_control.to_string());
     }
 
+    // accept max content length
+    if let Some(max_content_len) = values.get("max_content_len") {
+        settings.max_content_len = Some(max_content_len.to_string());
+    }
+
+    // accept routing on a shared listener
+    if let Some(host) = values.get("route_host") {
+        settings.routing.host = Some(host.to_string());
+    }
+    if let Some(path_prefix) = values.get("route_path_prefix") {
+        settings.routing.path_prefix = Some(path_prefix.to_string());
+    }
+
     // accept read only mode flag
     if let Some(readonly_mode) = values.get("readonly_mode") {
         settings.readonly_mode = Some(readonly_mode.to_string().parse().unwrap_or(false));
@@ -311,6 +369,29 @@ impl Tls {
     }
 }
 
+/// Routing of requests to the actor on a listener shared with other actors.
+/// If either field is set, the actor does not get its own listener, but shares one with all
+/// other routed actors using the same address.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(crate = "wasmcloud_provider_wit_bindgen::deps::serde")]
+pub struct Routing {
+    /// host name matched against the `Host` header (without port) of requests
+    pub host: Option<String>,
+
+    /// path prefix of requests, matched on path segment boundaries
+    pub path_prefix: Option<String>,
+}
+
+impl Routing {
+    fn merge(&mut self, other: Routing) {
+        merge!(self, other, host, path_prefix);
+    }
+
+    pub fn is_set(&self) -> bool {
+        self.host.is_some() || self.path_prefix.is_some()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(crate = "wasmcloud_provider_wit_bindgen::deps::serde")]
 pub struct Cors {
@@ -632,7 +713,7 @@ where
 mod test {
     use std::str::FromStr;
 
-    use crate::settings::{serde_json, CorsOrigin, ServiceSettings};
+    use crate::settings::{load_settings, serde_json, CorsOrigin, ServiceSettings};
 
     const GOOD_ORIGINS: &[&str] = &[
         // origins that should be parsed correctly
@@ -699,6 +780,26 @@ mod test {
         );
     }
 
+    #[test]
+    fn settings_routing() {
+        let s = load_settings(&[]).expect("default settings");
+        assert!(!s.is_routed());
+
+        let s = load_settings(&[
+            (
+                "config_json".to_string(),
+                r#"{"routing": {"host": "example.com"}}"#.to_string(),
+            ),
+            ("ROUTE_PATH_PREFIX".to_string(), "/api".to_string()),
+        ])
+        .expect("routing settings");
+        assert!(s.is_routed());
+        assert_eq!(s.routing.host.as_deref(), Some("example.com"));
+        assert_eq!(s.routing.path_prefix.as_deref(), Some("/api"));
+
+        assert!(load_settings(&[("route_path_prefix".to_string(), "api".to_string())]).is_err());
+    }
+
     #[test]
     fn origins_deserialize() {
         // test CorsOrigin
@@ -6,7 +6,7 @@ use warp::filters::cors::Builder;
 use warp::Filter;
 
 use crate::settings::ServiceSettings;
-use crate::HttpServerError;
+use crate::{HttpServerError, CONTENT_LEN_LIMIT, DEFAULT_MAX_CONTENT_LEN};
 
 /// Convert request headers from incoming warp server to HeaderMap
 pub(crate) fn convert_request_headers(headers: &http::HeaderMap) -> HashMap<String, Vec<String>> {
@@ -118,54 +118,55 @@ pub(crate) fn cors_filter(
     Ok(cors.build())
 }
 
-#[cfg(test)]
-mod tests {
-
-    use crate::{HttpServerError, CONTENT_LEN_LIMIT, DEFAULT_MAX_CONTENT_LEN};
-
-    /// Convert setting for max content length of form '[0-9]+(g|G|m|M|k|K)?'
-    /// Empty string is accepted and returns the default value (currently '10M')
-    pub fn convert_human_size(value: &str) -> Result<u64, HttpServerError> {
-        let value = value.trim();
-        let mut limit = None;
-        if value.is_empty() {
-            limit = Some(DEFAULT_MAX_CONTENT_LEN);
-        } else if let Ok(num) = value.parse::<u64>() {
-            limit = Some(num);
-        } else {
-            let (num, units) = value.split_at(value.len() - 1);
-            if let Ok(base_value) = num.trim().parse::<u64>() {
-                match units {
-                    "k" | "K" => {
-                        limit = Some(base_value * 1024);
-                    }
-                    "m" | "M" => {
-                        limit = Some(base_value * 1024 * 1024);
-                    }
-                    "g" | "G" => {
-                        limit = Some(base_value * 1024 * 1024 * 1024);
-                    }
-                    _ => {}
+/// Convert setting for max content length of form '[0-9]+(g|G|m|M|k|K)?'
+/// Empty string is accepted and returns the default value (currently '10M')
+pub(crate) fn convert_human_size(value: &str) -> Result<u64, HttpServerError> {
+    let value = value.trim();
+    let mut limit = None;
+    if value.is_empty() {
+        limit = Some(DEFAULT_MAX_CONTENT_LEN);
+    } else if let Ok(num) = value.parse::<u64>() {
+        limit = Some(num);
+    } else {
+        let (num, units) = value.split_at(value.len() - 1);
+        if let Ok(base_value) = num.trim().parse::<u64>() {
+            match units {
+                "k" | "K" => {
+                    limit = Some(base_value * 1024);
+                }
+                "m" | "M" => {
+                    limit = Some(base_value * 1024 * 1024);
                 }
+                "g" | "G" => {
+                    limit = Some(base_value * 1024 * 1024 * 1024);
+                }
+                _ => {}
             }
         }
-        match limit {
-            Some(x) if x > 0 && x <= CONTENT_LEN_LIMIT => Ok(x),
-            Some(_) => {
-                Err(HttpServerError::Settings(
-                    format!(
-                        "Invalid size in max_content_len '{value}': value must be >0 and <= {CONTENT_LEN_LIMIT}", 
-                    )
-                ))
-            }
-            None => {
-                Err(HttpServerError::Settings(
-                    format!(
-                        "Invalid size in max_content_len: '{value}'. Should be a number, optionally followed by 'K', 'M', or 'G'. Example: '10M'. Value must be <= i32::MAX")
-                ))
-            }
+    }
+    match limit {
+        Some(x) if x > 0 && x <= CONTENT_LEN_LIMIT => Ok(x),
+        Some(_) => {
+            Err(HttpServerError::Settings(
+                format!(
+                    "Invalid size in max_content_len '{value}': value must be >0 and <= {CONTENT_LEN_LIMIT}", 
+                )
+            ))
+        }
+        None => {
+            Err(HttpServerError::Settings(
+                format!(
+                    "Invalid size in max_content_len: '{value}'. Should be a number, optionally followed by 'K', 'M', or 'G'. Example: '10M'. Value must be <= i32::MAX")
+            ))
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::convert_human_size;
+    use crate::DEFAULT_MAX_CONTENT_LEN;
 
     #[test]
     fn parse_max_content_len() {
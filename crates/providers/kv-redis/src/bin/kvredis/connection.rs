@@ -0,0 +1,454 @@
+//! Connections to standalone, clustered, and sentinel-managed Redis deployments.
+//!
+//! The deployment is selected with the `MODE` link value:
+//! - `standalone` (default): `URL` is the URL of a single Redis server
+//! - `cluster`: `URL` is a comma-separated list of cluster nodes used to discover the cluster.
+//!   Commands are routed to the node serving the slot of their key.
+//! - `sentinel`: `URL` is a comma-separated list of sentinels, which are asked for the address of
+//!   the primary named by `SENTINEL_MASTER`. The primary is looked up again when it fails over.
+//!
+//! `USERNAME` and `PASSWORD` set ACL credentials for the Redis servers, and `TLS` (or a `rediss://`
+//! URL) enables TLS connections to them. In sentinel mode, credentials and TLS settings of the
+//! sentinels themselves are taken from their URLs.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use redis::aio::ConnectionManager;
+use redis::cluster::ClusterClient;
+use redis::cluster_async::ClusterConnection;
+use redis::sentinel::{Sentinel, SentinelNodeConnectionInfo};
+use redis::{
+    ConnectionAddr, ConnectionInfo, ErrorKind, FromRedisValue, IntoConnectionInfo,
+    RedisConnectionInfo, RedisError, RedisResult, TlsMode,
+};
+use tracing::{info, warn};
+
+use crate::get_redis_url;
+
+const MODE_KEY: &str = "MODE";
+const SENTINEL_MASTER_KEY: &str = "SENTINEL_MASTER";
+const USERNAME_KEY: &str = "USERNAME";
+const PASSWORD_KEY: &str = "PASSWORD";
+const TLS_KEY: &str = "TLS";
+const TLS_INSECURE_KEY: &str = "TLS_INSECURE";
+
+/// Number of hash slots of a Redis Cluster
+const CLUSTER_SLOTS: u16 = 16384;
+
+/// Kind of Redis deployment a link connects to
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum Mode {
+    #[default]
+    Standalone,
+    Cluster,
+    Sentinel,
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Mode::Standalone => "standalone",
+            Mode::Cluster => "cluster",
+            Mode::Sentinel => "sentinel",
+        })
+    }
+}
+
+/// Connection settings of an actor link
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct ConnectionConfig {
+    pub(crate) mode: Mode,
+    /// URL of the server, or URLs of the cluster nodes or sentinels
+    pub(crate) urls: Vec<String>,
+    /// name of the primary monitored by the sentinels
+    sentinel_master: Option<String>,
+    username: Option<String>,
+    password: Option<String>,
+    tls: bool,
+    /// skip verification of server certificates
+    tls_insecure: bool,
+}
+
+impl ConnectionConfig {
+    /// Reads the connection settings from link values, using `default_connect_url`
+    /// if the link has no `URL`
+    pub(crate) fn from_link_values(
+        link_values: &[(String, String)],
+        default_connect_url: &str,
+    ) -> RedisResult<Self> {
+        let mode = match link_value(link_values, MODE_KEY).map(str::to_ascii_lowercase) {
+            None => Mode::Standalone,
+            Some(mode) => match mode.as_str() {
+                "standalone" => Mode::Standalone,
+                "cluster" => Mode::Cluster,
+                "sentinel" => Mode::Sentinel,
+                _ => return Err(invalid_config(format!("unknown {MODE_KEY} '{mode}'"))),
+            },
+        };
+        let urls: Vec<String> = get_redis_url(link_values, default_connect_url)
+            .split(',')
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .map(String::from)
+            .collect();
+        match (mode, urls.len()) {
+            (_, 0) => return Err(invalid_config("missing Redis URL".to_string())),
+            (Mode::Standalone, 1) | (Mode::Cluster | Mode::Sentinel, _) => {}
+            (Mode::Standalone, _) => {
+                return Err(invalid_config(format!(
+                    "several URLs require {MODE_KEY} 'cluster' or 'sentinel'"
+                )))
+            }
+        }
+        let sentinel_master = link_value(link_values, SENTINEL_MASTER_KEY).map(String::from);
+        if mode == Mode::Sentinel && sentinel_master.is_none() {
+            return Err(invalid_config(format!(
+                "sentinel mode requires {SENTINEL_MASTER_KEY}"
+            )));
+        }
+        let tls_insecure = flag_value(link_values, TLS_INSECURE_KEY)?;
+        Ok(ConnectionConfig {
+            mode,
+            urls,
+            sentinel_master,
+            username: link_value(link_values, USERNAME_KEY).map(String::from),
+            password: link_value(link_values, PASSWORD_KEY).map(String::from),
+            tls: flag_value(link_values, TLS_KEY)? || tls_insecure,
+            tls_insecure,
+        })
+    }
+
+    /// Connection info of a Redis server, with the credentials and TLS settings of the link applied
+    fn server_info(&self, url: &str) -> RedisResult<ConnectionInfo> {
+        let mut info = url.into_connection_info()?;
+        if self.username.is_some() {
+            info.redis.username = self.username.clone();
+        }
+        if self.password.is_some() {
+            info.redis.password = self.password.clone();
+        }
+        match info.addr {
+            ConnectionAddr::Tcp(host, port) if self.tls => {
+                info.addr = ConnectionAddr::TcpTls {
+                    host,
+                    port,
+                    insecure: self.tls_insecure,
+                };
+            }
+            ConnectionAddr::TcpTls { host, port, .. } if self.tls_insecure => {
+                info.addr = ConnectionAddr::TcpTls {
+                    host,
+                    port,
+                    insecure: true,
+                };
+            }
+            _ => {}
+        }
+        Ok(info)
+    }
+
+    /// Connection settings passed to the sentinels for connecting to the primary
+    fn sentinel_node_info(&self) -> SentinelNodeConnectionInfo {
+        SentinelNodeConnectionInfo {
+            tls_mode: match (self.tls, self.tls_insecure) {
+                (false, _) => None,
+                (true, false) => Some(TlsMode::Secure),
+                (true, true) => Some(TlsMode::Insecure),
+            },
+            redis_connection_info: Some(RedisConnectionInfo {
+                db: 0,
+                username: self.username.clone(),
+                password: self.password.clone(),
+            }),
+        }
+    }
+
+    /// Connects to the Redis deployment
+    pub(crate) async fn connect(&self) -> RedisResult<RedisConnection> {
+        match self.mode {
+            Mode::Standalone => {
+                let client = redis::Client::open(self.server_info(&self.urls[0])?)?;
+                Ok(RedisConnection::Standalone(
+                    client.get_tokio_connection_manager().await?,
+                ))
+            }
+            Mode::Cluster => {
+                let nodes = self
+                    .urls
+                    .iter()
+                    .map(|url| self.server_info(url))
+                    .collect::<RedisResult<Vec<_>>>()?;
+                let client = ClusterClient::builder(nodes).build()?;
+                Ok(RedisConnection::Cluster(
+                    client.get_async_connection().await?,
+                ))
+            }
+            Mode::Sentinel => {
+                let mut conn = SentinelConnection {
+                    sentinel: Sentinel::build(self.urls.clone())?,
+                    master_name: self.sentinel_master.clone().unwrap_or_default(),
+                    node_info: self.sentinel_node_info(),
+                    conn: None,
+                };
+                conn.connect().await?;
+                Ok(RedisConnection::Sentinel(Box::new(conn)))
+            }
+        }
+    }
+}
+
+/// Connection of an actor to its Redis deployment
+pub(crate) enum RedisConnection {
+    Standalone(ConnectionManager),
+    Cluster(ClusterConnection),
+    Sentinel(Box<SentinelConnection>),
+}
+
+impl RedisConnection {
+    /// Sends a command and returns its response
+    pub(crate) async fn query<T: FromRedisValue>(&mut self, cmd: &redis::Cmd) -> RedisResult<T> {
+        match self {
+            RedisConnection::Standalone(conn) => cmd.query_async(conn).await,
+            RedisConnection::Cluster(conn) => cmd.query_async(conn).await,
+            RedisConnection::Sentinel(conn) => conn.query(cmd).await,
+        }
+    }
+
+    /// Returns the intersection or union of sets.
+    ///
+    /// A cluster only runs commands whose keys all hash to the same slot,
+    /// so sets in different slots are read one by one and combined here.
+    pub(crate) async fn set_op(
+        &mut self,
+        op: SetOp,
+        keys: Vec<String>,
+    ) -> RedisResult<Vec<String>> {
+        let in_one_slot = keys
+            .windows(2)
+            .all(|k| key_slot(k[0].as_bytes()) == key_slot(k[1].as_bytes()));
+        let RedisConnection::Cluster(conn) = self else {
+            return self.query(&op.cmd(keys)).await;
+        };
+        if in_one_slot {
+            return op.cmd(keys).query_async(conn).await;
+        }
+        let mut result: Option<HashSet<String>> = None;
+        for key in keys {
+            let members: HashSet<String> = redis::Cmd::smembers(key).query_async(conn).await?;
+            result = Some(match (result, op) {
+                (None, _) => members,
+                (Some(acc), SetOp::Intersection) => acc.intersection(&members).cloned().collect(),
+                (Some(mut acc), SetOp::Union) => {
+                    acc.extend(members);
+                    acc
+                }
+            });
+            if op == SetOp::Intersection && result.as_ref().is_some_and(HashSet::is_empty) {
+                break;
+            }
+        }
+        Ok(result.unwrap_or_default().into_iter().collect())
+    }
+}
+
+/// Operation combining several sets
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SetOp {
+    Intersection,
+    Union,
+}
+
+impl SetOp {
+    fn cmd(self, keys: Vec<String>) -> redis::Cmd {
+        match self {
+            SetOp::Intersection => redis::Cmd::sinter(keys),
+            SetOp::Union => redis::Cmd::sunion(keys),
+        }
+    }
+}
+
+/// Connection to the primary of a sentinel-managed deployment.
+///
+/// The primary is looked up from the sentinels when connecting, and again after the connection
+/// fails or the server reports that it is no longer the primary.
+pub(crate) struct SentinelConnection {
+    sentinel: Sentinel,
+    master_name: String,
+    node_info: SentinelNodeConnectionInfo,
+    conn: Option<ConnectionManager>,
+}
+
+impl SentinelConnection {
+    async fn connect(&mut self) -> RedisResult<&mut ConnectionManager> {
+        let client = self
+            .sentinel
+            .async_master_for(&self.master_name, Some(&self.node_info))
+            .await?;
+        info!(master = %self.master_name, addr = %client.get_connection_info().addr, "connecting to Redis primary");
+        Ok(self
+            .conn
+            .insert(client.get_tokio_connection_manager().await?))
+    }
+
+    async fn query<T: FromRedisValue>(&mut self, cmd: &redis::Cmd) -> RedisResult<T> {
+        let conn = match self.conn.as_mut() {
+            Some(conn) => conn,
+            None => self.connect().await?,
+        };
+        let err = match cmd.query_async(conn).await {
+            Err(err) if is_failover(&err) => err,
+            res => return res,
+        };
+        warn!(master = %self.master_name, %err, "Redis primary unavailable, asking sentinels for the current primary");
+        self.conn = None;
+        let conn = self.connect().await?;
+        // The command can only be sent again if the server did not run it
+        if is_not_run(&err) {
+            cmd.query_async(conn).await
+        } else {
+            Err(err)
+        }
+    }
+}
+
+/// Returns true if an error means the primary may have changed
+fn is_failover(err: &RedisError) -> bool {
+    is_not_run(err) || err.is_io_error() || err.is_connection_dropped()
+}
+
+/// Returns true if a command was not run because the server is unreachable or not the primary
+fn is_not_run(err: &RedisError) -> bool {
+    matches!(err.kind(), ErrorKind::ReadOnly | ErrorKind::MasterDown) || err.is_connection_refusal()
+}
+
+/// Returns the cluster hash slot of a key.
+/// Only the part of the key inside the first non-empty `{...}` hash tag is hashed, if there is one.
+pub(crate) fn key_slot(key: &[u8]) -> u16 {
+    let tagged = key.iter().position(|&b| b == b'{').and_then(|open| {
+        let rest = &key[open + 1..];
+        match rest.iter().position(|&b| b == b'}') {
+            Some(close) if close > 0 => Some(&rest[..close]),
+            _ => None,
+        }
+    });
+    crc16(tagged.unwrap_or(key)) % CLUSTER_SLOTS
+}
+
+/// CRC16-CCITT (XMODEM), the key hash of Redis Cluster
+fn crc16(data: &[u8]) -> u16 {
+    data.iter().fold(0u16, |crc, &byte| {
+        (0..8).fold(crc ^ (u16::from(byte) << 8), |crc, _| {
+            if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            }
+        })
+    })
+}
+
+/// Finds a link value by case-insensitive key
+fn link_value<'a>(link_values: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    link_values
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(key))
+        .map(|(_, v)| v.trim())
+}
+
+fn flag_value(link_values: &[(String, String)], key: &str) -> RedisResult<bool> {
+    match link_value(link_values, key) {
+        None => Ok(false),
+        Some(v) if v.eq_ignore_ascii_case("true") => Ok(true),
+        Some(v) if v.eq_ignore_ascii_case("false") => Ok(false),
+        Some(v) => Err(invalid_config(format!(
+            "{key} must be 'true' or 'false', not '{v}'"
+        ))),
+    }
+}
+
+fn invalid_config(detail: String) -> RedisError {
+    RedisError::from((
+        ErrorKind::InvalidClientConfig,
+        "invalid link values",
+        detail,
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn values(values: &[(&str, &str)]) -> Vec<(String, String)> {
+        values
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parses_link_values() {
+        let config = ConnectionConfig::from_link_values(&[], "redis://127.0.0.1:6379").unwrap();
+        assert_eq!(config.mode, Mode::Standalone);
+        assert_eq!(config.urls, vec!["redis://127.0.0.1:6379".to_string()]);
+
+        let config = ConnectionConfig::from_link_values(
+            &values(&[
+                ("mode", "Cluster"),
+                ("URL", "redis://node1:6379, redis://node2:6379"),
+                ("username", "app"),
+                ("PASSWORD", "secret"),
+                ("tls", "true"),
+            ]),
+            "",
+        )
+        .unwrap();
+        assert_eq!(config.mode, Mode::Cluster);
+        assert_eq!(config.urls.len(), 2);
+        let info = config.server_info(&config.urls[1]).unwrap();
+        assert_eq!(
+            info.addr,
+            ConnectionAddr::TcpTls {
+                host: "node2".to_string(),
+                port: 6379,
+                insecure: false
+            }
+        );
+        assert_eq!(info.redis.username.as_deref(), Some("app"));
+        assert_eq!(info.redis.password.as_deref(), Some("secret"));
+
+        let config = ConnectionConfig::from_link_values(
+            &values(&[("MODE", "sentinel"), ("SENTINEL_MASTER", "mymaster")]),
+            "redis://sentinel:26379",
+        )
+        .unwrap();
+        assert_eq!(config.sentinel_master.as_deref(), Some("mymaster"));
+        assert!(config.sentinel_node_info().tls_mode.is_none());
+
+        for invalid in [
+            values(&[("MODE", "replicated")]),
+            values(&[("MODE", "sentinel")]),
+            values(&[("URL", "redis://a:6379,redis://b:6379")]),
+            values(&[("TLS", "yes")]),
+        ] {
+            assert!(ConnectionConfig::from_link_values(&invalid, "redis://127.0.0.1").is_err());
+        }
+    }
+
+    #[test]
+    fn computes_key_slots() {
+        assert_eq!(crc16(b"123456789"), 0x31c3);
+        assert_eq!(key_slot(b"foo"), 12182);
+        assert_eq!(key_slot(b"{user1000}.following"), key_slot(b"user1000"));
+        assert_eq!(
+            key_slot(b"{user1000}.following"),
+            key_slot(b"{user1000}.followers")
+        );
+        // empty hash tags are not used
+        assert_eq!(
+            key_slot(b"foo{}{bar}"),
+            crc16(b"foo{}{bar}") % CLUSTER_SLOTS
+        );
+        assert_eq!(key_slot(b"foo{{bar}}zap"), key_slot(b"{bar"));
+    }
+}
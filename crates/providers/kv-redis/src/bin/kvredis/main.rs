@@ -13,10 +13,8 @@
 //!
 //!
 use std::collections::HashMap;
-use std::ops::DerefMut;
 use std::sync::Arc;
 
-use redis::aio::ConnectionManager;
 use redis::FromRedisValue;
 use tokio::sync::RwLock;
 use tracing::{error, info, instrument, warn};
@@ -30,6 +28,10 @@ use wasmcloud_provider_wit_bindgen::deps::{
     wasmcloud_provider_sdk::{load_host_data, Context},
 };
 
+use crate::connection::{ConnectionConfig, RedisConnection, SetOp};
+
+mod connection;
+
 wasmcloud_provider_wit_bindgen::generate!({
     impl_struct: KvRedisProvider,
     contract: "wasmcloud:keyvalue",
@@ -82,7 +84,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 #[derive(Default, Clone)]
 struct KvRedisProvider {
     // store redis connections per actor
-    actors: Arc<RwLock<HashMap<String, RwLock<ConnectionManager>>>>,
+    actors: Arc<RwLock<HashMap<String, Arc<RwLock<RedisConnection>>>>>,
     // Default connection URL for actors without a `URL` link value
     default_connect_url: String,
 }
@@ -105,29 +107,31 @@ impl WasmcloudCapabilityProvider for KvRedisProvider {
     /// If the link is allowed, return true, otherwise return false to deny the link.
     #[instrument(level = "debug", skip(self, ld), fields(actor_id = %ld.actor_id))]
     async fn put_link(&self, ld: &LinkDefinition) -> bool {
-        let redis_url = get_redis_url(&ld.values, &self.default_connect_url);
-
-        match redis::Client::open(redis_url.clone()) {
-            Ok(client) => match client.get_tokio_connection_manager().await {
-                Ok(conn_manager) => {
-                    info!(redis_url, "established link");
-                    let mut update_map = self.actors.write().await;
-                    update_map.insert(ld.actor_id.to_string(), RwLock::new(conn_manager));
-                }
-                Err(err) => {
-                    warn!(
-                        redis_url,
-                        ?err,
-                    "Could not create Redis connection manager for actor {}, keyvalue operations will fail",
+        let config = match ConnectionConfig::from_link_values(&ld.values, &self.default_connect_url)
+        {
+            Ok(config) => config,
+            Err(err) => {
+                warn!(
+                    ?err,
+                    "Invalid Redis link values for actor {}, keyvalue operations will fail",
                     ld.actor_id
                 );
-                    return false;
-                }
-            },
+                return false;
+            }
+        };
+
+        match config.connect().await {
+            Ok(conn) => {
+                info!(mode = %config.mode, redis_urls = ?config.urls, "established link");
+                let mut update_map = self.actors.write().await;
+                update_map.insert(ld.actor_id.to_string(), Arc::new(RwLock::new(conn)));
+            }
             Err(err) => {
                 warn!(
+                    mode = %config.mode,
+                    redis_urls = ?config.urls,
                     ?err,
-                    "Could not create Redis client for actor {}, keyvalue operations will fail",
+                    "Could not connect to Redis for actor {}, keyvalue operations will fail",
                     ld.actor_id
                 );
                 return false;
@@ -264,8 +268,7 @@ impl WasmcloudKeyvalueKeyValue for KvRedisProvider {
 
     #[instrument(level = "debug", skip(self, ctx, arg), fields(actor_id = ?ctx.actor, keys = ?arg))]
     async fn set_intersection(&self, ctx: Context, arg: Vec<String>) -> Vec<String> {
-        let mut cmd = redis::Cmd::sinter(arg);
-        self.exec(&ctx, &mut cmd).await
+        self.exec_set_op(&ctx, SetOp::Intersection, arg).await
     }
 
     #[instrument(level = "debug", skip(self, ctx, arg), fields(actor_id = ?ctx.actor, key = %arg.to_string()))]
@@ -276,8 +279,7 @@ impl WasmcloudKeyvalueKeyValue for KvRedisProvider {
 
     #[instrument(level = "debug", skip(self, ctx, arg), fields(actor_id = ?ctx.actor, keys = ?arg))]
     async fn set_union(&self, ctx: Context, arg: Vec<String>) -> Vec<String> {
-        let mut cmd = redis::Cmd::sunion(arg);
-        self.exec(&ctx, &mut cmd).await
+        self.exec_set_op(&ctx, SetOp::Union, arg).await
     }
 }
 
@@ -294,26 +296,17 @@ impl KvRedisProvider {
     /// message passing between actors and this provider, including serialization
     /// of requests and deserialization of responses, which are fully parallelizable.
     ///
-    /// There is a read lock held on the actors hashtable, which does not interfere
-    /// with redis operations, but any control commands for new actor links
-    /// or removal of actor links may need to wait for in-progress operations to complete.
-    /// That should be rare, because most links are passed to the provider at startup.
+    /// The read lock on the actors hashtable is only held while looking up the connection,
+    /// so control commands for new actor links or removal of actor links do not wait
+    /// for in-progress operations to complete.
     async fn exec<T: FromRedisValue + Default>(&self, ctx: &Context, cmd: &mut redis::Cmd) -> T {
-        let Some(actor_id) = ctx.actor.as_ref() else {
-            error!("missing actor reference in execution context");
-            return T::default();
-        };
-
-        // get read lock on actor-connections hashmap
-        let rd = self.actors.read().await;
-        let Some(rc) = rd.get(actor_id) else {
-            error!("No Redis connection found for actor {actor_id}. Please ensure the URL supplied in the link definition is a valid Redis URL");
+        let Some(rc) = self.connection(ctx).await else {
             return T::default();
         };
 
         // get write lock on this actor's connection
         let mut con = rc.write().await;
-        match cmd.query_async(con.deref_mut()).await {
+        match con.query(cmd).await {
             Ok(v) => v,
             Err(e) => {
                 error!("failed to perform redis command: {e}");
@@ -321,6 +314,39 @@ impl KvRedisProvider {
             }
         }
     }
+
+    /// Helper function to intersect or union sets, like [exec](#exec).
+    /// On a Redis Cluster, sets in different hash slots are combined by the provider.
+    async fn exec_set_op(&self, ctx: &Context, op: SetOp, keys: Vec<String>) -> Vec<String> {
+        let Some(rc) = self.connection(ctx).await else {
+            return Vec::new();
+        };
+
+        let mut con = rc.write().await;
+        match con.set_op(op, keys).await {
+            Ok(v) => v,
+            Err(e) => {
+                error!("failed to perform redis set operation: {e}");
+                Vec::new()
+            }
+        }
+    }
+
+    /// Returns the connection of the actor making a request
+    async fn connection(&self, ctx: &Context) -> Option<Arc<RwLock<RedisConnection>>> {
+        let Some(actor_id) = ctx.actor.as_ref() else {
+            error!("missing actor reference in execution context");
+            return None;
+        };
+
+        // get read lock on actor-connections hashmap
+        let rd = self.actors.read().await;
+        let rc = rd.get(actor_id).cloned();
+        if rc.is_none() {
+            error!("No Redis connection found for actor {actor_id}. Please ensure the URL supplied in the link definition is a valid Redis URL");
+        }
+        rc
+    }
 }
 
 fn get_redis_url(link_values: &[(String, String)], default_connect_url: &str) -> String {
@@ -1,22 +1,45 @@
 //! Hashicorp vault client
 //!
-use std::{string::ToString, sync::Arc};
+use std::{string::ToString, sync::Arc, time::Duration};
 
-use vaultrs::api::kv2::responses::SecretVersionMetadata;
-use vaultrs::client::{VaultClient, VaultClientSettings};
+use tokio::{sync::RwLock, task::JoinHandle};
+use tracing::{debug, warn};
+use vaultrs::api::kv2::{requests::SetSecretRequestOptions, responses::SecretVersionMetadata};
+use vaultrs::client::{Client as _, VaultClient, VaultClientSettings};
 
 use wasmcloud_provider_wit_bindgen::deps::serde::{de::DeserializeOwned, Serialize};
+use wasmcloud_provider_wit_bindgen::deps::serde_json::Value;
 
-use crate::{config::Config, error::VaultError};
+use crate::{
+    config::{Auth, Config},
+    error::VaultError,
+};
 
 /// Vault HTTP api version. As of Vault 1.9.x (Feb 2022), all http api calls use version 1
 const API_VERSION: u8 = 1;
 
+/// Number of times a read-modify-write of a secret is attempted before giving up,
+/// when the secret is concurrently modified by other writers
+const MAX_CAS_ATTEMPTS: usize = 10;
+
+/// Interval at which failed logins and token lookups are retried
+const AUTH_RETRY_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Aborts the token maintenance task once the last clone of a [`Client`] is dropped
+struct AuthTask(JoinHandle<()>);
+
+impl Drop for AuthTask {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
 /// Vault client connection information.
 #[derive(Clone)]
 pub struct Client {
-    inner: Arc<vaultrs::client::VaultClient>,
+    inner: Arc<RwLock<VaultClient>>,
     namespace: String,
+    _auth: Arc<AuthTask>,
 }
 
 impl Client {
@@ -24,25 +47,35 @@ impl Client {
     ///
     /// Note that this constructor does not attempt to connect to the vault server,
     /// so the vault server does not need to be running at the time a LinkDefinition to this provider is created.
+    /// Logging in and renewing the token happens in a background task, which must be spawned
+    /// within a tokio runtime.
     pub fn new(config: Config) -> Result<Self, VaultError> {
+        let token = match &config.auth {
+            Auth::Token(token) => token.clone(),
+            Auth::AppRole { .. } | Auth::Kubernetes { .. } => String::new(),
+        };
+        let inner = Arc::new(RwLock::new(VaultClient::new(VaultClientSettings {
+            token,
+            address: config.addr,
+            ca_certs: config.certs,
+            verify: false,
+            version: API_VERSION,
+            wrapping: false,
+            timeout: None,
+            namespace: None,
+        })?));
+        let auth = tokio::spawn(maintain_token(Arc::clone(&inner), config.auth));
         Ok(Client {
-            inner: Arc::new(VaultClient::new(VaultClientSettings {
-                token: config.token,
-                address: config.addr,
-                ca_certs: config.certs,
-                verify: false,
-                version: API_VERSION,
-                wrapping: false,
-                timeout: None,
-                namespace: None,
-            })?),
+            inner,
             namespace: config.mount,
+            _auth: Arc::new(AuthTask(auth)),
         })
     }
 
     /// Reads value of secret using namespace and key path
     pub async fn read_secret<D: DeserializeOwned>(&self, path: &str) -> Result<D, VaultError> {
-        match vaultrs::kv2::read(self.inner.as_ref(), &self.namespace, path).await {
+        let inner = self.inner.read().await;
+        match vaultrs::kv2::read(&*inner, &self.namespace, path).await {
             Err(vaultrs::error::ClientError::APIError {
                 code: 404,
                 errors: _,
@@ -61,23 +94,105 @@ impl Client {
         path: &str,
         data: &T,
     ) -> Result<SecretVersionMetadata, VaultError> {
-        vaultrs::kv2::set(self.inner.as_ref(), &self.namespace, path, data)
+        let inner = self.inner.read().await;
+        vaultrs::kv2::set(&*inner, &self.namespace, path, data)
             .await
             .map_err(VaultError::from)
     }
 
+    /// Reads the current version of the secret along with its value, which is `None` if the
+    /// secret does not exist or its current version is deleted. The version is 0 if the secret
+    /// has never been written.
+    async fn read_versioned(&self, path: &str) -> Result<(Option<Value>, u64), VaultError> {
+        let inner = self.inner.read().await;
+        let version = match vaultrs::kv2::read_metadata(&*inner, &self.namespace, path).await {
+            Ok(metadata) => metadata.current_version,
+            Err(vaultrs::error::ClientError::APIError { code: 404, .. }) => return Ok((None, 0)),
+            Err(e) => return Err(e.into()),
+        };
+        match vaultrs::kv2::read_version(&*inner, &self.namespace, path, version).await {
+            Ok(value) => Ok((Some(value), version)),
+            Err(vaultrs::error::ClientError::APIError { code: 404, .. }) => Ok((None, version)),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Writes value of secret, if the current version of the secret is still `version`
+    async fn write_versioned(
+        &self,
+        path: &str,
+        data: &Value,
+        version: u64,
+    ) -> Result<(), VaultError> {
+        let inner = self.inner.read().await;
+        let cas = version.try_into().unwrap_or(u32::MAX);
+        match vaultrs::kv2::set_with_options(
+            &*inner,
+            &self.namespace,
+            path,
+            data,
+            SetSecretRequestOptions { cas },
+        )
+        .await
+        {
+            Ok(_) => Ok(()),
+            Err(vaultrs::error::ClientError::APIError { code: 400, errors })
+                if errors.iter().any(|e| e.contains("check-and-set")) =>
+            {
+                Err(VaultError::CheckAndSet {
+                    path: path.to_string(),
+                })
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Atomically updates the value of a secret using check-and-set.
+    ///
+    /// `update` is called with the current value of the secret, or `None` if it does not exist,
+    /// and returns the new value to write, or `None` to leave the secret unchanged, along with the
+    /// result to return. If the secret is modified concurrently, `update` is called again with
+    /// the newer value.
+    pub async fn update<R>(
+        &self,
+        path: &str,
+        mut update: impl FnMut(Option<Value>) -> Result<(Option<Value>, R), VaultError>,
+    ) -> Result<R, VaultError> {
+        let mut attempt = 1;
+        loop {
+            let (current, version) = self.read_versioned(path).await?;
+            let (value, res) = update(current)?;
+            let Some(value) = value else {
+                return Ok(res);
+            };
+            match self.write_versioned(path, &value, version).await {
+                Ok(()) => return Ok(res),
+                Err(VaultError::CheckAndSet { .. }) if attempt < MAX_CAS_ATTEMPTS => {
+                    debug!(
+                        path,
+                        version, attempt, "secret modified concurrently, retrying"
+                    );
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
     /// Deletes the latest version of the secret. Note that if versions are in use, only the latest is deleted
     /// Returns Ok if the key was deleted, or Err for any other error including key not found
     pub async fn delete_latest(&self, path: impl AsRef<str>) -> Result<(), VaultError> {
         let path = path.as_ref();
-        vaultrs::kv2::delete_latest(self.inner.as_ref(), &self.namespace, path)
+        let inner = self.inner.read().await;
+        vaultrs::kv2::delete_latest(&*inner, &self.namespace, path)
             .await
             .map_err(VaultError::from)
     }
 
     /// Lists keys at the path
     pub async fn list_secrets(&self, path: &str) -> Result<Vec<String>, VaultError> {
-        match vaultrs::kv2::list(self.inner.as_ref(), &self.namespace, path).await {
+        let inner = self.inner.read().await;
+        match vaultrs::kv2::list(&*inner, &self.namespace, path).await {
             Err(vaultrs::error::ClientError::APIError {
                 code: 404,
                 errors: _,
@@ -90,3 +205,89 @@ impl Client {
         }
     }
 }
+
+/// Logs in with `auth`, if it is a login method, and sets the resulting token on `client`.
+/// Returns the lease duration in seconds of the token and whether it is renewable
+async fn login(client: &RwLock<VaultClient>, auth: &Auth) -> Result<(u64, bool), VaultError> {
+    let info = match auth {
+        Auth::Token(_) => {
+            let token = vaultrs::token::lookup_self(&*client.read().await).await?;
+            return Ok((token.ttl, token.renewable));
+        }
+        Auth::AppRole {
+            mount,
+            role_id,
+            secret_id,
+        } => {
+            vaultrs::auth::approle::login(&*client.read().await, mount, role_id, secret_id).await?
+        }
+        Auth::Kubernetes {
+            mount,
+            role,
+            jwt,
+            jwt_path,
+        } => {
+            let jwt = match jwt {
+                Some(jwt) => jwt.clone(),
+                None => tokio::fs::read_to_string(jwt_path)
+                    .await
+                    .map_err(|source| VaultError::Jwt {
+                        path: jwt_path.clone(),
+                        source,
+                    })?
+                    .trim()
+                    .to_string(),
+            };
+            vaultrs::auth::kubernetes::login(&*client.read().await, mount, role, &jwt).await?
+        }
+    };
+    client.write().await.set_token(&info.client_token);
+    Ok((info.lease_duration, info.renewable))
+}
+
+/// Keeps the token of `client` valid: logs in, renews the token once two thirds of its lease
+/// have passed, and logs in again when the token cannot be renewed. Returns once the token does
+/// not expire, or a static token cannot be renewed.
+async fn maintain_token(client: Arc<RwLock<VaultClient>>, auth: Auth) {
+    let mut renew = false;
+    loop {
+        let lease = if renew {
+            vaultrs::token::renew_self(&*client.read().await, None)
+                .await
+                .map(|info| (info.lease_duration, info.renewable))
+                .map_err(VaultError::from)
+        } else {
+            login(&client, &auth).await
+        };
+        match lease {
+            Ok((0, _)) => {
+                debug!("vault token does not expire");
+                return;
+            }
+            Ok((lease_duration, false)) if matches!(auth, Auth::Token(_)) => {
+                warn!(
+                    lease_duration,
+                    "vault token is not renewable and will expire"
+                );
+                return;
+            }
+            Ok((lease_duration, renewable)) => {
+                debug!(lease_duration, renewable, "vault token acquired");
+                renew = renewable;
+                tokio::time::sleep(Duration::from_secs(lease_duration) * 2 / 3).await;
+            }
+            Err(e) if renew => {
+                warn!(error = %e, "failed to renew vault token, logging in again");
+                renew = false;
+            }
+            Err(e) => {
+                warn!(
+                    error = %e,
+                    "failed to authenticate to vault, retrying in {}s",
+                    AUTH_RETRY_INTERVAL.as_secs()
+                );
+                tokio::time::sleep(AUTH_RETRY_INTERVAL).await;
+            }
+        }
+    }
+}
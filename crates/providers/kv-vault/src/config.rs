@@ -1,7 +1,7 @@
 //! Configuration for kv-vault capability provider
 //!
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use std::{collections::HashMap, env};
 use url::Url;
 
@@ -9,12 +9,53 @@ use url::Url;
 /// used if unspecified by configuration
 const DEFAULT_VAULT_ADDR: &str = "http://127.0.0.1:8200";
 
+/// Default mount point of the AppRole auth method
+const DEFAULT_APPROLE_MOUNT: &str = "approle";
+
+/// Default mount point of the Kubernetes auth method
+const DEFAULT_KUBERNETES_MOUNT: &str = "kubernetes";
+
+/// Default path of the service account token mounted into Kubernetes pods
+const DEFAULT_JWT_PATH: &str = "/var/run/secrets/kubernetes.io/serviceaccount/token";
+
+/// Method used to authenticate to vault
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Auth {
+    /// Static token
+    Token(String),
+    /// [AppRole](https://developer.hashicorp.com/vault/docs/auth/approle) login
+    AppRole {
+        /// Mount point of the auth method
+        mount: String,
+        role_id: String,
+        secret_id: String,
+    },
+    /// [Kubernetes](https://developer.hashicorp.com/vault/docs/auth/kubernetes) login with a
+    /// service account JWT
+    Kubernetes {
+        /// Mount point of the auth method
+        mount: String,
+        role: String,
+        /// JWT to log in with. If unset, the JWT is read from `jwt_path` on every login,
+        /// so that rotated service account tokens are picked up
+        jwt: Option<String>,
+        jwt_path: String,
+    },
+}
+
 /// KV-Vault configuration
 #[derive(Clone, Debug)]
 pub struct Config {
-    /// Token for connecting to vault, can be set in environment with VAULT_TOKEN.
-    /// Required
-    pub token: String,
+    /// Method used to authenticate to vault, selected with `auth_method` (or VAULT_AUTH_METHOD)
+    /// as one of `token`, `approle` or `kubernetes`. Defaults to `token`.
+    ///
+    /// - `token` requires `token` (VAULT_TOKEN)
+    /// - `approle` requires `role_id` (VAULT_ROLE_ID) and `secret_id` (VAULT_SECRET_ID)
+    /// - `kubernetes` requires `role` (VAULT_ROLE), and uses `jwt` (VAULT_JWT) or the file at
+    ///   `jwt_path` (VAULT_JWT_PATH), which defaults to the service account token of the pod
+    ///
+    /// The mount point of the login methods can be set with `auth_mount` (VAULT_AUTH_MOUNT)
+    pub auth: Auth,
     /// Url for connecting to vault, can be set in environment with VAULT_ADDR.
     /// Defaults to 'http://127.0.0.1:8200'
     pub addr: Url,
@@ -34,14 +75,19 @@ impl Default for Config {
     }
 }
 
+/// Looks up the setting `name` in the environment as `VAULT_<NAME>`, falling back to the
+/// linkdef value `name` in lowercase or uppercase
+fn setting(values: &HashMap<String, String>, name: &str) -> Option<String> {
+    env::var(format!("VAULT_{}", name.to_uppercase()))
+        .ok()
+        .or_else(|| values.get(name).cloned())
+        .or_else(|| values.get(&name.to_uppercase()).cloned())
+}
+
 impl Config {
     /// initialize from linkdef values, environment, and defaults
     pub fn from_values(values: &HashMap<String, String>) -> Result<Config> {
-        let addr = env::var("VAULT_ADDR")
-            .ok()
-            .or_else(|| values.get("addr").cloned())
-            .or_else(|| values.get("ADDR").cloned())
-            .unwrap_or_else(|| DEFAULT_VAULT_ADDR.to_string());
+        let addr = setting(values, "addr").unwrap_or_else(|| DEFAULT_VAULT_ADDR.to_string());
         let addr = addr.parse().unwrap_or_else(|_| {
             eprintln!(
                 "Could not parse VAULT_ADDR [{addr}] as Url, using default of {}",
@@ -49,27 +95,94 @@ impl Config {
             );
             DEFAULT_VAULT_ADDR.parse().unwrap()
         });
-        let token = env::var("VAULT_TOKEN")
-            .ok()
-            .or_else(|| values.get("token").cloned())
-            .or_else(|| values.get("TOKEN").cloned())
-            .context("missing setting for 'token' or VAULT_TOKEN")?;
-        let mount = env::var("VAULT_MOUNT")
-            .ok()
-            .or_else(|| values.get("mount").cloned())
-            .or_else(|| values.get("MOUNT").cloned())
-            .unwrap_or_else(|| "secret".to_string());
-        let certs = env::var("VAULT_CERTS")
-            .ok()
-            .or_else(|| values.get("certs").cloned())
-            .or_else(|| values.get("CERTS").cloned())
+        let auth = match setting(values, "auth_method")
+            .map(|method| method.to_lowercase())
+            .as_deref()
+        {
+            None | Some("token") => Auth::Token(
+                setting(values, "token").context("missing setting for 'token' or VAULT_TOKEN")?,
+            ),
+            Some("approle") => Auth::AppRole {
+                mount: setting(values, "auth_mount")
+                    .unwrap_or_else(|| DEFAULT_APPROLE_MOUNT.to_string()),
+                role_id: setting(values, "role_id")
+                    .context("missing setting for 'role_id' or VAULT_ROLE_ID")?,
+                secret_id: setting(values, "secret_id")
+                    .context("missing setting for 'secret_id' or VAULT_SECRET_ID")?,
+            },
+            Some("kubernetes") => Auth::Kubernetes {
+                mount: setting(values, "auth_mount")
+                    .unwrap_or_else(|| DEFAULT_KUBERNETES_MOUNT.to_string()),
+                role: setting(values, "role")
+                    .context("missing setting for 'role' or VAULT_ROLE")?,
+                jwt: setting(values, "jwt"),
+                jwt_path: setting(values, "jwt_path")
+                    .unwrap_or_else(|| DEFAULT_JWT_PATH.to_string()),
+            },
+            Some(method) => bail!(
+                "invalid auth method '{method}', expected one of 'token', 'approle' or 'kubernetes'"
+            ),
+        };
+        let mount = setting(values, "mount").unwrap_or_else(|| "secret".to_string());
+        let certs = setting(values, "certs")
             .map(|certs| certs.split(',').map(|s| s.trim().to_string()).collect())
             .unwrap_or_default();
         Ok(Config {
+            auth,
             addr,
-            token,
             mount,
             certs,
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn values(values: &[(&str, &str)]) -> HashMap<String, String> {
+        values
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn auth_methods() -> Result<()> {
+        let config = Config::from_values(&values(&[("TOKEN", "s.abc")]))?;
+        assert_eq!(config.auth, Auth::Token("s.abc".into()));
+
+        let config = Config::from_values(&values(&[
+            ("auth_method", "AppRole"),
+            ("role_id", "role"),
+            ("secret_id", "secret"),
+        ]))?;
+        assert_eq!(
+            config.auth,
+            Auth::AppRole {
+                mount: "approle".into(),
+                role_id: "role".into(),
+                secret_id: "secret".into(),
+            }
+        );
+
+        let config = Config::from_values(&values(&[
+            ("auth_method", "kubernetes"),
+            ("auth_mount", "k8s"),
+            ("role", "kv"),
+        ]))?;
+        assert_eq!(
+            config.auth,
+            Auth::Kubernetes {
+                mount: "k8s".into(),
+                role: "kv".into(),
+                jwt: None,
+                jwt_path: DEFAULT_JWT_PATH.into(),
+            }
+        );
+
+        assert!(Config::from_values(&values(&[("auth_method", "approle")])).is_err());
+        assert!(Config::from_values(&values(&[("auth_method", "ldap")])).is_err());
+        Ok(())
+    }
+}
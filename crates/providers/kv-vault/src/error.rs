@@ -9,6 +9,25 @@ pub enum VaultError {
     #[error("Key not found: namespace/key {namespace}/{path}")]
     NotFound { namespace: String, path: String },
 
+    /// The secret was modified since it was read, so a check-and-set write was rejected
+    #[error("Secret {path} was modified concurrently")]
+    CheckAndSet { path: String },
+
+    /// The stored value does not have the type required by the operation
+    #[error("Secret {path} does not hold a {expected}")]
+    InvalidValue {
+        path: String,
+        expected: &'static str,
+    },
+
+    /// The JWT used for Kubernetes auth could not be read
+    #[error("Failed to read JWT from {path}")]
+    Jwt {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
     /// All other errors
     #[error("An error occurred with the request")]
     Client {
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 use anyhow::Context as _;
 use anyhow::Result;
@@ -6,7 +6,7 @@ use tokio::sync::RwLock;
 use tracing::{debug, error, info, instrument};
 
 use wasmcloud_provider_wit_bindgen::deps::{
-    async_trait::async_trait, serde_json, serde_json::Value,
+    async_trait::async_trait, serde_json, serde_json::json, serde_json::Value,
     wasmcloud_provider_sdk::core::LinkDefinition, wasmcloud_provider_sdk::Context,
 };
 
@@ -21,6 +21,12 @@ use crate::error::VaultError;
 /// Token to indicate string data was passed during set
 pub const STRING_VALUE_MARKER: &str = "string_data___";
 
+/// Token to indicate list data was stored by list operations
+pub const LIST_VALUE_MARKER: &str = "list_data___";
+
+/// Token to indicate set data was stored by set operations
+pub const SET_VALUE_MARKER: &str = "set_data___";
+
 wasmcloud_provider_wit_bindgen::generate!({
     impl_struct: KvVaultProvider,
     contract: "wasmcloud:keyvalue",
@@ -125,6 +131,7 @@ impl WasmcloudCapabilityProvider for KvVaultProvider {
 impl WasmcloudKeyvalueKeyValue for KvVaultProvider {
     /// Gets a value for a specified key. Deserialize the value as json
     /// if it's a map containing the key STRING_VALUE_MARKER, with a sting value, return the value
+    /// If it's a list or set, the items are returned as a serialized json array
     /// If it's any other map, the entire map is returned as a serialized json string
     /// If the stored value is a plain string, returns the plain value
     /// All other values are returned as serialized json
@@ -148,6 +155,14 @@ impl WasmcloudKeyvalueKeyValue for KvVaultProvider {
                         value,
                         exists: true,
                     }
+                } else if let Some(items @ Value::Array(_)) = map
+                    .remove(LIST_VALUE_MARKER)
+                    .or_else(|| map.remove(SET_VALUE_MARKER))
+                {
+                    GetResponse {
+                        value: items.to_string(),
+                        exists: true,
+                    }
                 } else {
                     GetResponse {
                         value: serde_json::to_string(&map).unwrap(),
@@ -217,38 +232,128 @@ impl WasmcloudKeyvalueKeyValue for KvVaultProvider {
     }
 
     /// Increments a numeric value, returning the new value
-    async fn increment(&self, _ctx: Context, _arg: IncrementRequest) -> i32 {
-        error!("`increment` not implemented");
-        0
+    #[instrument(level = "debug", skip(self, ctx, arg), fields(actor_id = ?ctx.actor, key = %arg.key))]
+    async fn increment(&self, ctx: Context, arg: IncrementRequest) -> i32 {
+        let client = match self.get_client(&ctx).await {
+            Ok(client) => client,
+            Err(e) => {
+                error!("failed to retrieve client: {e}");
+                return 0;
+            }
+        };
+
+        match client
+            .update(&arg.key, |value| {
+                let value = counter(&arg.key, value)?
+                    .checked_add(arg.value)
+                    .ok_or_else(|| VaultError::InvalidValue {
+                        path: arg.key.clone(),
+                        expected: "32-bit counter",
+                    })?;
+                Ok((
+                    Some(json!({ STRING_VALUE_MARKER: value.to_string() })),
+                    value,
+                ))
+            })
+            .await
+        {
+            Ok(value) => value,
+            Err(e) => {
+                error!(error = %e, "vault increment: error");
+                0
+            }
+        }
     }
 
     /// Append a value onto the end of a list. Returns the new list size
-    async fn list_add(&self, _ctx: Context, _arg: ListAddRequest) -> u32 {
-        error!("`list_add` not implemented");
-        0
+    #[instrument(level = "debug", skip(self, ctx, arg), fields(actor_id = ?ctx.actor, list_name = %arg.list_name))]
+    async fn list_add(&self, ctx: Context, arg: ListAddRequest) -> u32 {
+        let client = match self.get_client(&ctx).await {
+            Ok(client) => client,
+            Err(e) => {
+                error!("failed to retrieve client: {e}");
+                return 0;
+            }
+        };
+
+        match client
+            .update(&arg.list_name, |value| {
+                let mut list = items(&arg.list_name, value, LIST_VALUE_MARKER)?;
+                list.push(arg.value.clone());
+                let len = list.len().try_into().unwrap_or(u32::MAX);
+                Ok((Some(json!({ LIST_VALUE_MARKER: list })), len))
+            })
+            .await
+        {
+            Ok(len) => len,
+            Err(e) => {
+                error!(error = %e, "vault list add: error");
+                0
+            }
+        }
     }
 
     /// Deletes a list and its contents
     /// input: list name
     /// returns: true if the list existed and was deleted
-    async fn list_clear(&self, _ctx: Context, _arg: String) -> bool {
-        error!("`list_clear` not implemented");
-        false
+    #[instrument(level = "debug", skip(self, ctx, arg), fields(actor_id = ?ctx.actor, arg = %arg.to_string()))]
+    async fn list_clear(&self, ctx: Context, arg: String) -> bool {
+        self.contains(ctx.clone(), arg.clone()).await && self.del(ctx, arg).await
     }
 
     /// Deletes an item from a list. Returns true if the item was removed.
-    async fn list_del(&self, _ctx: Context, _arg: ListDelRequest) -> bool {
-        error!("`list_del` not implemented");
-        false
+    #[instrument(level = "debug", skip(self, ctx, arg), fields(actor_id = ?ctx.actor, list_name = %arg.list_name))]
+    async fn list_del(&self, ctx: Context, arg: ListDelRequest) -> bool {
+        let client = match self.get_client(&ctx).await {
+            Ok(client) => client,
+            Err(e) => {
+                error!("failed to retrieve client: {e}");
+                return false;
+            }
+        };
+
+        match client
+            .update(&arg.list_name, |value| {
+                let mut list = items(&arg.list_name, value, LIST_VALUE_MARKER)?;
+                match list.iter().position(|item| *item == arg.value) {
+                    Some(index) => {
+                        list.remove(index);
+                        Ok((Some(json!({ LIST_VALUE_MARKER: list })), true))
+                    }
+                    None => Ok((None, false)),
+                }
+            })
+            .await
+        {
+            Ok(removed) => removed,
+            Err(e) => {
+                error!(error = %e, "vault list del: error");
+                false
+            }
+        }
     }
 
     /// Retrieves a range of values from a list using 0-based indices.
     /// Start and end values are inclusive, for example, (0,10) returns
     /// 11 items if the list contains at least 11 items. If the stop value
     /// is beyond the end of the list, it is treated as the end of the list.
-    async fn list_range(&self, _ctx: Context, _arg: ListRangeRequest) -> Vec<String> {
-        error!("`list_range` not implemented");
-        Vec::new()
+    #[instrument(level = "debug", skip(self, ctx, arg), fields(actor_id = ?ctx.actor, list_name = %arg.list_name))]
+    async fn list_range(&self, ctx: Context, arg: ListRangeRequest) -> Vec<String> {
+        let client = match self.get_client(&ctx).await {
+            Ok(client) => client,
+            Err(e) => {
+                error!("failed to retrieve client: {e}");
+                return Vec::new();
+            }
+        };
+
+        match read_items(&client, &arg.list_name, LIST_VALUE_MARKER).await {
+            Ok(list) => range(list, arg.start, arg.stop),
+            Err(e) => {
+                error!(error = %e, "vault list range: error");
+                Vec::new()
+            }
+        }
     }
 
     /// Sets the value of a key.
@@ -288,20 +393,87 @@ impl WasmcloudKeyvalueKeyValue for KvVaultProvider {
     }
 
     /// Add an item into a set. Returns number of items added
-    async fn set_add(&self, _ctx: Context, _arg: SetAddRequest) -> u32 {
-        error!("`set_add` not implemented");
-        0
+    #[instrument(level = "debug", skip(self, ctx, arg), fields(actor_id = ?ctx.actor, set_name = %arg.set_name))]
+    async fn set_add(&self, ctx: Context, arg: SetAddRequest) -> u32 {
+        let client = match self.get_client(&ctx).await {
+            Ok(client) => client,
+            Err(e) => {
+                error!("failed to retrieve client: {e}");
+                return 0;
+            }
+        };
+
+        match client
+            .update(&arg.set_name, |value| {
+                let mut set: BTreeSet<_> = items(&arg.set_name, value, SET_VALUE_MARKER)?
+                    .into_iter()
+                    .collect();
+                if set.insert(arg.value.clone()) {
+                    Ok((Some(json!({ SET_VALUE_MARKER: set })), 1))
+                } else {
+                    Ok((None, 0))
+                }
+            })
+            .await
+        {
+            Ok(added) => added,
+            Err(e) => {
+                error!(error = %e, "vault set add: error");
+                0
+            }
+        }
     }
 
-    /// Remove a item from the set. Returns
-    async fn set_del(&self, _ctx: Context, _arg: SetDelRequest) -> u32 {
-        error!("`set_del` not implemented");
-        0
+    /// Remove a item from the set. Returns number of items removed
+    #[instrument(level = "debug", skip(self, ctx, arg), fields(actor_id = ?ctx.actor, set_name = %arg.set_name))]
+    async fn set_del(&self, ctx: Context, arg: SetDelRequest) -> u32 {
+        let client = match self.get_client(&ctx).await {
+            Ok(client) => client,
+            Err(e) => {
+                error!("failed to retrieve client: {e}");
+                return 0;
+            }
+        };
+
+        match client
+            .update(&arg.set_name, |value| {
+                let mut set: BTreeSet<_> = items(&arg.set_name, value, SET_VALUE_MARKER)?
+                    .into_iter()
+                    .collect();
+                if set.remove(&arg.value) {
+                    Ok((Some(json!({ SET_VALUE_MARKER: set })), 1))
+                } else {
+                    Ok((None, 0))
+                }
+            })
+            .await
+        {
+            Ok(removed) => removed,
+            Err(e) => {
+                error!(error = %e, "vault set del: error");
+                0
+            }
+        }
     }
 
-    async fn set_intersection(&self, _ctx: Context, _arg: Vec<String>) -> Vec<String> {
-        error!("`set_intersection` not implemented");
-        Vec::new()
+    /// Returns the members present in all of the sets
+    #[instrument(level = "debug", skip(self, ctx, arg), fields(actor_id = ?ctx.actor, sets = ?arg))]
+    async fn set_intersection(&self, ctx: Context, arg: Vec<String>) -> Vec<String> {
+        let client = match self.get_client(&ctx).await {
+            Ok(client) => client,
+            Err(e) => {
+                error!("failed to retrieve client: {e}");
+                return Vec::new();
+            }
+        };
+
+        match read_sets(&client, &arg).await {
+            Ok(sets) => intersection(sets).into_iter().collect(),
+            Err(e) => {
+                error!(error = %e, "vault set intersection: error");
+                Vec::new()
+            }
+        }
     }
 
     /// returns a list of all secrets at the path
@@ -331,16 +503,201 @@ impl WasmcloudKeyvalueKeyValue for KvVaultProvider {
         }
     }
 
-    async fn set_union(&self, _ctx: Context, _arg: Vec<String>) -> Vec<String> {
-        error!("`set_union` not implemented");
-        Vec::new()
+    /// Returns the members present in any of the sets
+    #[instrument(level = "debug", skip(self, ctx, arg), fields(actor_id = ?ctx.actor, sets = ?arg))]
+    async fn set_union(&self, ctx: Context, arg: Vec<String>) -> Vec<String> {
+        let client = match self.get_client(&ctx).await {
+            Ok(client) => client,
+            Err(e) => {
+                error!("failed to retrieve client: {e}");
+                return Vec::new();
+            }
+        };
+
+        match read_sets(&client, &arg).await {
+            Ok(sets) => sets
+                .into_iter()
+                .flatten()
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect(),
+            Err(e) => {
+                error!(error = %e, "vault set union: error");
+                Vec::new()
+            }
+        }
     }
 
     /// Deletes a set and its contents
     /// input: set name
     /// returns: true if the set existed and was deleted
-    async fn set_clear(&self, _ctx: Context, _arg: String) -> bool {
-        error!("`set_clear` not implemented");
-        false
+    #[instrument(level = "debug", skip(self, ctx, arg), fields(actor_id = ?ctx.actor, arg = %arg.to_string()))]
+    async fn set_clear(&self, ctx: Context, arg: String) -> bool {
+        self.contains(ctx.clone(), arg.clone()).await && self.del(ctx, arg).await
+    }
+}
+
+/// Returns the value of a counter stored by `increment` or `set`, which is 0 if it does not exist
+fn counter(path: &str, value: Option<Value>) -> Result<i32, VaultError> {
+    let invalid = || VaultError::InvalidValue {
+        path: path.to_string(),
+        expected: "32-bit counter",
+    };
+    match value {
+        None => Ok(0),
+        Some(Value::Object(mut map)) => match map.remove(STRING_VALUE_MARKER) {
+            Some(Value::String(value)) => value.parse().map_err(|_| invalid()),
+            _ => Err(invalid()),
+        },
+        Some(Value::Number(value)) => value
+            .as_i64()
+            .and_then(|value| value.try_into().ok())
+            .ok_or_else(invalid),
+        Some(_) => Err(invalid()),
+    }
+}
+
+/// Returns the items of a list or set stored under `marker`, which are empty if it does not exist
+fn items(path: &str, value: Option<Value>, marker: &str) -> Result<Vec<String>, VaultError> {
+    let invalid = || VaultError::InvalidValue {
+        path: path.to_string(),
+        expected: if marker == SET_VALUE_MARKER {
+            "set"
+        } else {
+            "list"
+        },
+    };
+    let Some(value) = value else {
+        return Ok(Vec::new());
+    };
+    let Value::Object(mut map) = value else {
+        return Err(invalid());
+    };
+    let Some(Value::Array(items)) = map.remove(marker) else {
+        return Err(invalid());
+    };
+    items
+        .into_iter()
+        .map(|item| match item {
+            Value::String(item) => Ok(item),
+            _ => Err(invalid()),
+        })
+        .collect()
+}
+
+/// Reads the items of the list or set `path`
+async fn read_items(client: &Client, path: &str, marker: &str) -> Result<Vec<String>, VaultError> {
+    match client.read_secret(path).await {
+        Ok(value) => items(path, Some(value), marker),
+        Err(VaultError::NotFound { .. }) => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Reads the members of each of the sets `paths`
+async fn read_sets(client: &Client, paths: &[String]) -> Result<Vec<Vec<String>>, VaultError> {
+    let mut sets = Vec::with_capacity(paths.len());
+    for path in paths {
+        sets.push(read_items(client, path, SET_VALUE_MARKER).await?);
+    }
+    Ok(sets)
+}
+
+/// Returns the members present in all of `sets`, which is empty if there are no sets
+fn intersection(sets: Vec<Vec<String>>) -> BTreeSet<String> {
+    let mut sets = sets
+        .into_iter()
+        .map(|set| set.into_iter().collect::<BTreeSet<_>>());
+    let Some(first) = sets.next() else {
+        return BTreeSet::new();
+    };
+    sets.fold(first, |acc, set| acc.intersection(&set).cloned().collect())
+}
+
+/// Returns the items from `start` to `stop` inclusive. Negative indices count from the end of
+/// the list, and indices beyond the end of the list are treated as the end of the list
+fn range(list: Vec<String>, start: i32, stop: i32) -> Vec<String> {
+    let len = list.len() as i64;
+    let index = |i: i32| {
+        if i < 0 {
+            len + i64::from(i)
+        } else {
+            i64::from(i)
+        }
+    };
+    let start = index(start).max(0);
+    let stop = index(stop).min(len - 1);
+    if start > stop {
+        return Vec::new();
+    }
+    list.into_iter()
+        .skip(start as usize)
+        .take((stop - start + 1) as usize)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn counter_values() {
+        assert_eq!(counter("c", None).unwrap(), 0);
+        assert_eq!(
+            counter("c", Some(json!({ STRING_VALUE_MARKER: "41" }))).unwrap(),
+            41
+        );
+        assert_eq!(counter("c", Some(json!(-3))).unwrap(), -3);
+        assert!(counter("c", Some(json!({ STRING_VALUE_MARKER: "abc" }))).is_err());
+        assert!(counter("c", Some(json!({ "foo": "bar" }))).is_err());
+    }
+
+    #[test]
+    fn list_and_set_items() {
+        assert!(items("l", None, LIST_VALUE_MARKER).unwrap().is_empty());
+        assert_eq!(
+            items(
+                "l",
+                Some(json!({ LIST_VALUE_MARKER: ["a", "b"] })),
+                LIST_VALUE_MARKER
+            )
+            .unwrap(),
+            vec!["a", "b"]
+        );
+        assert!(items(
+            "s",
+            Some(json!({ LIST_VALUE_MARKER: ["a"] })),
+            SET_VALUE_MARKER
+        )
+        .is_err());
+        assert!(items(
+            "l",
+            Some(json!({ STRING_VALUE_MARKER: "a" })),
+            LIST_VALUE_MARKER
+        )
+        .is_err());
+
+        let set = |items: &[&str]| items.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            intersection(vec![
+                set(&["a", "b", "c"]),
+                set(&["c", "b"]),
+                set(&["b", "c", "d"])
+            ]),
+            BTreeSet::from(["b".to_string(), "c".to_string()])
+        );
+        assert!(intersection(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn list_range() {
+        let list: Vec<_> = ["a", "b", "c", "d"].map(String::from).into();
+        assert_eq!(range(list.clone(), 0, 10), list);
+        assert_eq!(range(list.clone(), 1, 2), vec!["b", "c"]);
+        assert_eq!(range(list.clone(), -2, -1), vec!["c", "d"]);
+        assert_eq!(range(list.clone(), -10, 0), vec!["a"]);
+        assert!(range(list.clone(), 3, 1).is_empty());
+        assert!(range(list, 5, 10).is_empty());
+        assert!(range(Vec::new(), 0, -1).is_empty());
     }
 }
@@ -0,0 +1,341 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, ensure, Context, Result};
+use rskafka::client::{consumer::StartOffset, Client, ClientBuilder, SaslConfig};
+
+/// Linkdef value for hosts, accepted as a comma separated string
+const KAFKA_HOSTS: &str = "HOSTS";
+const DEFAULT_HOST: &str = "127.0.0.1:9092";
+
+/// Linkdef value for topic, accepted as a single string
+const KAFKA_TOPIC: &str = "TOPIC";
+const DEFAULT_TOPIC: &str = "my-topic";
+
+/// Linkdef value for the consumer group offsets are committed for
+const KAFKA_CONSUMER_GROUP: &str = "CONSUMER_GROUP";
+
+/// Linkdef value for the number of members of the consumer group the partitions of the topic are
+/// assigned to
+const KAFKA_CONSUMER_GROUP_MEMBERS: &str = "CONSUMER_GROUP_MEMBERS";
+
+/// Linkdef value for the index of this member of the consumer group, counted from 0
+const KAFKA_CONSUMER_GROUP_MEMBER: &str = "CONSUMER_GROUP_MEMBER";
+
+/// Linkdef value for the topic committed offsets are stored in
+const KAFKA_OFFSETS_TOPIC: &str = "OFFSETS_TOPIC";
+const DEFAULT_OFFSETS_TOPIC: &str = "__wasmcloud_consumer_offsets";
+
+/// Linkdef value for the interval offsets are committed at in milliseconds
+const KAFKA_COMMIT_INTERVAL_MS: &str = "COMMIT_INTERVAL_MS";
+const DEFAULT_COMMIT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Linkdef value for the offset consumption starts at, one of `earliest`, `latest` or `committed`
+const KAFKA_START_OFFSET: &str = "START_OFFSET";
+
+/// Linkdef value for the topic replies to requests are received on
+const KAFKA_REPLY_TOPIC: &str = "REPLY_TOPIC";
+
+/// Linkdef value for the timeout of requests in milliseconds, used if a request does not specify one
+const KAFKA_REQUEST_TIMEOUT_MS: &str = "REQUEST_TIMEOUT_MS";
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Linkdef values for SASL authentication, only the `PLAIN` mechanism is supported
+const KAFKA_SASL_MECHANISM: &str = "SASL_MECHANISM";
+const KAFKA_SASL_USERNAME: &str = "SASL_USERNAME";
+const KAFKA_SASL_PASSWORD: &str = "SASL_PASSWORD";
+
+/// Linkdef value to enable TLS, accepted as `true` or `false`
+const KAFKA_TLS: &str = "TLS";
+
+/// Linkdef value for a PEM file of CA certificates to trust in addition to the webpki roots
+const KAFKA_TLS_CA_FILE: &str = "TLS_CA_FILE";
+
+/// Offset consumption of a partition starts at
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum StartFrom {
+    /// The earliest record retained in the partition
+    Earliest,
+    /// The next record produced to the partition
+    Latest,
+    /// The offset committed for the consumer group, or the next record produced to the partition
+    /// if no offset was committed
+    Committed,
+}
+
+impl StartFrom {
+    /// Returns the [`StartOffset`] of a partition, given the offset `committed` for it
+    pub(crate) fn start_offset(self, committed: Option<i64>) -> StartOffset {
+        match (self, committed) {
+            (Self::Earliest, _) => StartOffset::Earliest,
+            (Self::Latest, _) | (Self::Committed, None) => StartOffset::Latest,
+            (Self::Committed, Some(offset)) => StartOffset::At(offset),
+        }
+    }
+}
+
+/// Static membership of a consumer group, used to split the partitions of a topic between
+/// provider instances
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct ConsumerGroup {
+    /// Name of the consumer group, which identifies its committed offsets
+    pub(crate) name: String,
+    /// Number of members of the consumer group
+    pub(crate) members: u32,
+    /// Index of this member of the consumer group
+    pub(crate) member: u32,
+}
+
+impl ConsumerGroup {
+    /// Returns whether `partition` is assigned to this member of the consumer group
+    pub(crate) fn is_assigned(&self, partition: i32) -> bool {
+        u32::try_from(partition).map_or(false, |partition| partition % self.members == self.member)
+    }
+}
+
+/// Configuration of a link, parsed from link definition values
+#[derive(Clone, Debug)]
+pub(crate) struct KafkaConfig {
+    pub(crate) hosts: Vec<String>,
+    pub(crate) topic: String,
+    pub(crate) consumer_group: Option<ConsumerGroup>,
+    pub(crate) offsets_topic: String,
+    pub(crate) commit_interval: Duration,
+    pub(crate) start_from: StartFrom,
+    pub(crate) reply_topic: String,
+    pub(crate) request_timeout: Duration,
+    sasl: Option<SaslConfig>,
+    tls: bool,
+    tls_ca_file: Option<PathBuf>,
+}
+
+impl KafkaConfig {
+    /// Parses the configuration from link definition `values`
+    pub(crate) fn from_tuples(values: &[(String, String)]) -> Result<Self> {
+        let values = values.iter().cloned().collect::<HashMap<_, _>>();
+        let value = |key| values.get(key).map(|value: &String| value.trim());
+
+        // Collect comma separated hosts into a Vec<String>
+        let hosts = value(KAFKA_HOSTS)
+            .unwrap_or(DEFAULT_HOST)
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .collect();
+        let topic = value(KAFKA_TOPIC).unwrap_or(DEFAULT_TOPIC).to_string();
+
+        let consumer_group = if let Some(name) = value(KAFKA_CONSUMER_GROUP) {
+            let members = value(KAFKA_CONSUMER_GROUP_MEMBERS)
+                .map(str::parse)
+                .transpose()
+                .with_context(|| format!("invalid `{KAFKA_CONSUMER_GROUP_MEMBERS}`"))?
+                .unwrap_or(1);
+            let member = value(KAFKA_CONSUMER_GROUP_MEMBER)
+                .map(str::parse)
+                .transpose()
+                .with_context(|| format!("invalid `{KAFKA_CONSUMER_GROUP_MEMBER}`"))?
+                .unwrap_or(0);
+            ensure!(
+                member < members,
+                "`{KAFKA_CONSUMER_GROUP_MEMBER}` must be less than `{KAFKA_CONSUMER_GROUP_MEMBERS}`"
+            );
+            Some(ConsumerGroup {
+                name: name.to_string(),
+                members,
+                member,
+            })
+        } else {
+            None
+        };
+
+        let start_from = match value(KAFKA_START_OFFSET).map(str::to_ascii_lowercase) {
+            Some(start) if start == "earliest" => StartFrom::Earliest,
+            Some(start) if start == "latest" => StartFrom::Latest,
+            Some(start) if start == "committed" => StartFrom::Committed,
+            Some(start) => bail!("invalid `{KAFKA_START_OFFSET}`: `{start}`"),
+            // Resume from committed offsets by default, if they are tracked
+            None if consumer_group.is_some() => StartFrom::Committed,
+            None => StartFrom::Latest,
+        };
+        ensure!(
+            start_from != StartFrom::Committed || consumer_group.is_some(),
+            "`{KAFKA_START_OFFSET}` of `committed` requires `{KAFKA_CONSUMER_GROUP}`"
+        );
+
+        let commit_interval = value(KAFKA_COMMIT_INTERVAL_MS)
+            .map(str::parse)
+            .transpose()
+            .with_context(|| format!("invalid `{KAFKA_COMMIT_INTERVAL_MS}`"))?
+            .map_or(DEFAULT_COMMIT_INTERVAL, Duration::from_millis);
+        ensure!(
+            !commit_interval.is_zero(),
+            "`{KAFKA_COMMIT_INTERVAL_MS}` must be greater than 0"
+        );
+
+        let reply_topic = value(KAFKA_REPLY_TOPIC)
+            .map(ToString::to_string)
+            .unwrap_or_else(|| format!("{topic}.replies"));
+        let request_timeout = value(KAFKA_REQUEST_TIMEOUT_MS)
+            .map(str::parse)
+            .transpose()
+            .with_context(|| format!("invalid `{KAFKA_REQUEST_TIMEOUT_MS}`"))?
+            .map_or(DEFAULT_REQUEST_TIMEOUT, Duration::from_millis);
+
+        let sasl = match (value(KAFKA_SASL_USERNAME), value(KAFKA_SASL_PASSWORD)) {
+            (Some(username), Some(password)) => {
+                if let Some(mechanism) = value(KAFKA_SASL_MECHANISM) {
+                    ensure!(
+                        mechanism.eq_ignore_ascii_case("PLAIN"),
+                        "unsupported `{KAFKA_SASL_MECHANISM}`: `{mechanism}`"
+                    );
+                }
+                Some(SaslConfig::Plain {
+                    username: username.to_string(),
+                    password: password.to_string(),
+                })
+            }
+            (None, None) => None,
+            _ => bail!("`{KAFKA_SASL_USERNAME}` and `{KAFKA_SASL_PASSWORD}` must be set together"),
+        };
+        let tls_ca_file = value(KAFKA_TLS_CA_FILE).map(PathBuf::from);
+        let tls = value(KAFKA_TLS)
+            .map(str::parse)
+            .transpose()
+            .with_context(|| format!("invalid `{KAFKA_TLS}`"))?
+            .unwrap_or(tls_ca_file.is_some());
+
+        Ok(Self {
+            hosts,
+            topic,
+            consumer_group,
+            offsets_topic: value(KAFKA_OFFSETS_TOPIC)
+                .unwrap_or(DEFAULT_OFFSETS_TOPIC)
+                .to_string(),
+            commit_interval,
+            start_from,
+            reply_topic,
+            request_timeout,
+            sasl,
+            tls,
+            tls_ca_file,
+        })
+    }
+
+    /// Builds a client connected to the configured hosts
+    pub(crate) async fn client(&self) -> Result<Client> {
+        let mut builder = ClientBuilder::new(self.hosts.clone());
+        if let Some(sasl) = &self.sasl {
+            builder = builder.sasl_config(sasl.clone());
+        }
+        if self.tls {
+            builder = builder.tls_config(Arc::new(self.tls_config()?));
+        }
+        builder
+            .build()
+            .await
+            .context("failed to build Kafka client")
+    }
+
+    fn tls_config(&self) -> Result<rustls::ClientConfig> {
+        let mut roots = rustls::RootCertStore::empty();
+        roots.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|ta| {
+            rustls::OwnedTrustAnchor::from_subject_spki_name_constraints(
+                ta.subject,
+                ta.spki,
+                ta.name_constraints,
+            )
+        }));
+        if let Some(path) = &self.tls_ca_file {
+            let file =
+                File::open(path).with_context(|| format!("failed to open `{}`", path.display()))?;
+            let certs = rustls_pemfile::certs(&mut BufReader::new(file)).with_context(|| {
+                format!("failed to read certificates from `{}`", path.display())
+            })?;
+            let (added, _) = roots.add_parsable_certificates(&certs);
+            ensure!(added > 0, "no valid certificates in `{}`", path.display());
+        }
+        Ok(rustls::ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(roots)
+            .with_no_client_auth())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn values(values: &[(&str, &str)]) -> Vec<(String, String)> {
+        values
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn default_config() -> Result<()> {
+        let config = KafkaConfig::from_tuples(&[])?;
+        assert_eq!(config.hosts, vec![DEFAULT_HOST]);
+        assert_eq!(config.topic, DEFAULT_TOPIC);
+        assert_eq!(config.consumer_group, None);
+        assert_eq!(config.start_from, StartFrom::Latest);
+        assert_eq!(config.commit_interval, DEFAULT_COMMIT_INTERVAL);
+        assert_eq!(config.reply_topic, "my-topic.replies");
+        assert_eq!(config.request_timeout, DEFAULT_REQUEST_TIMEOUT);
+        assert!(config.sasl.is_none());
+        assert!(!config.tls);
+        Ok(())
+    }
+
+    #[test]
+    fn consumer_group_config() -> Result<()> {
+        let config = KafkaConfig::from_tuples(&values(&[
+            (KAFKA_HOSTS, "kafka-0:9092, kafka-1:9092"),
+            (KAFKA_CONSUMER_GROUP, "orders"),
+            (KAFKA_CONSUMER_GROUP_MEMBERS, "2"),
+            (KAFKA_CONSUMER_GROUP_MEMBER, "1"),
+            (KAFKA_COMMIT_INTERVAL_MS, "1000"),
+        ]))?;
+        assert_eq!(config.hosts, vec!["kafka-0:9092", "kafka-1:9092"]);
+        assert_eq!(config.start_from, StartFrom::Committed);
+        assert_eq!(config.commit_interval, Duration::from_secs(1));
+        let group = config.consumer_group.expect("consumer group missing");
+        assert!(!group.is_assigned(0));
+        assert!(group.is_assigned(1));
+        assert!(group.is_assigned(3));
+
+        assert!(KafkaConfig::from_tuples(&values(&[
+            (KAFKA_CONSUMER_GROUP, "orders"),
+            (KAFKA_CONSUMER_GROUP_MEMBERS, "2"),
+            (KAFKA_CONSUMER_GROUP_MEMBER, "2"),
+        ]))
+        .is_err());
+        assert!(KafkaConfig::from_tuples(&values(&[(KAFKA_START_OFFSET, "committed")])).is_err());
+        assert!(KafkaConfig::from_tuples(&values(&[(KAFKA_COMMIT_INTERVAL_MS, "0")])).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn security_config() -> Result<()> {
+        let config = KafkaConfig::from_tuples(&values(&[
+            (KAFKA_SASL_USERNAME, "user"),
+            (KAFKA_SASL_PASSWORD, "pass"),
+            (KAFKA_TLS, "true"),
+        ]))?;
+        assert!(config.sasl.is_some());
+        assert!(config.tls);
+        config.tls_config()?;
+
+        assert!(KafkaConfig::from_tuples(&values(&[(KAFKA_SASL_USERNAME, "user")])).is_err());
+        assert!(KafkaConfig::from_tuples(&values(&[
+            (KAFKA_SASL_USERNAME, "user"),
+            (KAFKA_SASL_PASSWORD, "pass"),
+            (KAFKA_SASL_MECHANISM, "SCRAM-SHA-256"),
+        ]))
+        .is_err());
+        Ok(())
+    }
+}
@@ -2,12 +2,15 @@
 //!
 use std::collections::{BTreeMap, HashMap};
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
+use futures::future::join_all;
 use futures::StreamExt;
 use rskafka::client::consumer::{StartOffset, StreamConsumerBuilder};
-use rskafka::client::partition::{Compression, UnknownTopicHandling};
-use rskafka::client::ClientBuilder;
+use rskafka::client::partition::{Compression, PartitionClient, UnknownTopicHandling};
+use rskafka::client::Client;
 use rskafka::record::{Record, RecordAndOffset};
+use tokio::sync::OnceCell;
 use tokio::task::JoinHandle;
 use tracing::{debug, error, instrument, warn};
 
@@ -16,25 +19,27 @@ use wasmcloud_provider_wit_bindgen::deps::{
     wasmcloud_provider_sdk::Context,
 };
 
+mod config;
+mod offsets;
+mod request;
+
+use config::KafkaConfig;
+use offsets::{OffsetStore, PendingOffsets};
+use request::{split_reply_to, Replies, CORRELATION_ID_HEADER, REPLY_TO_HEADER};
+
 wasmcloud_provider_wit_bindgen::generate!({
     impl_struct: KafkaMessagingProvider,
     contract: "wasmcloud:messaging",
     wit_bindgen_cfg: "provider-messaging-kafka"
 });
 
-/// Linkdef value for hosts, accepted as a comma separated string
-const KAFKA_HOSTS: &str = "HOSTS";
-const DEFAULT_HOST: &str = "127.0.0.1:9092";
-
-/// Linkdef value for topic, accepted as a single string
-const KAFKA_TOPIC: &str = "TOPIC";
-const DEFAULT_TOPIC: &str = "my-topic";
-
 #[derive(Clone)]
-/// A struct that contains a consumer task handler and the host connection strings
+/// A struct that contains a consumer task handler and the link configuration
 struct KafkaConnection {
-    connection_hosts: Vec<String>,
+    config: KafkaConfig,
     consumer_handle: Arc<JoinHandle<()>>,
+    /// Listener for replies to requests, started on the first request
+    replies: Arc<OnceCell<Replies>>,
 }
 
 #[derive(Clone, Default)]
@@ -44,108 +49,224 @@ pub struct KafkaMessagingProvider {
     connections: Arc<RwLock<HashMap<String, KafkaConnection>>>,
 }
 
+/// Returns the partitions of the configured topic, which are assigned to this provider
+async fn assigned_partitions(client: &Client, config: &KafkaConfig) -> anyhow::Result<Vec<i32>> {
+    let topic = client
+        .list_topics()
+        .await?
+        .into_iter()
+        .find(|topic| topic.name == config.topic)
+        .ok_or_else(|| anyhow::anyhow!("topic `{}` does not exist", config.topic))?;
+    Ok(topic
+        .partitions
+        .into_iter()
+        .filter(|partition| {
+            config
+                .consumer_group
+                .as_ref()
+                .map_or(true, |group| group.is_assigned(*partition))
+        })
+        .collect())
+}
+
+/// Commits the pending offset of `partition`, if any
+async fn commit(
+    offsets: Option<&OffsetStore>,
+    topic: &str,
+    partition: i32,
+    pending: &mut PendingOffsets,
+) {
+    let (Some(offsets), Some(offset)) = (offsets, pending.take()) else {
+        return;
+    };
+    if let Err(e) = offsets.commit(partition, offset).await {
+        warn!(topic, partition, offset, "{e:#}");
+        pending.restore(offset);
+    }
+}
+
+/// Forwards the records of a partition to the actor. If a consumer group is configured, the
+/// offsets of the records the actor handled successfully are committed every `commit_interval`,
+/// up to the first record the actor failed to handle
+async fn consume(
+    ld: &LinkDefinition,
+    topic: &str,
+    partition: i32,
+    partition_client: PartitionClient,
+    start_offset: StartOffset,
+    offsets: Option<&OffsetStore>,
+    commit_interval: Duration,
+) {
+    let mut stream = StreamConsumerBuilder::new(Arc::new(partition_client), start_offset)
+        .with_max_wait_ms(100)
+        .build();
+    let mut pending = PendingOffsets::default();
+    let mut commits = tokio::time::interval(commit_interval);
+    commits.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    // Continue to pull records off the stream until it closes
+    loop {
+        let record = tokio::select! {
+            record = stream.next() => record,
+            _ = commits.tick() => {
+                commit(offsets, topic, partition, &mut pending).await;
+                continue;
+            }
+        };
+        let Some(record) = record else {
+            break;
+        };
+        let (RecordAndOffset { record, offset }, _water_mark) = match record {
+            Ok(record) => record,
+            Err(e) => {
+                error!(topic, partition, "failed to consume record: {e}");
+                break;
+            }
+        };
+        let Some(message) = record.value else {
+            pending.handled(offset);
+            continue;
+        };
+        let reply_to = record
+            .headers
+            .get(REPLY_TO_HEADER)
+            .and_then(|reply_to| String::from_utf8(reply_to.clone()).ok());
+        if let Err(e) = InvocationHandler::new(ld)
+            .handle_message(Message {
+                body: message,
+                reply_to,
+                subject: topic.to_owned(),
+            })
+            .await
+        {
+            error!(
+                topic,
+                partition, offset, "Unable to send subscription: {e:?}"
+            );
+            if offsets.is_some() && pending.failed(offset) {
+                warn!(
+                    topic,
+                    partition,
+                    offset,
+                    "offsets past the failed record will not be committed, so it is redelivered \
+                     along with all later records once consumption of the partition resumes"
+                );
+            }
+            continue;
+        }
+        pending.handled(offset);
+    }
+    commit(offsets, topic, partition, &mut pending).await;
+}
+
 #[async_trait]
 impl WasmcloudCapabilityProvider for KafkaMessagingProvider {
     #[instrument(level = "info", skip(self))]
     async fn put_link(&self, ld: &LinkDefinition) -> bool {
         debug!("putting link for actor {ld:?}");
-        // Collect comma separated hosts into a Vec<String>
-        let hosts = ld
-            .values
-            .iter()
-            .find_map(|(k, v)| {
-                if k == KAFKA_HOSTS {
-                    Some(v.to_string())
-                } else {
-                    None
-                }
-            })
-            .unwrap_or_else(|| DEFAULT_HOST.to_string())
-            .trim()
-            .split(',')
-            .map(|s| s.to_string())
-            .collect::<Vec<String>>();
-
-        // Retrieve or use default topic, trimming off extra whitespace
-        let topic = ld
-            .values
-            .iter()
-            .find_map(|(k, v)| {
-                if k == KAFKA_TOPIC {
-                    Some(v.to_string())
-                } else {
-                    None
-                }
-            })
-            .unwrap_or_else(|| DEFAULT_TOPIC.to_string())
-            .trim()
-            .to_string();
+        let config = match KafkaConfig::from_tuples(&ld.values) {
+            Ok(config) => config,
+            Err(e) => {
+                error!("Failed to build Kafka configuration: {e:#}");
+                return false;
+            }
+        };
 
         // Do some basic validation before spawning off in a thread
-        let Ok(client) = ClientBuilder::new(hosts.clone()).build().await else {
-            warn!(
-                "Could not create Kafka client for actor {}, messages won't be received",
-                ld.actor_id
-            );
-            return true;
+        let client = match config.client().await {
+            Ok(client) => client,
+            Err(e) => {
+                warn!(
+                    "Could not create Kafka client for actor {}, messages won't be received: {e:#}",
+                    ld.actor_id
+                );
+                return true;
+            }
+        };
+        let partitions = match assigned_partitions(&client, &config).await {
+            Ok(partitions) => partitions,
+            Err(e) => {
+                warn!(
+                    "Could not list partitions for actor {}, messages won't be received: {e:#}",
+                    ld.actor_id
+                );
+                return true;
+            }
         };
 
-        // Create a partition client
-        let Ok(partition_client) = client
-            .partition_client(&topic, 0, UnknownTopicHandling::Error)
-            .await
-        else {
-            warn!(
-                "Could not create partition client for actor {}, messages won't be received",
-                ld.actor_id
-            );
-            return true;
+        // Committed offsets are only tracked for consumer groups
+        let (offsets, committed) = if let Some(group) = &config.consumer_group {
+            let offsets =
+                match OffsetStore::new(&client, &config.offsets_topic, &group.name, &config.topic)
+                    .await
+                {
+                    Ok(offsets) => offsets,
+                    Err(e) => {
+                        error!("Failed to access committed offsets: {e:#}");
+                        return false;
+                    }
+                };
+            let committed = match offsets.committed().await {
+                Ok(committed) => committed,
+                Err(e) => {
+                    error!("Failed to read committed offsets: {e:#}");
+                    return false;
+                }
+            };
+            (Some(offsets), committed)
+        } else {
+            (None, HashMap::default())
         };
 
+        // Create a partition client for every assigned partition
+        let mut consumers = Vec::with_capacity(partitions.len());
+        for partition in partitions {
+            let Ok(partition_client) = client
+                .partition_client(&config.topic, partition, UnknownTopicHandling::Error)
+                .await
+            else {
+                warn!(
+                    "Could not create partition client for actor {}, messages won't be received",
+                    ld.actor_id
+                );
+                return true;
+            };
+            let start_offset = config
+                .start_from
+                .start_offset(committed.get(&partition).copied());
+            consumers.push((partition, partition_client, start_offset));
+        }
+
         // Clone for moving into thread
         let ld = ld.clone();
         let actor_id = ld.actor_id.clone();
-        let join = tokio::task::spawn(async move {
-            // construct stream consumer
-            let mut stream =
-            // StartOffset::Latest only processes new messages, but Earliest will send every message.
-            // This could be a linkdef tunable value in the future
-                StreamConsumerBuilder::new(Arc::new(partition_client), StartOffset::Latest)
-                .with_max_wait_ms(100)
-                .build();
-
-            // Continue to pull records off the stream until it closes
-            while let Some(Ok((
-                RecordAndOffset {
-                    record:
-                        Record {
-                            value: Some(message),
-                            ..
-                        },
-                    ..
-                },
-                _water_mark,
-            ))) = stream.next().await
-            {
-                if let Err(e) = InvocationHandler::new(&ld)
-                    .handle_message(Message {
-                        body: message,
-                        reply_to: None,
-                        subject: topic.to_owned(),
-                    })
-                    .await
-                {
-                    eprintln!("Unable to send subscription: {:?}", e);
-                }
-            }
-        });
+        let topic = config.topic.clone();
+        let commit_interval = config.commit_interval;
+        let join =
+            tokio::task::spawn(async move {
+                join_all(consumers.into_iter().map(
+                    |(partition, partition_client, start_offset)| {
+                        consume(
+                            &ld,
+                            &topic,
+                            partition,
+                            partition_client,
+                            start_offset,
+                            offsets.as_ref(),
+                            commit_interval,
+                        )
+                    },
+                ))
+                .await;
+            });
 
         let mut connections = self.connections.write().unwrap();
         connections.insert(
             actor_id,
             KafkaConnection {
+                config,
                 consumer_handle: Arc::new(join),
-                connection_hosts: hosts,
+                replies: Arc::default(),
             },
         );
 
@@ -181,109 +302,187 @@ impl WasmcloudCapabilityProvider for KafkaMessagingProvider {
     }
 }
 
+/// Produces a record with `body` and `headers` to partition 0 of `topic`, which is created if it
+/// does not exist
+async fn produce(
+    client: &Client,
+    topic: &str,
+    body: Vec<u8>,
+    mut headers: BTreeMap<String, Vec<u8>>,
+) -> anyhow::Result<()> {
+    // Ensure topic exists
+    let controller_client = client
+        .controller_client()
+        .map_err(|e| anyhow::anyhow!("failed to build controller client: {e}"))?;
+
+    // TODO: accept linkdef tunable values for these
+    if let Err(e) = controller_client
+        .create_topic(
+            topic, 1,     // partition
+            1,     // replication factor
+            1_000, // timeout (ms)
+        )
+        .await
+    {
+        warn!("could not create topic: {e:?}")
+    }
+
+    // Get a partition-bound client
+    let partition_client = client
+        .partition_client(
+            topic,
+            0, // partition
+            UnknownTopicHandling::Error,
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to create partition client: {e}"))?;
+
+    // produce some data
+    headers.insert("source".to_owned(), b"wasm".to_vec());
+    let records = vec![Record {
+        key: None,
+        value: Some(body),
+        headers,
+        timestamp: chrono::offset::Utc::now(),
+    }];
+
+    partition_client
+        .produce(records, Compression::default())
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to produce record: {e}"))?;
+    Ok(())
+}
+
+impl KafkaMessagingProvider {
+    /// Returns the connection of the actor invoking the provider
+    fn connection(&self, ctx: &Context) -> Option<KafkaConnection> {
+        let connections = match self.connections.read() {
+            Ok(connections) => connections,
+            Err(e) => {
+                error!("failed to read connections: {e}");
+                return None;
+            }
+        };
+        let connection = ctx
+            .actor
+            .as_ref()
+            .and_then(|actor_id| connections.get(actor_id));
+        if connection.is_none() {
+            error!("no actor config for connection");
+        }
+        connection.cloned()
+    }
+}
+
 /// Implement the 'wasmcloud:messaging' capability provider interface
 #[async_trait]
 impl WasmcloudMessagingMessaging for KafkaMessagingProvider {
     #[instrument(
-        level = "debug", 
+        level = "debug",
         skip_all,
         fields(subject = %msg.subject, reply_to = ?msg.reply_to, body_len = %msg.body.len())
     )]
     async fn publish(&self, ctx: Context, msg: Message) -> () {
         debug!("publishing message: {msg:?}");
 
-        let hosts = {
-            let connections = match self.connections.read() {
-                Ok(connections) => connections,
-                Err(e) => {
-                    error!("failed to read connections: {e}");
-                    return;
-                }
-            };
-
-            let config = match connections.get(&ctx.actor.clone().unwrap()) {
-                Some(config) => config,
-                None => {
-                    error!("no actor config for connection");
-                    return;
-                }
-            };
-
-            config.connection_hosts.clone()
+        let Some(KafkaConnection { config, .. }) = self.connection(&ctx) else {
+            return;
         };
 
-        let client = match ClientBuilder::new(hosts).build().await {
+        let client = match config.client().await {
             Ok(client) => client,
             Err(e) => {
-                error!("failed to build client: {e}");
+                error!("failed to build client: {e:#}");
                 return;
             }
         };
 
-        // Ensure topic exists
-        let controller_client = match client.controller_client() {
-            Ok(controller_client) => controller_client,
-            Err(e) => {
-                error!("failed to build controller client: {e}");
-                return;
-            }
+        // Messages published to the `reply_to` of a request are replies to it
+        let (topic, correlation_id) = split_reply_to(&msg.subject);
+        let mut headers = BTreeMap::new();
+        if let Some(correlation_id) = correlation_id {
+            headers.insert(
+                CORRELATION_ID_HEADER.to_owned(),
+                correlation_id.as_bytes().to_vec(),
+            );
+        }
+        if let Some(reply_to) = msg.reply_to {
+            headers.insert(REPLY_TO_HEADER.to_owned(), reply_to.into_bytes());
+        }
+
+        if let Err(e) = produce(&client, topic, msg.body, headers).await {
+            error!("{e:#}");
         };
+    }
 
-        // TODO: accept linkdef tunable values for these
-        if let Err(e) = controller_client
-            .create_topic(
-                msg.subject.to_owned(),
-                1,     // partition
-                1,     // replication factor
-                1_000, // timeout (ms)
-            )
-            .await
-        {
-            warn!("could not create topic: {e:?}")
-        }
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(subject = %msg.subject, timeout_ms = %msg.timeout_ms, body_len = %msg.body.len())
+    )]
+    async fn request(&self, ctx: Context, msg: RequestMessage) -> Message {
+        // Kafka does not support request-reply in the traditional sense, so requests are published
+        // with a reply topic and correlation ID, and the reply is awaited on the reply topic
+        let no_reply = Message {
+            subject: String::default(),
+            reply_to: None,
+            body: Vec::new(),
+        };
+        let Some(KafkaConnection {
+            config, replies, ..
+        }) = self.connection(&ctx)
+        else {
+            return no_reply;
+        };
 
-        // Get a partition-bound client
-        let partition_client = match client
-            .partition_client(
-                msg.subject.to_owned(),
-                0, // partition
-                UnknownTopicHandling::Error,
-            )
+        let client = match config.client().await {
+            Ok(client) => client,
+            Err(e) => {
+                error!("failed to build client: {e:#}");
+                return no_reply;
+            }
+        };
+        let replies = match replies
+            .get_or_try_init(|| Replies::listen(&client, &config.reply_topic))
             .await
         {
-            Ok(partition_client) => partition_client,
+            Ok(replies) => replies,
             Err(e) => {
-                error!("failed to create partition client: {e}");
-                return;
+                error!("failed to listen for replies: {e:#}");
+                return no_reply;
+            }
+        };
+        let (correlation_id, reply_to, reply) = match replies.register() {
+            Ok(pending) => pending,
+            Err(e) => {
+                error!("failed to register request: {e:#}");
+                return no_reply;
             }
         };
 
-        // produce some data
-        let records = vec![Record {
-            key: None,
-            value: Some(msg.body.clone()),
-            headers: BTreeMap::from([("source".to_owned(), b"wasm".to_vec())]),
-            timestamp: chrono::offset::Utc::now(),
-        }];
+        let headers = BTreeMap::from([(REPLY_TO_HEADER.to_owned(), reply_to.into_bytes())]);
+        if let Err(e) = produce(&client, &msg.subject, msg.body, headers).await {
+            error!("{e:#}");
+            replies.cancel(&correlation_id);
+            return no_reply;
+        }
 
-        if let Err(e) = partition_client
-            .produce(records, Compression::default())
-            .await
-        {
-            error!("failed to produce record: {e}");
+        let timeout = if msg.timeout_ms == 0 {
+            config.request_timeout
+        } else {
+            Duration::from_millis(msg.timeout_ms.into())
         };
-    }
-
-    #[instrument(level = "debug", skip_all, fields(subject = %_msg.subject))]
-    async fn request(&self, _ctx: Context, _msg: RequestMessage) -> Message {
-        // Kafka does not support request-reply in the traditional sense. You can publish to a
-        // topic, and get an acknowledgement that it was received, but you can't get a
-        // reply from a consumer on the other side.
-        error!("not implemented (Kafka does not officially support the request-reply paradigm)");
-        Message {
-            subject: String::default(),
-            reply_to: None,
-            body: Vec::new(),
+        match tokio::time::timeout(timeout, reply).await {
+            Ok(Ok(body)) => Message {
+                subject: replies.topic().to_string(),
+                reply_to: None,
+                body,
+            },
+            _ => {
+                error!(correlation_id, "request timed out after {timeout:?}");
+                replies.cancel(&correlation_id);
+                no_reply
+            }
         }
     }
 }
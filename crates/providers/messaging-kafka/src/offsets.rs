@@ -0,0 +1,222 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use anyhow::{Context, Result};
+use rskafka::client::partition::{Compression, OffsetAt, PartitionClient, UnknownTopicHandling};
+use rskafka::client::Client;
+use rskafka::record::{Record, RecordAndOffset};
+use tracing::{debug, warn};
+
+/// Maximum number of bytes fetched at once when reading committed offsets
+const FETCH_MAX_BYTES: i32 = 1_000_000;
+
+/// Number of records retained in the offsets topic per key, above which the topic is assumed not
+/// to be compacted
+const UNCOMPACTED_RECORDS_PER_KEY: usize = 100;
+
+/// Offsets committed by a consumer group for the partitions of a topic.
+///
+/// rskafka does not implement the consumer group APIs of Kafka, so offsets are stored as records
+/// keyed by `<group>/<topic>/<partition>` in a single-partition topic instead, where the latest
+/// record for a key holds the offset of the next record to consume from that partition. Log
+/// compaction must be enabled on that topic to keep it from growing unbounded, since rskafka cannot
+/// create topics with compaction enabled.
+pub(crate) struct OffsetStore {
+    partition_client: PartitionClient,
+    offsets_topic: String,
+    group: String,
+    topic: String,
+}
+
+impl OffsetStore {
+    /// Connects to the offsets topic, which is created if it does not exist
+    pub(crate) async fn new(
+        client: &Client,
+        offsets_topic: &str,
+        group: &str,
+        topic: &str,
+    ) -> Result<Self> {
+        let partition_client = match client
+            .partition_client(offsets_topic, 0, UnknownTopicHandling::Error)
+            .await
+        {
+            Ok(partition_client) => partition_client,
+            Err(_) => {
+                debug!(offsets_topic, "creating offsets topic");
+                let controller_client = client
+                    .controller_client()
+                    .context("failed to build controller client")?;
+                if let Err(e) = controller_client
+                    .create_topic(offsets_topic, 1, 1, 5_000)
+                    .await
+                {
+                    warn!("could not create offsets topic: {e:?}");
+                }
+                client
+                    .partition_client(offsets_topic, 0, UnknownTopicHandling::Retry)
+                    .await
+                    .context("failed to create offsets partition client")?
+            }
+        };
+        Ok(Self {
+            partition_client,
+            offsets_topic: offsets_topic.to_string(),
+            group: group.to_string(),
+            topic: topic.to_string(),
+        })
+    }
+
+    fn key(&self, partition: i32) -> String {
+        format!("{}/{}/{partition}", self.group, self.topic)
+    }
+
+    /// Reads the offsets committed for every partition of the topic
+    pub(crate) async fn committed(&self) -> Result<HashMap<i32, i64>> {
+        let prefix = format!("{}/{}/", self.group, self.topic);
+        let mut offset = self
+            .partition_client
+            .get_offset(OffsetAt::Earliest)
+            .await
+            .context("failed to get earliest offset of offsets topic")?;
+        let end = self
+            .partition_client
+            .get_offset(OffsetAt::Latest)
+            .await
+            .context("failed to get latest offset of offsets topic")?;
+        let mut committed = HashMap::new();
+        let mut keys = HashSet::new();
+        let mut records_read: usize = 0;
+        while offset < end {
+            let (records, _) = self
+                .partition_client
+                .fetch_records(offset, 1..FETCH_MAX_BYTES, 1_000)
+                .await
+                .context("failed to fetch committed offsets")?;
+            let Some(last) = records.last().map(|record| record.offset) else {
+                break;
+            };
+            records_read = records_read.saturating_add(records.len());
+            for RecordAndOffset { record, .. } in records {
+                let (Some(key), Some(value)) = (record.key, record.value) else {
+                    continue;
+                };
+                keys.insert(key.clone());
+                let (Ok(key), Ok(value)) = (String::from_utf8(key), String::from_utf8(value))
+                else {
+                    continue;
+                };
+                let Some(partition) = key.strip_prefix(&prefix) else {
+                    continue;
+                };
+                if let (Ok(partition), Ok(value)) = (partition.parse(), value.parse()) {
+                    committed.insert(partition, value);
+                }
+            }
+            offset = last + 1;
+        }
+        if records_read
+            > keys
+                .len()
+                .max(1)
+                .saturating_mul(UNCOMPACTED_RECORDS_PER_KEY)
+        {
+            warn!(
+                offsets_topic = %self.offsets_topic,
+                records = records_read,
+                keys = keys.len(),
+                "offsets topic retains many superseded offsets and is likely not compacted, which \
+                 makes it grow without bound and slows down linking. Set `cleanup.policy=compact` \
+                 on the topic"
+            );
+        }
+        Ok(committed)
+    }
+
+    /// Commits `offset` as the offset of the next record to consume from `partition`
+    pub(crate) async fn commit(&self, partition: i32, offset: i64) -> Result<()> {
+        let record = Record {
+            key: Some(self.key(partition).into_bytes()),
+            value: Some(offset.to_string().into_bytes()),
+            headers: BTreeMap::new(),
+            timestamp: chrono::offset::Utc::now(),
+        };
+        self.partition_client
+            .produce(vec![record], Compression::default())
+            .await
+            .context("failed to commit offset")?;
+        Ok(())
+    }
+}
+
+/// Offsets of a partition awaiting commit.
+///
+/// Offsets are committed periodically rather than for every record. Once the actor fails to handle
+/// a record, no offset past that record is committed, so that it is redelivered when consumption
+/// of the partition resumes.
+#[derive(Debug, Default)]
+pub(crate) struct PendingOffsets {
+    /// Offset of the next record to consume, if it advanced since the last commit
+    pending: Option<i64>,
+    /// Offset of the first record the actor failed to handle
+    failed: Option<i64>,
+}
+
+impl PendingOffsets {
+    /// Records that the actor handled the record at `offset`
+    pub(crate) fn handled(&mut self, offset: i64) {
+        if self.failed.is_none() {
+            self.pending = Some(offset + 1);
+        }
+    }
+
+    /// Records that the actor failed to handle the record at `offset`, returning `true` if it is
+    /// the first failure in the partition
+    pub(crate) fn failed(&mut self, offset: i64) -> bool {
+        if self.failed.is_some() {
+            return false;
+        }
+        self.failed = Some(offset);
+        true
+    }
+
+    /// Takes the offset to commit, if any
+    pub(crate) fn take(&mut self) -> Option<i64> {
+        self.pending.take()
+    }
+
+    /// Returns `offset` taken by [`Self::take`] after its commit failed, unless a later offset is
+    /// pending already
+    pub(crate) fn restore(&mut self, offset: i64) {
+        self.pending.get_or_insert(offset);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pending_offsets() {
+        let mut offsets = PendingOffsets::default();
+        assert_eq!(offsets.take(), None);
+
+        offsets.handled(0);
+        offsets.handled(1);
+        assert_eq!(offsets.take(), Some(2));
+        assert_eq!(offsets.take(), None);
+
+        offsets.restore(2);
+        offsets.handled(2);
+        assert_eq!(offsets.take(), Some(3));
+        offsets.restore(3);
+        assert_eq!(offsets.take(), Some(3));
+
+        // Nothing past a failed record is committed
+        offsets.handled(3);
+        assert!(offsets.failed(4));
+        offsets.handled(5);
+        assert!(!offsets.failed(6));
+        offsets.handled(7);
+        assert_eq!(offsets.take(), Some(4));
+        assert_eq!(offsets.take(), None);
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result};
+use futures::future::join_all;
+use futures::StreamExt;
+use rskafka::client::consumer::{StartOffset, StreamConsumerBuilder};
+use rskafka::client::partition::{OffsetAt, UnknownTopicHandling};
+use rskafka::client::Client;
+use rskafka::record::RecordAndOffset;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, warn};
+
+/// Header carrying the value delivered to actors as `reply_to` of a message
+pub(crate) const REPLY_TO_HEADER: &str = "reply-to";
+
+/// Header carrying the correlation ID of a request in the reply to it
+pub(crate) const CORRELATION_ID_HEADER: &str = "correlation-id";
+
+/// Separator of the reply topic and the correlation ID in the `reply_to` of a request, which is
+/// not valid in Kafka topic names
+const REPLY_TO_SEPARATOR: char = ':';
+
+/// Splits `subject` into the topic to publish to and the correlation ID of the request being
+/// replied to, if `subject` is the `reply_to` of a request
+pub(crate) fn split_reply_to(subject: &str) -> (&str, Option<&str>) {
+    match subject.split_once(REPLY_TO_SEPARATOR) {
+        Some((topic, correlation_id)) => (topic, Some(correlation_id)),
+        None => (subject, None),
+    }
+}
+
+type Pending = Arc<Mutex<HashMap<String, oneshot::Sender<Vec<u8>>>>>;
+
+/// Listener for replies to requests on a reply topic, which routes replies to the pending
+/// requests using their correlation ID.
+///
+/// A request is published with a `reply-to` header of `<reply topic>:<correlation ID>`, which is
+/// delivered to the responding actor as `reply_to`. Publishing the reply to that `reply_to`
+/// produces a record on the reply topic with a `correlation-id` header.
+pub(crate) struct Replies {
+    topic: String,
+    pending: Pending,
+    listener: JoinHandle<()>,
+}
+
+impl Drop for Replies {
+    fn drop(&mut self) {
+        self.listener.abort();
+    }
+}
+
+impl Replies {
+    /// Starts listening for replies on all partitions of `topic`, which is created if it does not
+    /// exist
+    pub(crate) async fn listen(client: &Client, topic: &str) -> Result<Self> {
+        if client
+            .partition_client(topic, 0, UnknownTopicHandling::Error)
+            .await
+            .is_err()
+        {
+            debug!(topic, "creating reply topic");
+            let controller_client = client
+                .controller_client()
+                .context("failed to build controller client")?;
+            if let Err(e) = controller_client.create_topic(topic, 1, 1, 5_000).await {
+                warn!("could not create reply topic: {e:?}");
+            }
+        }
+        let partitions = client
+            .list_topics()
+            .await
+            .context("failed to list topics")?
+            .into_iter()
+            .find(|t| t.name == topic)
+            .with_context(|| format!("reply topic `{topic}` does not exist"))?
+            .partitions;
+
+        // Resolve the latest offsets up front, so that no reply produced after this returns is
+        // skipped
+        let mut consumers = Vec::with_capacity(partitions.len());
+        for partition in partitions {
+            let partition_client = client
+                .partition_client(topic, partition, UnknownTopicHandling::Retry)
+                .await
+                .context("failed to create reply partition client")?;
+            let offset = partition_client
+                .get_offset(OffsetAt::Latest)
+                .await
+                .context("failed to get latest offset of reply topic")?;
+            consumers.push(
+                StreamConsumerBuilder::new(Arc::new(partition_client), StartOffset::At(offset))
+                    .with_max_wait_ms(100)
+                    .build(),
+            );
+        }
+
+        let pending = Pending::default();
+        let listener = tokio::spawn({
+            let pending = Arc::clone(&pending);
+            async move {
+                join_all(consumers.into_iter().map(|mut stream| {
+                    let pending = Arc::clone(&pending);
+                    async move {
+                        while let Some(record) = stream.next().await {
+                            let (RecordAndOffset { record, .. }, _water_mark) = match record {
+                                Ok(record) => record,
+                                Err(e) => {
+                                    error!("failed to consume reply: {e}");
+                                    break;
+                                }
+                            };
+                            let Some(correlation_id) = record
+                                .headers
+                                .get(CORRELATION_ID_HEADER)
+                                .and_then(|id| std::str::from_utf8(id).ok())
+                            else {
+                                continue;
+                            };
+                            let Some(tx) = pending
+                                .lock()
+                                .ok()
+                                .and_then(|mut pending| pending.remove(correlation_id))
+                            else {
+                                // The reply is for a request of another provider instance, or it
+                                // timed out already
+                                continue;
+                            };
+                            let _ = tx.send(record.value.unwrap_or_default());
+                        }
+                    }
+                }))
+                .await;
+            }
+        });
+        Ok(Self {
+            topic: topic.to_string(),
+            pending,
+            listener,
+        })
+    }
+
+    /// Topic the replies are received on
+    pub(crate) fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    /// Registers a pending request, returning the `reply_to` to publish it with and a receiver
+    /// of the reply body
+    pub(crate) fn register(&self) -> Result<(String, String, oneshot::Receiver<Vec<u8>>)> {
+        let correlation_id = uuid::Uuid::new_v4().to_string();
+        let (tx, rx) = oneshot::channel();
+        self.pending
+            .lock()
+            .map_err(|_| anyhow::anyhow!("pending requests lock poisoned"))?
+            .insert(correlation_id.clone(), tx);
+        let reply_to = format!("{}{REPLY_TO_SEPARATOR}{correlation_id}", self.topic);
+        Ok((correlation_id, reply_to, rx))
+    }
+
+    /// Removes a pending request, which will not receive a reply
+    pub(crate) fn cancel(&self, correlation_id: &str) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.remove(correlation_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reply_to() {
+        assert_eq!(split_reply_to("orders"), ("orders", None));
+        assert_eq!(
+            split_reply_to("orders.replies:2c5ea4c0-4067-11e9-8bad-9b1deb4d3b7d"),
+            (
+                "orders.replies",
+                Some("2c5ea4c0-4067-11e9-8bad-9b1deb4d3b7d")
+            )
+        );
+    }
+}
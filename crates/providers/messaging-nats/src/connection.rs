@@ -10,17 +10,77 @@ use wasmcloud_provider_wit_bindgen::deps::{
 
 const DEFAULT_NATS_URI: &str = "0.0.0.0:4222";
 
+/// Default maximum number of messages dispatched to the actor concurrently per link.
+// MAGIC NUMBER: Based on our benchmark testing, this seems to be a good upper limit
+// where we start to get diminishing returns.
+pub const DEFAULT_MAX_IN_FLIGHT: usize = 75;
+
 const ENV_NATS_SUBSCRIPTION: &str = "SUBSCRIPTION";
 const ENV_NATS_URI: &str = "URI";
 const ENV_NATS_CLIENT_JWT: &str = "CLIENT_JWT";
 const ENV_NATS_CLIENT_SEED: &str = "CLIENT_SEED";
+const ENV_NATS_CONSUMERS: &str = "CONSUMERS";
+const ENV_NATS_MAX_DELIVER: &str = "MAX_DELIVER";
+const ENV_NATS_BACKOFF_MS: &str = "BACKOFF_MS";
+const ENV_NATS_JS_DOMAIN: &str = "JS_DOMAIN";
+const ENV_NATS_MAX_IN_FLIGHT: &str = "MAX_IN_FLIGHT";
+
+/// Parses a subscription entry, formatted as `subject` or `subject|queue_group`, into the subject
+/// and the queue group to subscribe in, if any
+pub fn parse_subscription(sub: &str) -> Result<(&str, Option<&str>)> {
+    let (subject, queue_group) = match sub.split_once('|') {
+        Some((subject, queue_group)) => (subject.trim(), Some(queue_group.trim())),
+        None => (sub.trim(), None),
+    };
+    if subject.is_empty() {
+        bail!("invalid subscription `{sub}`, subject must not be empty");
+    }
+    if let Some(queue_group) = queue_group {
+        if queue_group.is_empty() || queue_group.contains(['|', ' ']) {
+            bail!("invalid subscription `{sub}`, expected `subject|queue_group`");
+        }
+    }
+    Ok((subject, queue_group))
+}
+
+/// JetStream durable pull consumer, which delivers the messages of a stream to the actor at least
+/// once. Messages are acknowledged after the actor handled them successfully, and redelivered
+/// otherwise.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "wasmcloud_provider_wit_bindgen::deps::serde")]
+pub struct ConsumerConfig {
+    /// Stream to consume
+    pub stream: String,
+
+    /// Name of the durable consumer, which is created if it does not exist
+    pub durable_name: String,
+
+    /// Only deliver messages with subjects matching this filter
+    #[serde(default)]
+    pub filter_subject: Option<String>,
+
+    /// Subjects to create the stream with if it does not exist. The stream must exist if empty
+    #[serde(default)]
+    pub stream_subjects: Vec<String>,
+
+    /// Maximum number of times a message is delivered, unlimited if not set
+    #[serde(default)]
+    pub max_deliver: Option<i64>,
+
+    /// Delays in milliseconds before redelivering a message, indexed by the number of times the
+    /// message was delivered already. The last delay is used for all subsequent redeliveries.
+    /// If set, `max_deliver` must be greater than the number of delays
+    #[serde(default)]
+    pub backoff_ms: Vec<u64>,
+}
 
 /// Configuration for connecting a nats client.
 /// More options are available if you use the json than variables in the values string map.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(crate = "wasmcloud_provider_wit_bindgen::deps::serde")]
 pub struct ConnectionConfig {
-    /// List of topics to subscribe to
+    /// List of topics to subscribe to, each formatted as `subject` or `subject|queue_group`.
+    /// Subscriptions in a queue group are load-balanced between all subscribers of the group
     #[serde(default)]
     pub subscriptions: Vec<String>,
 
@@ -39,6 +99,19 @@ pub struct ConnectionConfig {
     /// ping interval in seconds
     #[serde(default)]
     pub ping_interval_sec: Option<u16>,
+
+    /// JetStream durable consumers to deliver messages from
+    #[serde(default)]
+    pub consumers: Vec<ConsumerConfig>,
+
+    /// JetStream domain of the streams of `consumers`
+    #[serde(default)]
+    pub js_domain: Option<String>,
+
+    /// Maximum number of messages dispatched to the actor concurrently, shared by all
+    /// subscriptions and consumers of the link. Defaults to [`DEFAULT_MAX_IN_FLIGHT`]
+    #[serde(default)]
+    pub max_in_flight: Option<usize>,
 }
 
 impl ConnectionConfig {
@@ -64,6 +137,15 @@ impl ConnectionConfig {
         if extra.ping_interval_sec.is_some() {
             out.ping_interval_sec = extra.ping_interval_sec
         }
+        if !extra.consumers.is_empty() {
+            out.consumers = extra.consumers.clone();
+        }
+        if extra.js_domain.is_some() {
+            out.js_domain = extra.js_domain.clone()
+        }
+        if extra.max_in_flight.is_some() {
+            out.max_in_flight = extra.max_in_flight
+        }
         out
     }
 }
@@ -76,6 +158,9 @@ impl Default for ConnectionConfig {
             auth_jwt: None,
             auth_seed: None,
             ping_interval_sec: None,
+            consumers: vec![],
+            js_domain: None,
+            max_in_flight: None,
         }
     }
 }
@@ -110,6 +195,71 @@ impl ConnectionConfig {
         if let Some(seed) = values.get(ENV_NATS_CLIENT_SEED) {
             config.auth_seed = Some(seed.clone());
         }
+        if let Some(consumers) = values.get(ENV_NATS_CONSUMERS) {
+            let max_deliver = values
+                .get(ENV_NATS_MAX_DELIVER)
+                .map(|max_deliver| max_deliver.trim().parse())
+                .transpose()
+                .with_context(|| format!("invalid {ENV_NATS_MAX_DELIVER}"))?;
+            let backoff_ms = values
+                .get(ENV_NATS_BACKOFF_MS)
+                .map(|backoff| {
+                    backoff
+                        .split(',')
+                        .map(|delay| delay.trim().parse())
+                        .collect::<Result<Vec<_>, _>>()
+                })
+                .transpose()
+                .with_context(|| format!("invalid {ENV_NATS_BACKOFF_MS}"))?
+                .unwrap_or_default();
+            for consumer in consumers.split(',').filter(|s| !s.is_empty()) {
+                let mut parts = consumer.split('|').map(str::trim);
+                let (Some(stream), Some(durable_name), filter_subject, None) =
+                    (parts.next(), parts.next(), parts.next(), parts.next())
+                else {
+                    bail!("invalid consumer `{consumer}`, expected `stream|durable_name[|filter_subject]`");
+                };
+                config.consumers.push(ConsumerConfig {
+                    stream: stream.to_string(),
+                    durable_name: durable_name.to_string(),
+                    filter_subject: filter_subject.map(String::from),
+                    max_deliver,
+                    backoff_ms: backoff_ms.clone(),
+                    ..Default::default()
+                });
+            }
+        }
+        if let Some(domain) = values.get(ENV_NATS_JS_DOMAIN) {
+            config.js_domain = Some(domain.clone());
+        }
+        if let Some(max_in_flight) = values.get(ENV_NATS_MAX_IN_FLIGHT) {
+            config.max_in_flight = Some(
+                max_in_flight
+                    .trim()
+                    .parse()
+                    .with_context(|| format!("invalid {ENV_NATS_MAX_IN_FLIGHT}"))?,
+            );
+        }
+        if config.max_in_flight == Some(0) {
+            bail!("{ENV_NATS_MAX_IN_FLIGHT} must be greater than 0");
+        }
+        for sub in config.subscriptions.iter().filter(|s| !s.is_empty()) {
+            parse_subscription(sub)?;
+        }
+        for consumer in config.consumers.iter().filter(|c| !c.backoff_ms.is_empty()) {
+            // NATS rejects consumers with backoff delays that may not all be used
+            if consumer
+                .max_deliver
+                .map_or(true, |max| max <= consumer.backoff_ms.len() as i64)
+            {
+                bail!(
+                    "consumer `{}` of stream `{}` has {} backoff delays, {ENV_NATS_MAX_DELIVER} must be greater than that",
+                    consumer.durable_name,
+                    consumer.stream,
+                    consumer.backoff_ms.len()
+                );
+            }
+        }
         if config.auth_jwt.is_some() && config.auth_seed.is_none() {
             bail!("if you specify jwt, you must also specify a seed");
         }
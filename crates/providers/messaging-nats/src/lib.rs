@@ -3,6 +3,8 @@ use core::time::Duration;
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use anyhow::Context as _;
+use async_nats::jetstream::{self, consumer::pull, AckKind};
 use futures::StreamExt;
 use opentelemetry_nats::{attach_span_context, NatsHeaderInjector};
 use tokio::sync::{OwnedSemaphorePermit, RwLock, Semaphore};
@@ -19,7 +21,7 @@ use wasmcloud_provider_wit_bindgen::deps::{
 };
 
 mod connection;
-use connection::ConnectionConfig;
+use connection::{parse_subscription, ConnectionConfig, ConsumerConfig, DEFAULT_MAX_IN_FLIGHT};
 
 wasmcloud_provider_wit_bindgen::generate!({
     impl_struct: NatsMessagingProvider,
@@ -104,20 +106,44 @@ impl NatsMessagingProvider {
             .connect(url)
             .await?;
 
+        // Limits the messages dispatched to the actor concurrently across all subscriptions and
+        // consumers of the link
+        let semaphore = Arc::new(Semaphore::new(
+            cfg.max_in_flight.unwrap_or(DEFAULT_MAX_IN_FLIGHT),
+        ));
+
         // Connections
         let mut sub_handles = Vec::new();
         for sub in cfg.subscriptions.iter().filter(|s| !s.is_empty()) {
-            let (sub, queue) = match sub.split_once('|') {
-                Some((sub, queue)) => (sub, Some(queue.to_string())),
-                None => (sub.as_str(), None),
-            };
+            let (sub, queue) = parse_subscription(sub)?;
 
             sub_handles.push((
                 sub.to_string(),
-                self.subscribe(&client, ld, sub.to_string(), queue).await?,
+                self.subscribe(
+                    &client,
+                    ld,
+                    sub.to_string(),
+                    queue.map(String::from),
+                    Arc::clone(&semaphore),
+                )
+                .await?,
             ));
         }
 
+        if !cfg.consumers.is_empty() {
+            let jetstream = match cfg.js_domain {
+                Some(domain) => jetstream::with_domain(client.clone(), domain),
+                None => jetstream::new(client.clone()),
+            };
+            for consumer in &cfg.consumers {
+                sub_handles.push((
+                    format!("{}/{}", consumer.stream, consumer.durable_name),
+                    self.consume(&jetstream, ld, consumer, Arc::clone(&semaphore))
+                        .await?,
+                ));
+            }
+        }
+
         Ok(NatsClientBundle {
             client,
             sub_handles,
@@ -131,6 +157,7 @@ impl NatsMessagingProvider {
         ld: &LinkDefinition,
         sub: String,
         queue: Option<String>,
+        semaphore: Arc<Semaphore>,
     ) -> anyhow::Result<JoinHandle<()>> {
         let mut subscriber = match queue {
             Some(queue) => client.queue_subscribe(sub.clone(), queue).await,
@@ -143,15 +170,6 @@ impl NatsMessagingProvider {
         // Spawn a thread that listens for messages coming from NATS
         // this thread is expected to run the full duration that the provider is available
         let join_handle = tokio::spawn(async move {
-            // MAGIC NUMBER: Based on our benchmark testing, this seems to be a good upper limit
-            // where we start to get diminishing returns. We can consider making this
-            // configurable down the line.
-            // NOTE (thomastaylor312): It may be better to have a semaphore pool on the
-            // NatsMessagingProvider struct that has a global limit of permits so that we don't end
-            // up with 20 subscriptions all getting slammed with up to 75 tasks, but we should wait
-            // to do anything until we see what happens with real world usage and benchmarking
-            let semaphore = Arc::new(Semaphore::new(75));
-
             // Listen for NATS message(s)
             while let Some(msg) = subscriber.next().await {
                 debug!(?msg, actor_id = ?link_def.actor_id, "received messsage");
@@ -176,6 +194,129 @@ impl NatsMessagingProvider {
 
         Ok(join_handle)
     }
+
+    /// Add a JetStream durable consumer, creating the consumer and the stream if necessary
+    async fn consume(
+        &self,
+        jetstream: &jetstream::Context,
+        ld: &LinkDefinition,
+        config: &ConsumerConfig,
+        semaphore: Arc<Semaphore>,
+    ) -> anyhow::Result<JoinHandle<()>> {
+        let stream = if config.stream_subjects.is_empty() {
+            jetstream
+                .get_stream(&config.stream)
+                .await
+                .with_context(|| format!("failed to get stream `{}`", config.stream))?
+        } else {
+            jetstream
+                .get_or_create_stream(jetstream::stream::Config {
+                    name: config.stream.clone(),
+                    subjects: config.stream_subjects.clone(),
+                    ..Default::default()
+                })
+                .await
+                .with_context(|| format!("failed to create stream `{}`", config.stream))?
+        };
+        let backoff: Vec<_> = config
+            .backoff_ms
+            .iter()
+            .copied()
+            .map(Duration::from_millis)
+            .collect();
+        let consumer: jetstream::consumer::PullConsumer = stream
+            .get_or_create_consumer(
+                &config.durable_name,
+                pull::Config {
+                    durable_name: Some(config.durable_name.clone()),
+                    filter_subject: config.filter_subject.clone().unwrap_or_default(),
+                    ack_policy: jetstream::consumer::AckPolicy::Explicit,
+                    max_deliver: config.max_deliver.unwrap_or_default(),
+                    backoff: backoff.clone(),
+                    ..Default::default()
+                },
+            )
+            .await
+            .with_context(|| format!("failed to create consumer `{}`", config.durable_name))?;
+        let mut messages = consumer
+            .messages()
+            .await
+            .context("failed to consume messages")?;
+
+        let link_def = ld.to_owned();
+        let backoff: Arc<[Duration]> = backoff.into();
+        debug!(?link_def, ?config, "spawning consumer for link def");
+
+        let join_handle = tokio::spawn(async move {
+            while let Some(msg) = messages.next().await {
+                let msg = match msg {
+                    Ok(msg) => msg,
+                    Err(e) => {
+                        warn!(error = %e, "failed to receive JetStream message");
+                        continue;
+                    }
+                };
+                debug!(?msg, actor_id = ?link_def.actor_id, "received JetStream messsage");
+                let span = tracing::debug_span!("handle_message", actor_id = %link_def.actor_id);
+
+                span.in_scope(|| {
+                    attach_span_context(&msg);
+                });
+
+                let permit = match semaphore.clone().acquire_owned().await {
+                    Ok(p) => p,
+                    Err(_) => {
+                        warn!("Work pool has been closed, exiting consumer");
+                        break;
+                    }
+                };
+
+                tokio::spawn(
+                    dispatch_jetstream_msg(link_def.clone(), msg, Arc::clone(&backoff), permit)
+                        .instrument(span),
+                );
+            }
+        });
+
+        Ok(join_handle)
+    }
+}
+
+/// Dispatch a JetStream message to the actor, acknowledging it if the actor handled it
+/// successfully and requesting redelivery after the configured backoff otherwise
+#[instrument(level = "debug", skip_all, fields(actor_id = %link_def.actor_id, subject = %js_msg.subject))]
+async fn dispatch_jetstream_msg(
+    link_def: LinkDefinition,
+    js_msg: jetstream::Message,
+    backoff: Arc<[Duration]>,
+    _permit: OwnedSemaphorePermit,
+) {
+    let msg = Message {
+        body: js_msg.payload.to_vec(),
+        reply_to: None,
+        subject: js_msg.subject.to_string(),
+    };
+    let actor = InvocationHandler::new(&link_def);
+    let ack = match actor.handle_message(msg).await {
+        Ok(()) => AckKind::Ack,
+        Err(e) => {
+            let delivered = js_msg.info().map(|info| info.delivered).unwrap_or(1);
+            let delay = usize::try_from(delivered.saturating_sub(1))
+                .ok()
+                .and_then(|i| backoff.get(i).or(backoff.last()))
+                .copied();
+            error!(
+                error = %e,
+                delivered,
+                ?delay,
+                "Unable to send message, requesting redelivery"
+            );
+            AckKind::Nak(delay)
+        }
+    };
+    if let Err(e) = js_msg.ack_with(ack).await {
+        error!(error = %e, "failed to acknowledge JetStream message");
+    }
 }
 
 #[instrument(level = "debug", skip_all, fields(actor_id = %link_def.actor_id, subject = %nats_msg.subject, reply_to = ?nats_msg.reply))]
@@ -409,7 +550,7 @@ fn should_strip_headers(topic: &str) -> bool {
 
 #[cfg(test)]
 mod test {
-    use crate::{serde_json, ConnectionConfig, NatsMessagingProvider};
+    use crate::{parse_subscription, serde_json, ConnectionConfig, NatsMessagingProvider};
 
     use wasmcloud_provider_wit_bindgen::deps::wasmcloud_provider_sdk::core::LinkDefinition;
     use wasmcloud_provider_wit_bindgen::deps::wasmcloud_provider_sdk::ProviderHandler;
@@ -452,6 +593,85 @@ mod test {
         assert_eq!(cc3.auth_jwt, Some("jawty".to_string()))
     }
 
+    #[test]
+    fn test_consumers_from_tuples() -> anyhow::Result<()> {
+        let config = ConnectionConfig::from_tuples(&[
+            (
+                String::from("CONSUMERS"),
+                String::from("ORDERS|orders-actor,EVENTS|events-actor|events.created"),
+            ),
+            (String::from("MAX_DELIVER"), String::from("5")),
+            (String::from("BACKOFF_MS"), String::from("100, 1000")),
+            (String::from("JS_DOMAIN"), String::from("leaf")),
+        ])?;
+        assert_eq!(config.consumers.len(), 2);
+        assert_eq!(config.consumers[0].stream, "ORDERS");
+        assert_eq!(config.consumers[0].durable_name, "orders-actor");
+        assert_eq!(config.consumers[0].filter_subject, None);
+        assert_eq!(
+            config.consumers[1].filter_subject.as_deref(),
+            Some("events.created")
+        );
+        assert_eq!(config.consumers[1].max_deliver, Some(5));
+        assert_eq!(config.consumers[1].backoff_ms, [100, 1000]);
+        assert_eq!(config.js_domain.as_deref(), Some("leaf"));
+
+        assert!(ConnectionConfig::from_tuples(&[(
+            String::from("CONSUMERS"),
+            String::from("ORDERS")
+        )])
+        .is_err());
+        for max_deliver in [None, Some("2")] {
+            let mut values = vec![
+                (
+                    String::from("CONSUMERS"),
+                    String::from("ORDERS|orders-actor"),
+                ),
+                (String::from("BACKOFF_MS"), String::from("100,1000")),
+            ];
+            values.extend(max_deliver.map(|max| (String::from("MAX_DELIVER"), max.to_string())));
+            assert!(ConnectionConfig::from_tuples(&values).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_queue_group_subscriptions() -> anyhow::Result<()> {
+        let config = ConnectionConfig::from_tuples(&[
+            (
+                String::from("SUBSCRIPTION"),
+                String::from("example.actor,example.task|work_queue"),
+            ),
+            (String::from("MAX_IN_FLIGHT"), String::from("10")),
+        ])?;
+        assert_eq!(
+            config
+                .subscriptions
+                .iter()
+                .map(|sub| parse_subscription(sub))
+                .collect::<anyhow::Result<Vec<_>>>()?,
+            [
+                ("example.actor", None),
+                ("example.task", Some("work_queue"))
+            ]
+        );
+        assert_eq!(config.max_in_flight, Some(10));
+
+        for invalid in ["example.task|", "|work_queue", "example.task|work|queue"] {
+            assert!(ConnectionConfig::from_tuples(&[(
+                String::from("SUBSCRIPTION"),
+                String::from(invalid)
+            )])
+            .is_err());
+        }
+        assert!(ConnectionConfig::from_tuples(&[(
+            String::from("MAX_IN_FLIGHT"),
+            String::from("0")
+        )])
+        .is_err());
+        Ok(())
+    }
+
     /// Ensure that unlink triggers subscription removal
     /// https://github.com/wasmCloud/capability-providers/issues/196
     ///
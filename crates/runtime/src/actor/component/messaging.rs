@@ -1,14 +1,28 @@
-use super::{Ctx, Instance};
+use super::{Ctx, Instance, InterfaceBindings, InterfaceInstance};
 
 use crate::capability::messaging::{consumer, types};
-use crate::capability::Messaging;
+use crate::capability::{Messaging, MessagingHandler};
 
 use core::time::Duration;
 
+use std::io::Cursor;
 use std::sync::Arc;
 
+use anyhow::Context as _;
 use async_trait::async_trait;
-use tracing::instrument;
+use tokio::io::sink;
+use tokio::sync::Mutex;
+use tracing::{instrument, trace};
+
+pub mod messaging_handler_bindings {
+    wasmtime::component::bindgen!({
+        world: "messaging-handler",
+        async: true,
+        with: {
+           "wasmcloud:messaging/types": crate::capability::messaging::types,
+        },
+    });
+}
 
 impl Instance {
     /// Set [`Messaging`] handler for this [Instance].
@@ -16,6 +30,30 @@ impl Instance {
         self.handler_mut().replace_messaging(messaging);
         self
     }
+
+    /// Instantiates and returns an [`InterfaceInstance<messaging_handler_bindings::MessagingHandler>`] if exported by the [`Instance`].
+    ///
+    /// # Errors
+    ///
+    /// Fails if messaging handler bindings are not exported by the [`Instance`]
+    pub async fn into_messaging_handler(
+        mut self,
+    ) -> anyhow::Result<InterfaceInstance<messaging_handler_bindings::MessagingHandler>> {
+        let instance = self.take_instance().await?;
+        let bindings = if let Ok(bindings) =
+            messaging_handler_bindings::MessagingHandler::new(&mut self.store, &instance)
+        {
+            InterfaceBindings::Interface(bindings)
+        } else {
+            self.guest_bindings(&instance)
+                .map(InterfaceBindings::Guest)
+                .context("failed to instantiate `wasmcloud:messaging/handler` interface")?
+        };
+        Ok(InterfaceInstance {
+            store: Mutex::new(self.store),
+            bindings,
+        })
+    }
 }
 
 #[async_trait]
@@ -63,3 +101,38 @@ impl consumer::Host for Ctx {
             .map_err(|err| format!("{err:#}")))
     }
 }
+
+#[async_trait]
+impl MessagingHandler for InterfaceInstance<messaging_handler_bindings::MessagingHandler> {
+    #[instrument(skip(self, msg))]
+    async fn handle_message(
+        &self,
+        msg: types::BrokerMessage,
+    ) -> anyhow::Result<Result<(), String>> {
+        let mut store = self.store.lock().await;
+        match &self.bindings {
+            InterfaceBindings::Guest(guest) => {
+                let msg = wasmcloud_compat::messaging::SubMessage::from(
+                    wasmcloud_compat::messaging::BrokerMessage::from(msg),
+                );
+                let request = rmp_serde::to_vec_named(&msg).context("failed to encode request")?;
+                guest
+                    .call(
+                        &mut store,
+                        wasmcloud_compat::messaging::HANDLE_MESSAGE_OPERATION,
+                        Cursor::new(request),
+                        sink(),
+                    )
+                    .await
+                    .context("failed to call actor")
+            }
+            InterfaceBindings::Interface(bindings) => {
+                trace!("call `wasmcloud:messaging/handler.handle-message`");
+                bindings
+                    .wasmcloud_messaging_handler()
+                    .call_handle_message(&mut *store, &msg)
+                    .await
+            }
+        }
+    }
+}
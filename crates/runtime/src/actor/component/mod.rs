@@ -1,12 +1,13 @@
-use crate::actor::claims;
+use crate::actor::{claims, configure_store, core_instances, record_compilation, Limits};
 use crate::capability::{builtin, Bus, Interfaces, TargetInterface};
-use crate::Runtime;
+use crate::{ActorConfig, Runtime};
 
 use core::fmt::{self, Debug};
 use core::mem::replace;
 use core::ops::{Deref, DerefMut};
 
 use std::sync::Arc;
+use std::time::Instant;
 
 use anyhow::{anyhow, bail, ensure, Context as _};
 use async_trait::async_trait;
@@ -15,7 +16,7 @@ use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::sync::Mutex;
 use tracing::{error, instrument, trace};
 use wascap::jwt;
-use wasmtime::component::{Linker, Val};
+use wasmtime::component::{InstancePre, Linker, Val};
 use wasmtime_wasi::preview2::command::{self, Command};
 use wasmtime_wasi::preview2::pipe::{
     AsyncReadStream, AsyncWriteStream, ClosedInputStream, ClosedOutputStream,
@@ -36,6 +37,7 @@ mod messaging;
 
 pub(crate) use self::http::incoming_http_bindings;
 pub(crate) use self::logging::logging_bindings;
+pub(crate) use self::messaging::messaging_handler_bindings;
 
 type TableResult<T> = Result<T, TableError>;
 
@@ -203,6 +205,7 @@ struct Ctx {
     stdin: StdioStream<Box<dyn HostInputStream>>,
     stdout: StdioStream<Box<dyn HostOutputStream>>,
     stderr: StdioStream<Box<dyn HostOutputStream>>,
+    limits: Limits,
 }
 
 impl WasiView for Ctx {
@@ -232,11 +235,13 @@ impl Debug for Ctx {
 /// Pre-compiled actor [Component], which is cheapily-[Cloneable](Clone)
 #[derive(Clone)]
 pub struct Component {
-    component: wasmtime::component::Component,
     engine: wasmtime::Engine,
-    linker: Linker<Ctx>,
+    instance_pre: InstancePre<Ctx>,
     claims: Option<jwt::Claims<jwt::Actor>>,
     handler: builtin::HandlerBuilder,
+    actor_config: ActorConfig,
+    /// Number of core instances created by instantiating the component
+    core_instances: usize,
 }
 
 impl Debug for Component {
@@ -244,6 +249,7 @@ impl Debug for Component {
         f.debug_struct("Component")
             .field("claims", &self.claims)
             .field("handler", &self.handler)
+            .field("actor_config", &self.actor_config)
             .field("runtime", &"wasmtime")
             .finish_non_exhaustive()
     }
@@ -491,11 +497,14 @@ fn wasifill(
 
 #[instrument(level = "trace", skip_all)]
 fn instantiate(
-    component: wasmtime::component::Component,
+    instance_pre: InstancePre<Ctx>,
     engine: &wasmtime::Engine,
-    linker: Linker<Ctx>,
+    actor_config: &ActorConfig,
+    core_instances: usize,
     handler: impl Into<builtin::Handler>,
 ) -> anyhow::Result<Instance> {
+    let limits = Limits::from(actor_config);
+    limits.check_instances(core_instances)?;
     let stdin = StdioStream::default();
     let stdout = StdioStream::default();
     let stderr = StdioStream::default();
@@ -516,11 +525,13 @@ fn instantiate(
         stdin,
         stdout,
         stderr,
+        limits,
     };
-    let store = wasmtime::Store::new(engine, ctx);
+    let mut store = wasmtime::Store::new(engine, ctx);
+    configure_store(&mut store, actor_config, |ctx| &mut ctx.limits);
     Ok(Instance {
-        component,
-        linker,
+        instance_pre,
+        instance: None,
         store,
     })
 }
@@ -529,6 +540,17 @@ impl Component {
     /// Extracts [Claims](jwt::Claims) from WebAssembly component and compiles it using [Runtime].
     #[instrument(skip(wasm))]
     pub fn new(rt: &Runtime, wasm: impl AsRef<[u8]>) -> anyhow::Result<Self> {
+        Self::new_with_config(rt, wasm, rt.actor_config.clone())
+    }
+
+    /// Like [Self::new], but instantiates the component with `actor_config` instead of the
+    /// [`ActorConfig`] of the [Runtime]
+    #[instrument(skip(wasm))]
+    pub fn new_with_config(
+        rt: &Runtime,
+        wasm: impl AsRef<[u8]>,
+        actor_config: ActorConfig,
+    ) -> anyhow::Result<Self> {
         let wasm = wasm.as_ref();
         let engine = rt.engine.clone();
         let (resolve, world) =
@@ -539,8 +561,15 @@ impl Component {
                 }
             };
         let claims = claims(wasm)?;
-        let component = wasmtime::component::Component::new(&engine, wasm)
-            .context("failed to compile component")?;
+        let core_instances = core_instances(wasm)?;
+        let start = Instant::now();
+        let component = if let Some(cache) = &rt.compilation_cache {
+            cache.component(&engine, wasm)?
+        } else {
+            wasmtime::component::Component::new(&engine, wasm)
+                .context("failed to compile component")?
+        };
+        record_compilation("component", start.elapsed());
 
         let mut linker = Linker::new(&engine);
 
@@ -557,13 +586,17 @@ impl Component {
         command::add_to_linker(&mut linker).context("failed to link core WASI interfaces")?;
 
         wasifill(&component, &resolve, world, &mut linker);
+        let instance_pre = linker
+            .instantiate_pre(&component)
+            .context("failed to pre-instantiate component")?;
 
         Ok(Self {
-            component,
             engine,
-            linker,
+            instance_pre,
             claims,
             handler: rt.handler.clone(),
+            actor_config,
+            core_instances,
         })
     }
 
@@ -573,6 +606,11 @@ impl Component {
         self.claims.as_ref()
     }
 
+    /// [`ActorConfig`] this [Component] is instantiated with
+    pub(crate) fn actor_config(&self) -> &ActorConfig {
+        &self.actor_config
+    }
+
     /// Like [Self::instantiate], but moves the [Component].
     #[instrument]
     pub fn into_instance(self) -> anyhow::Result<Instance> {
@@ -584,7 +622,13 @@ impl Component {
     pub fn into_instance_claims(
         self,
     ) -> anyhow::Result<(Instance, Option<jwt::Claims<jwt::Actor>>)> {
-        let instance = instantiate(self.component, &self.engine, self.linker, self.handler)?;
+        let instance = instantiate(
+            self.instance_pre,
+            &self.engine,
+            &self.actor_config,
+            self.core_instances,
+            self.handler,
+        )?;
         Ok((instance, self.claims))
     }
 
@@ -592,9 +636,10 @@ impl Component {
     #[instrument]
     pub fn instantiate(&self) -> anyhow::Result<Instance> {
         instantiate(
-            self.component.clone(),
+            self.instance_pre.clone(),
             &self.engine,
-            self.linker.clone(),
+            &self.actor_config,
+            self.core_instances,
             self.handler.clone(),
         )
     }
@@ -622,8 +667,8 @@ impl From<Component> for Option<jwt::Claims<jwt::Actor>> {
 
 /// An instance of a [Component]
 pub struct Instance {
-    component: wasmtime::component::Component,
-    linker: wasmtime::component::Linker<Ctx>,
+    instance_pre: InstancePre<Ctx>,
+    instance: Option<wasmtime::component::Instance>,
     store: wasmtime::Store<Ctx>,
 }
 
@@ -641,6 +686,12 @@ impl Instance {
         &mut self.store.data_mut().handler
     }
 
+    /// Restarts the execution time limit of the [`Instance`]
+    pub(crate) fn restart_deadline(&mut self) {
+        let limits = self.store.data().limits;
+        limits.restart_deadline(&mut self.store);
+    }
+
     /// Reset [`Instance`] state to defaults
     pub async fn reset(&mut self, rt: &Runtime) {
         *self.handler_mut() = rt.handler.clone().into();
@@ -668,24 +719,52 @@ impl Instance {
         Ok(self)
     }
 
-    /// Instantiates and returns [`GuestBindings`] if exported by the [`Instance`].
-    async fn as_guest_bindings(&mut self) -> anyhow::Result<GuestBindings> {
-        // Attempt to instantiate using guest bindings
-        let guest_err = match guest_bindings::Guest::instantiate_async(
-            &mut self.store,
-            &self.component,
-            &self.linker,
-        )
-        .await
-        {
-            Ok((bindings, _)) => return Ok(GuestBindings::Interface(bindings)),
+    /// Instantiates the component within the [`Instance`] store ahead of an invocation, so that
+    /// the invocation itself does not have to.
+    ///
+    /// # Errors
+    ///
+    /// Fails if instantiation fails
+    pub async fn preinstantiate(&mut self) -> anyhow::Result<()> {
+        if self.instance.is_none() {
+            let instance = self.instance_pre.instantiate_async(&mut self.store).await?;
+            self.instance = Some(instance);
+        }
+        Ok(())
+    }
+
+    /// Whether the component has already been instantiated within the [`Instance`] store
+    #[must_use]
+    pub fn is_preinstantiated(&self) -> bool {
+        self.instance.is_some()
+    }
+
+    /// Takes the pre-instantiated component instance, or instantiates the component if there is
+    /// none. Every invocation consumes its own component instance.
+    async fn take_instance(&mut self) -> anyhow::Result<wasmtime::component::Instance> {
+        self.preinstantiate()
+            .await
+            .context("failed to instantiate component")?;
+        self.instance
+            .take()
+            .context("component instance missing after instantiation")
+    }
+
+    /// Returns [`GuestBindings`] if exported by the component `instance`.
+    fn guest_bindings(
+        &mut self,
+        instance: &wasmtime::component::Instance,
+    ) -> anyhow::Result<GuestBindings> {
+        // Attempt to use guest bindings
+        let guest_err = match guest_bindings::Guest::new(&mut self.store, instance) {
+            Ok(bindings) => return Ok(GuestBindings::Interface(bindings)),
             Err(e) => e,
         };
 
-        // Attempt to instantiate using only bindings available in command
-        match Command::instantiate_async(&mut self.store, &self.component, &self.linker).await {
-            Ok((bindings, _)) => Ok(GuestBindings::Command(bindings)),
-            // If neither of the above instantiations worked, the instance cannot be run
+        // Attempt to use only bindings available in command
+        match Command::new(&mut self.store, instance) {
+            Ok(bindings) => Ok(GuestBindings::Command(bindings)),
+            // If neither of the above bindings worked, the instance cannot be run
             Err(command_err) => bail!(
                 r#"failed to instantiate instance (no bindings satisfied exports):
 
@@ -697,6 +776,12 @@ impl Instance {
         }
     }
 
+    /// Instantiates and returns [`GuestBindings`] if exported by the [`Instance`].
+    async fn as_guest_bindings(&mut self) -> anyhow::Result<GuestBindings> {
+        let instance = self.take_instance().await?;
+        self.guest_bindings(&instance)
+    }
+
     /// Invoke an operation on an [Instance] producing a result.
     #[instrument(skip_all)]
     pub async fn call(
@@ -0,0 +1,201 @@
+use super::Config;
+
+use core::fmt;
+use core::time::Duration;
+
+use anyhow::Context as _;
+use tracing::warn;
+
+/// Interval at which the epoch of engines interrupting actors is incremented
+pub(crate) const EPOCH_TICK: Duration = Duration::from_millis(10);
+
+/// Epoch deadline of instances without an execution time limit, which is far enough in the
+/// future to never be reached, but does not overflow when added to the current epoch
+const NO_DEADLINE: u64 = u64::MAX / 2;
+
+/// Resource limit of an actor instance, which was exceeded
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LimitExceeded {
+    /// Linear memory of the instance was requested to grow beyond the limit, in bytes
+    MemorySize(usize),
+    /// A table of the instance was requested to grow beyond the limit, in elements
+    TableElements(u32),
+    /// The instance attempted to create more core instances than permitted
+    Instances,
+    /// The instance executed for longer than permitted
+    ExecutionTime,
+}
+
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MemorySize(limit) => {
+                write!(
+                    f,
+                    "actor linear memory size limit of {limit} bytes exceeded"
+                )
+            }
+            Self::TableElements(limit) => {
+                write!(f, "actor table element limit of {limit} exceeded")
+            }
+            Self::Instances => write!(f, "actor instance count limit exceeded"),
+            Self::ExecutionTime => write!(f, "actor execution time limit exceeded"),
+        }
+    }
+}
+
+impl std::error::Error for LimitExceeded {}
+
+impl LimitExceeded {
+    /// Returns the [`LimitExceeded`] that caused `err`, if any
+    #[must_use]
+    pub fn from_error(err: &anyhow::Error) -> Option<Self> {
+        err.chain().find_map(|err| {
+            if let Some(limit) = err.downcast_ref::<Self>() {
+                return Some(*limit);
+            }
+            match err.downcast_ref::<wasmtime::Trap>() {
+                Some(wasmtime::Trap::Interrupt) => Some(Self::ExecutionTime),
+                _ => None,
+            }
+        })
+    }
+}
+
+/// [`wasmtime::ResourceLimiter`] enforcing the limits of an actor [`Config`], which, unlike
+/// [`wasmtime::StoreLimits`], traps with [`LimitExceeded`] once a limit is exceeded
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Limits {
+    memory_size: Option<usize>,
+    table_elements: Option<u32>,
+    instances: Option<usize>,
+    execution_time: Option<Duration>,
+}
+
+impl From<&Config> for Limits {
+    fn from(
+        Config {
+            max_memory_size,
+            max_table_elements,
+            max_instances,
+            max_execution_time,
+            ..
+        }: &Config,
+    ) -> Self {
+        Self {
+            memory_size: *max_memory_size,
+            table_elements: *max_table_elements,
+            instances: *max_instances,
+            execution_time: *max_execution_time,
+        }
+    }
+}
+
+impl wasmtime::ResourceLimiter for Limits {
+    fn memory_growing(
+        &mut self,
+        _current: usize,
+        desired: usize,
+        _maximum: Option<usize>,
+    ) -> anyhow::Result<bool> {
+        match self.memory_size {
+            Some(limit) if desired > limit => {
+                warn!(desired, limit, "actor linear memory size limit exceeded");
+                Err(LimitExceeded::MemorySize(limit).into())
+            }
+            _ => Ok(true),
+        }
+    }
+
+    fn table_growing(
+        &mut self,
+        _current: u32,
+        desired: u32,
+        _maximum: Option<u32>,
+    ) -> anyhow::Result<bool> {
+        match self.table_elements {
+            Some(limit) if desired > limit => {
+                warn!(desired, limit, "actor table element limit exceeded");
+                Err(LimitExceeded::TableElements(limit).into())
+            }
+            _ => Ok(true),
+        }
+    }
+}
+
+impl Limits {
+    /// Fails with [`LimitExceeded::Instances`] if creating `count` core instances exceeds the
+    /// instance limit. `wasmtime` does not expose a typed error for exceeding its own instance
+    /// limit, so the limit is checked against the instances declared by an actor before it is
+    /// instantiated instead
+    pub(crate) fn check_instances(&self, count: usize) -> Result<(), LimitExceeded> {
+        match self.instances {
+            Some(limit) if count > limit => {
+                warn!(count, limit, "actor instance count limit exceeded");
+                Err(LimitExceeded::Instances)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Restarts the execution time limit of `store`, which is counted from the time the limit was
+    /// last (re)started
+    pub(crate) fn restart_deadline<T>(&self, store: &mut wasmtime::Store<T>) {
+        if let Some(execution_time) = self.execution_time {
+            store.set_epoch_deadline(epoch_ticks(execution_time));
+        }
+    }
+}
+
+/// Configures `store` to enforce the limits of an actor [`Config`], which are stored in the
+/// store data and accessed using `limits`
+pub(crate) fn configure_store<T: 'static>(
+    store: &mut wasmtime::Store<T>,
+    config: &Config,
+    limits: fn(&mut T) -> &mut Limits,
+) {
+    store.limiter(move |data| limits(data));
+    if config.max_execution_time.is_some() {
+        let limits = *limits(store.data_mut());
+        limits.restart_deadline(store);
+    } else {
+        // Epoch interruption is enabled for all engines, so instances without an execution time
+        // limit are given a deadline, which is never reached
+        store.set_epoch_deadline(NO_DEADLINE);
+    }
+    store.epoch_deadline_trap();
+}
+
+/// Returns the number of core instances created by instantiating `wasm`, which is either a core
+/// module or a component. Core instances declared by nested components are counted once
+pub(crate) fn core_instances(wasm: &[u8]) -> anyhow::Result<usize> {
+    let mut payloads = wasmparser::Parser::new(0).parse_all(wasm);
+    if let Some(Ok(wasmparser::Payload::Version {
+        encoding: wasmparser::Encoding::Module,
+        ..
+    })) = payloads.next()
+    {
+        return Ok(1);
+    }
+    let mut count = 0;
+    for payload in payloads {
+        if let wasmparser::Payload::InstanceSection(instances) =
+            payload.context("failed to parse Wasm")?
+        {
+            for instance in instances {
+                if let wasmparser::Instance::Instantiate { .. } =
+                    instance.context("failed to parse core instance")?
+                {
+                    count += 1;
+                }
+            }
+        }
+    }
+    Ok(count)
+}
+
+/// Returns the number of [`EPOCH_TICK`]s in `duration`, rounded up
+fn epoch_ticks(duration: Duration) -> u64 {
+    let ticks = duration.as_nanos().div_ceil(EPOCH_TICK.as_nanos()).max(1);
+    ticks.try_into().unwrap_or(u64::MAX)
+}
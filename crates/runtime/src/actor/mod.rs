@@ -1,28 +1,38 @@
 mod component;
+mod limits;
 mod module;
+mod pool;
 
 pub use component::{
     Component, GuestInstance as ComponentGuestInstance, Instance as ComponentInstance,
     InterfaceInstance as ComponentInterfaceInstance,
 };
+pub use limits::LimitExceeded;
+pub(crate) use limits::{configure_store, core_instances, Limits, EPOCH_TICK};
 pub use module::{
     Config as ModuleConfig, GuestInstance as ModuleGuestInstance, Instance as ModuleInstance,
     Module,
 };
+pub use pool::InstancePool;
 
 use crate::capability::logging::logging;
+use crate::capability::messaging::types::BrokerMessage;
 use crate::capability::{
     Blobstore, Bus, IncomingHttp, KeyValueAtomic, KeyValueReadWrite, Logging, Messaging,
-    OutgoingHttp,
+    MessagingHandler, OutgoingHttp,
 };
 use crate::Runtime;
 
 use core::fmt::Debug;
+use core::num::NonZeroUsize;
+use core::time::Duration;
 
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 use anyhow::{ensure, Context, Result};
 use async_trait::async_trait;
+use opentelemetry::metrics::{Histogram, Unit};
+use opentelemetry::{global, KeyValue};
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite};
 use tracing::instrument;
 use wascap::jwt;
@@ -33,6 +43,21 @@ use wascap::wasm::extract_claims;
 pub struct Config {
     /// Whether actors are required to be signed to be executed
     pub require_signature: bool,
+    /// Maximum size of the linear memory of an actor instance, in bytes
+    pub max_memory_size: Option<usize>,
+    /// Maximum number of elements in a table of an actor instance
+    pub max_table_elements: Option<u32>,
+    /// Maximum number of core WebAssembly instances an actor instance may create, which is checked
+    /// against the core instances declared by the actor before it is instantiated
+    pub max_instances: Option<usize>,
+    /// Maximum time an actor instance may execute for, after which it traps with
+    /// [`LimitExceeded::ExecutionTime`]
+    pub max_execution_time: Option<Duration>,
+    /// Whether to keep an [`InstancePool`] of pre-instantiated instances of each actor
+    pub instance_pool: bool,
+    /// Maximum number of idle instances kept in an [`InstancePool`], which is further bounded by
+    /// the maximum number of concurrent instances of the actor
+    pub instance_pool_size: Option<NonZeroUsize>,
 }
 
 /// Extracts and validates claims contained within `WebAssembly` binary, if such are found
@@ -52,6 +77,21 @@ fn claims(wasm: impl AsRef<[u8]>) -> Result<Option<jwt::Claims<jwt::Actor>>> {
     Ok(Some(claims.claims))
 }
 
+/// Records the time taken to compile an actor of `kind`, either `module` or `component`, with the
+/// global OTEL meter provider
+fn record_compilation(kind: &'static str, duration: Duration) {
+    static COMPILE_DURATION: OnceLock<Histogram<f64>> = OnceLock::new();
+    COMPILE_DURATION
+        .get_or_init(|| {
+            global::meter("wasmcloud-runtime")
+                .f64_histogram("wasmcloud_runtime.actor.compile.duration")
+                .with_description("Duration of actor compilation, including cache lookups")
+                .with_unit(Unit::new("s"))
+                .init()
+        })
+        .record(duration.as_secs_f64(), &[KeyValue::new("kind", kind)]);
+}
+
 /// A pre-loaded wasmCloud actor, which is either a module or a component
 #[derive(Clone, Debug)]
 pub enum Actor {
@@ -69,6 +109,21 @@ impl Actor {
     /// Fails if [Component::new] or [Module::new] fails
     #[instrument(level = "trace", skip_all)]
     pub fn new(rt: &Runtime, wasm: impl AsRef<[u8]>) -> Result<Self> {
+        Self::new_with_config(rt, wasm, rt.actor_config.clone())
+    }
+
+    /// Like [Self::new], but instantiates the actor with `actor_config` instead of the
+    /// [`Config`] of the [Runtime], for example to enforce resource limits specific to the actor.
+    ///
+    /// # Errors
+    ///
+    /// Fails if [Component::new_with_config] or [Module::new_with_config] fails
+    #[instrument(level = "trace", skip_all)]
+    pub fn new_with_config(
+        rt: &Runtime,
+        wasm: impl AsRef<[u8]>,
+        actor_config: Config,
+    ) -> Result<Self> {
         let wasm = wasm.as_ref();
         // TODO: Optimize parsing, add functionality to `wascap` to parse from a custom section
         // directly
@@ -76,9 +131,9 @@ impl Actor {
             Some(Ok(wasmparser::Payload::Version {
                 encoding: wasmparser::Encoding::Component,
                 ..
-            })) => Component::new(rt, wasm).map(Self::Component),
+            })) => Component::new_with_config(rt, wasm, actor_config).map(Self::Component),
             // fallback to module type
-            _ => Module::new(rt, wasm).map(Self::Module),
+            _ => Module::new_with_config(rt, wasm, actor_config).map(Self::Module),
         }
     }
 
@@ -156,6 +211,24 @@ impl Actor {
         }
     }
 
+    /// Creates an empty [`InstancePool`] of pre-instantiated instances of an actor running up to
+    /// `max_instances` concurrent instances, if enabled in the actor [`Config`]. The pool holds up
+    /// to `max_instances` instances, unless bounded further by [`Config::instance_pool_size`].
+    #[instrument(level = "trace", skip(self))]
+    #[must_use]
+    pub fn instance_pool(&self, max_instances: NonZeroUsize) -> Option<InstancePool> {
+        let config = match self {
+            Self::Module(module) => module.actor_config(),
+            Self::Component(component) => component.actor_config(),
+        };
+        config.instance_pool.then(|| {
+            let size = config
+                .instance_pool_size
+                .map_or(max_instances, |size| size.min(max_instances));
+            InstancePool::new(self.clone(), size)
+        })
+    }
+
     /// Instantiate the actor and invoke an operation on it.
     ///
     /// # Errors
@@ -213,6 +286,19 @@ impl Actor {
             .into_logging()
             .await
     }
+
+    /// Instantiates and returns a [`MessagingHandlerInstance`] if exported by the [`Instance`].
+    ///
+    /// # Errors
+    ///
+    /// Fails if either instantiation fails or no messaging handler bindings are exported by the [`Instance`]
+    pub async fn as_messaging_handler(&self) -> anyhow::Result<MessagingHandlerInstance> {
+        self.instantiate()
+            .await
+            .context("failed to instantiate actor")?
+            .into_messaging_handler()
+            .await
+    }
 }
 
 /// A pre-loaded, configured wasmCloud actor instance, which is either a module or a component
@@ -249,6 +335,14 @@ pub enum IncomingHttpInstance {
     Component(ComponentInterfaceInstance<component::incoming_http_bindings::IncomingHttp>),
 }
 
+/// A pre-loaded, configured [`MessagingHandler`] instance, which is either a module or a component
+pub enum MessagingHandlerInstance {
+    /// WebAssembly module containing an actor
+    Module(ModuleGuestInstance),
+    /// WebAssembly component containing an actor
+    Component(ComponentInterfaceInstance<component::messaging_handler_bindings::MessagingHandler>),
+}
+
 impl GuestInstance {
     /// Invoke an operation on a [GuestInstance] producing a response
     ///
@@ -306,7 +400,49 @@ impl IncomingHttp for IncomingHttpInstance {
     }
 }
 
+#[async_trait]
+impl MessagingHandler for MessagingHandlerInstance {
+    async fn handle_message(&self, msg: BrokerMessage) -> anyhow::Result<Result<(), String>> {
+        match self {
+            Self::Module(module) => module.handle_message(msg),
+            Self::Component(component) => component.handle_message(msg),
+        }
+        .await
+    }
+}
+
 impl Instance {
+    /// Instantiates the underlying module or component ahead of an invocation, if not already
+    /// done by [`Actor::instantiate`].
+    ///
+    /// # Errors
+    ///
+    /// Fails if instantiation fails
+    pub async fn preinstantiate(&mut self) -> anyhow::Result<()> {
+        match self {
+            Self::Module(..) => Ok(()),
+            Self::Component(component) => component.preinstantiate().await,
+        }
+    }
+
+    /// Whether the [`Instance`] can be invoked without instantiating the underlying module or
+    /// component first
+    #[must_use]
+    pub fn is_preinstantiated(&self) -> bool {
+        match self {
+            Self::Module(..) => true,
+            Self::Component(component) => component.is_preinstantiated(),
+        }
+    }
+
+    /// Restarts the execution time limit of the [`Instance`]
+    pub(crate) fn restart_deadline(&mut self) {
+        match self {
+            Self::Module(module) => module.restart_deadline(),
+            Self::Component(component) => component.restart_deadline(),
+        }
+    }
+
     /// Reset [`Instance`] state to defaults
     pub async fn reset(&mut self, rt: &Runtime) {
         match self {
@@ -521,4 +657,21 @@ impl Instance {
                 .map(LoggingInstance::Component),
         }
     }
+
+    /// Instantiates and returns a [`MessagingHandlerInstance`] if exported by the [`Instance`].
+    ///
+    /// # Errors
+    ///
+    /// Fails if no messaging handler bindings are exported by the [`Instance`]
+    pub async fn into_messaging_handler(self) -> anyhow::Result<MessagingHandlerInstance> {
+        match self {
+            Self::Module(module) => Ok(MessagingHandlerInstance::Module(
+                ModuleGuestInstance::from(module),
+            )),
+            Self::Component(component) => component
+                .into_messaging_handler()
+                .await
+                .map(MessagingHandlerInstance::Component),
+        }
+    }
 }
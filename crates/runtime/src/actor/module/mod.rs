@@ -2,20 +2,22 @@ mod wasmbus;
 
 use wasmbus::guest_call;
 
-use crate::actor::claims;
+use crate::actor::{claims, configure_store, record_compilation, Limits};
 use crate::capability::logging::logging;
+use crate::capability::messaging::types::BrokerMessage;
 use crate::capability::{
     builtin, Blobstore, Bus, IncomingHttp, KeyValueAtomic, KeyValueReadWrite, Logging, Messaging,
-    OutgoingHttp,
+    MessagingHandler, OutgoingHttp,
 };
 use crate::io::AsyncVec;
-use crate::Runtime;
+use crate::{ActorConfig, Runtime};
 
 use core::any::Any;
 use core::fmt::{self, Debug};
 
 use std::io::Cursor;
 use std::sync::Arc;
+use std::time::Instant;
 
 use anyhow::{anyhow, bail, ensure, Context};
 use async_trait::async_trait;
@@ -122,6 +124,7 @@ impl<T: AsyncWrite + Sync + Send + Unpin + 'static> WasiFile for AsyncWritePipe<
 struct Ctx {
     wasi: wasmtime_wasi::WasiCtx,
     wasmbus: wasmbus::Ctx,
+    limits: Limits,
 }
 
 impl Debug for Ctx {
@@ -146,6 +149,7 @@ pub struct Module {
     linker: Linker<Ctx>,
     claims: Option<jwt::Claims<jwt::Actor>>,
     config: Config,
+    actor_config: ActorConfig,
     handler: builtin::HandlerBuilder,
 }
 
@@ -154,6 +158,7 @@ impl Debug for Module {
         f.debug_struct("Module")
             .field("claims", &self.claims)
             .field("config", &self.config)
+            .field("actor_config", &self.actor_config)
             .field("handler", &self.handler)
             .field("runtime", &"wasmtime")
             .finish_non_exhaustive()
@@ -164,6 +169,7 @@ async fn instantiate(
     module: &wasmtime::Module,
     mut linker: Linker<Ctx>,
     config: &Config,
+    actor_config: &ActorConfig,
     handler: impl Into<builtin::Handler>,
 ) -> anyhow::Result<Instance> {
     let mut wasi = WasiCtxBuilder::new();
@@ -171,12 +177,16 @@ async fn instantiate(
         .arg("main.wasm")
         .context("failed to set argv[0]")?
         .build();
+    let limits = Limits::from(actor_config);
+    limits.check_instances(1)?;
     let ctx = Ctx {
         wasi,
         wasmbus: wasmbus::Ctx::new(handler),
+        limits,
     };
 
     let mut store = wasmtime::Store::new(module.engine(), ctx);
+    configure_store(&mut store, actor_config, |ctx| &mut ctx.limits);
     let memory = wasmtime::Memory::new(
         &mut store,
         wasmtime::MemoryType::new(config.min_memory_pages, config.max_memory_pages),
@@ -212,9 +222,26 @@ impl Module {
     /// Extracts [Claims](jwt::Claims) from WebAssembly module and compiles it using [Runtime].
     #[instrument(level = "trace", skip_all)]
     pub fn new(rt: &Runtime, wasm: impl AsRef<[u8]>) -> anyhow::Result<Self> {
+        Self::new_with_config(rt, wasm, rt.actor_config.clone())
+    }
+
+    /// Like [Self::new], but instantiates the module with `actor_config` instead of the
+    /// [`ActorConfig`] of the [Runtime]
+    #[instrument(level = "trace", skip_all)]
+    pub fn new_with_config(
+        rt: &Runtime,
+        wasm: impl AsRef<[u8]>,
+        actor_config: ActorConfig,
+    ) -> anyhow::Result<Self> {
         let wasm = wasm.as_ref();
         let claims = claims(wasm)?;
-        let module = wasmtime::Module::new(&rt.engine, wasm).context("failed to compile module")?;
+        let start = Instant::now();
+        let module = if let Some(cache) = &rt.compilation_cache {
+            cache.module(&rt.engine, wasm)?
+        } else {
+            wasmtime::Module::new(&rt.engine, wasm).context("failed to compile module")?
+        };
+        record_compilation("module", start.elapsed());
 
         let mut linker = Linker::<Ctx>::new(module.engine());
 
@@ -228,6 +255,7 @@ impl Module {
             claims,
             handler: rt.handler.clone(),
             config: rt.module_config,
+            actor_config,
         })
     }
 
@@ -237,10 +265,22 @@ impl Module {
         self.claims.as_ref()
     }
 
+    /// [`ActorConfig`] this [Module] is instantiated with
+    pub(crate) fn actor_config(&self) -> &ActorConfig {
+        &self.actor_config
+    }
+
     /// Like [Self::instantiate], but moves the [Module].
     #[instrument]
     pub async fn into_instance(self) -> anyhow::Result<Instance> {
-        instantiate(&self.module, self.linker, &self.config, self.handler).await
+        instantiate(
+            &self.module,
+            self.linker,
+            &self.config,
+            &self.actor_config,
+            self.handler,
+        )
+        .await
     }
 
     /// Like [Self::instantiate], but moves the [Module] and returns the associated [jwt::Claims].
@@ -248,7 +288,14 @@ impl Module {
     pub async fn into_instance_claims(
         self,
     ) -> anyhow::Result<(Instance, Option<jwt::Claims<jwt::Actor>>)> {
-        let instance = instantiate(&self.module, self.linker, &self.config, self.handler).await?;
+        let instance = instantiate(
+            &self.module,
+            self.linker,
+            &self.config,
+            &self.actor_config,
+            self.handler,
+        )
+        .await?;
         Ok((instance, self.claims))
     }
 
@@ -259,6 +306,7 @@ impl Module {
             &self.module,
             self.linker.clone(),
             &self.config,
+            &self.actor_config,
             self.handler.clone(),
         )
         .await
@@ -303,6 +351,12 @@ impl Instance {
         &mut self.store.data_mut().wasmbus.handler
     }
 
+    /// Restarts the execution time limit of the [`Instance`]
+    pub(crate) fn restart_deadline(&mut self) {
+        let limits = self.store.data().limits;
+        limits.restart_deadline(&mut self.store);
+    }
+
     /// Reset [`Instance`] state to defaults
     pub fn reset(&mut self, rt: &Runtime) {
         *self.handler_mut() = rt.handler.clone().into();
@@ -564,3 +618,21 @@ impl IncomingHttp for GuestInstance {
         }
     }
 }
+
+#[async_trait]
+impl MessagingHandler for GuestInstance {
+    #[instrument(level = "trace", skip_all)]
+    async fn handle_message(&self, msg: BrokerMessage) -> anyhow::Result<Result<(), String>> {
+        let msg = wasmcloud_compat::messaging::SubMessage::from(
+            wasmcloud_compat::messaging::BrokerMessage::from(msg),
+        );
+        let request = rmp_serde::to_vec_named(&msg).context("failed to encode request")?;
+        self.call(
+            wasmcloud_compat::messaging::HANDLE_MESSAGE_OPERATION,
+            Cursor::new(request),
+            sink(),
+        )
+        .await
+        .context("failed to call actor")
+    }
+}
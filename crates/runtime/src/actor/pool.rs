@@ -0,0 +1,119 @@
+use super::{Actor, Instance};
+
+use core::num::NonZeroUsize;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use std::sync::{Arc, Mutex};
+
+use tracing::{instrument, warn};
+
+/// Pool of pre-instantiated [`Instance`]s of an [`Actor`], which takes instantiation off the
+/// invocation path.
+///
+/// Every pooled instance is used for a single invocation and dropped afterwards, so no state is
+/// retained between invocations. The pool is filled lazily: taking an instance from the pool
+/// triggers instantiation of a replacement in the background, up to the size of the pool. If the
+/// pool is empty, an instance is instantiated on demand.
+#[derive(Clone)]
+pub struct InstancePool {
+    actor: Actor,
+    size: NonZeroUsize,
+    idle: Arc<Mutex<Vec<Instance>>>,
+    /// Number of pool slots in use, which are either idle instances or pending instantiations.
+    /// Slots are reserved before instantiating, so the pool never holds more than `size` instances
+    slots: Arc<AtomicUsize>,
+}
+
+impl core::fmt::Debug for InstancePool {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("InstancePool")
+            .field("actor", &self.actor)
+            .field("size", &self.size)
+            .finish_non_exhaustive()
+    }
+}
+
+impl InstancePool {
+    /// Creates an empty pool of up to `size` instances of `actor`.
+    #[must_use]
+    pub fn new(actor: Actor, size: NonZeroUsize) -> Self {
+        Self {
+            actor,
+            size,
+            idle: Arc::default(),
+            slots: Arc::default(),
+        }
+    }
+
+    /// Maximum number of idle instances kept in the pool
+    #[must_use]
+    pub fn size(&self) -> NonZeroUsize {
+        self.size
+    }
+
+    /// Number of instances currently idle in the pool
+    #[must_use]
+    pub fn idle(&self) -> usize {
+        self.idle.lock().map_or(0, |idle| idle.len())
+    }
+
+    /// Takes an instance from the pool, or instantiates one if the pool is empty, and refills the
+    /// pool in the background.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the pool is empty and instantiation fails
+    #[instrument(level = "trace", skip_all)]
+    pub async fn instance(&self) -> anyhow::Result<Instance> {
+        let instance = self.idle.lock().ok().and_then(|mut idle| idle.pop());
+        if instance.is_some() {
+            self.slots.fetch_sub(1, Ordering::AcqRel);
+        }
+        self.refill();
+        let Some(mut instance) = instance else {
+            return self.actor.instantiate().await;
+        };
+        // The execution time limit is counted from instantiation, which happened ahead of time
+        instance.restart_deadline();
+        Ok(instance)
+    }
+
+    /// Instantiates an instance in the background, unless the pool is already full or will be
+    /// once pending instantiations complete
+    fn refill(&self) {
+        let size = self.size.get();
+        // Reserve a slot with a compare-exchange loop, so that concurrent refills cannot reserve
+        // more slots than the pool has
+        if self
+            .slots
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |slots| {
+                (slots < size).then_some(slots + 1)
+            })
+            .is_err()
+        {
+            return;
+        }
+        let actor = self.actor.clone();
+        let idle = Arc::clone(&self.idle);
+        let slots = Arc::clone(&self.slots);
+        tokio::spawn(async move {
+            let instance = match actor.instantiate().await {
+                Ok(mut instance) => instance.preinstantiate().await.map(|()| instance),
+                Err(err) => Err(err),
+            };
+            match instance {
+                // The reserved slot is now held by the idle instance
+                Ok(instance) => match idle.lock() {
+                    Ok(mut idle) => idle.push(instance),
+                    Err(_) => {
+                        slots.fetch_sub(1, Ordering::AcqRel);
+                    }
+                },
+                Err(err) => {
+                    warn!(?err, "failed to instantiate pooled actor instance");
+                    slots.fetch_sub(1, Ordering::AcqRel);
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,279 @@
+use core::hash::{Hash, Hasher};
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+use sha2::{Digest, Sha256};
+use tracing::{debug, instrument, trace, warn};
+
+/// Length of the SHA-256 checksum prefixed to every cached artifact
+const CHECKSUM_LEN: usize = 32;
+
+/// Extension of compiled module artifacts
+const MODULE_EXTENSION: &str = "cwasm";
+
+/// Extension of compiled component artifacts
+const COMPONENT_EXTENSION: &str = "ccomponent";
+
+/// Configuration of the on-disk cache of compiled actor modules and components
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CompilationCacheConfig {
+    /// Directory compiled artifacts are stored in. It is created if it does not exist
+    pub dir: PathBuf,
+    /// Maximum total size of the cached artifacts, in bytes. The oldest artifacts are evicted
+    /// once it is exceeded
+    pub max_size: u64,
+}
+
+impl CompilationCacheConfig {
+    /// Default maximum cache size, 1 GiB
+    pub const DEFAULT_MAX_SIZE: u64 = 1 << 30;
+
+    /// Returns a new [`CompilationCacheConfig`] storing artifacts in `dir`, limited to
+    /// [`Self::DEFAULT_MAX_SIZE`] bytes
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            max_size: Self::DEFAULT_MAX_SIZE,
+        }
+    }
+}
+
+/// On-disk cache of serialized [`wasmtime`] artifacts, keyed by the SHA-256 digest of the
+/// compatibility hash of the engine that compiled them and the Wasm binary
+#[derive(Clone, Debug)]
+pub(crate) struct CompilationCache {
+    config: CompilationCacheConfig,
+    engine_hasher: Sha256,
+}
+
+/// [`Hasher`] feeding all hashed data into a SHA-256 digest. Unlike
+/// [`DefaultHasher`](std::collections::hash_map::DefaultHasher), the resulting digest is stable
+/// across Rust releases
+struct Sha256Hasher(Sha256);
+
+impl Hasher for Sha256Hasher {
+    fn finish(&self) -> u64 {
+        let digest = self.0.clone().finalize();
+        let mut buf = [0; 8];
+        buf.copy_from_slice(&digest[..8]);
+        u64::from_be_bytes(buf)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
+    }
+}
+
+impl CompilationCache {
+    /// Opens the cache in the configured directory for artifacts compiled by `engine`
+    pub(crate) fn new(
+        config: CompilationCacheConfig,
+        engine: &wasmtime::Engine,
+    ) -> anyhow::Result<Self> {
+        create_private_dir(&config.dir).with_context(|| {
+            format!(
+                "failed to open compilation cache directory `{}`",
+                config.dir.display()
+            )
+        })?;
+        let mut hasher = Sha256Hasher(Sha256::new());
+        engine.precompile_compatibility_hash().hash(&mut hasher);
+        Ok(Self {
+            config,
+            engine_hasher: hasher.0,
+        })
+    }
+
+    /// Returns the compiled module for `wasm`, loading it from the cache if present
+    #[instrument(level = "trace", skip_all)]
+    pub(crate) fn module(
+        &self,
+        engine: &wasmtime::Engine,
+        wasm: &[u8],
+    ) -> anyhow::Result<wasmtime::Module> {
+        let path = self.path(wasm, MODULE_EXTENSION);
+        if let Some(artifact) = load(&path) {
+            // SAFETY: The cache directory is owned by the current user and not accessible to any
+            // other user, which was checked when the cache was opened, so the artifact was
+            // written by a process running as the current user. The checksum only guards against
+            // artifacts truncated or corrupted on disk, not against deliberate modification
+            match unsafe { wasmtime::Module::deserialize(engine, artifact) } {
+                Ok(module) => return Ok(module),
+                Err(err) => {
+                    warn!(path = %path.display(), ?err, "failed to deserialize cached module");
+                    remove(&path);
+                }
+            }
+        }
+        let module = wasmtime::Module::new(engine, wasm).context("failed to compile module")?;
+        match module.serialize() {
+            Ok(artifact) => self.store(&path, &artifact),
+            Err(err) => warn!(?err, "failed to serialize module"),
+        }
+        Ok(module)
+    }
+
+    /// Returns the compiled component for `wasm`, loading it from the cache if present
+    #[instrument(level = "trace", skip_all)]
+    pub(crate) fn component(
+        &self,
+        engine: &wasmtime::Engine,
+        wasm: &[u8],
+    ) -> anyhow::Result<wasmtime::component::Component> {
+        let path = self.path(wasm, COMPONENT_EXTENSION);
+        if let Some(artifact) = load(&path) {
+            // SAFETY: The cache directory is owned by the current user and not accessible to any
+            // other user, which was checked when the cache was opened, so the artifact was
+            // written by a process running as the current user. The checksum only guards against
+            // artifacts truncated or corrupted on disk, not against deliberate modification
+            match unsafe { wasmtime::component::Component::deserialize(engine, artifact) } {
+                Ok(component) => return Ok(component),
+                Err(err) => {
+                    warn!(path = %path.display(), ?err, "failed to deserialize cached component");
+                    remove(&path);
+                }
+            }
+        }
+        let component = wasmtime::component::Component::new(engine, wasm)
+            .context("failed to compile component")?;
+        match component.serialize() {
+            Ok(artifact) => self.store(&path, &artifact),
+            Err(err) => warn!(?err, "failed to serialize component"),
+        }
+        Ok(component)
+    }
+
+    fn path(&self, wasm: &[u8], extension: &str) -> PathBuf {
+        let mut hasher = self.engine_hasher.clone();
+        hasher.update(wasm);
+        let digest = hex::encode(hasher.finalize());
+        self.config.dir.join(format!("{digest}.{extension}"))
+    }
+
+    /// Writes the artifact to `path` and evicts old artifacts if the cache exceeds its size limit.
+    /// Failures are logged, since the cache is only an optimization
+    fn store(&self, path: &Path, artifact: &[u8]) {
+        if let Err(err) = self.write(path, artifact) {
+            warn!(path = %path.display(), ?err, "failed to cache compiled artifact");
+            return;
+        }
+        if let Err(err) = self.evict() {
+            warn!(?err, "failed to evict compiled artifacts from cache");
+        }
+    }
+
+    fn write(&self, path: &Path, artifact: &[u8]) -> anyhow::Result<()> {
+        let size = u64::try_from(artifact.len() + CHECKSUM_LEN)
+            .context("artifact size does not fit in u64")?;
+        if size > self.config.max_size {
+            bail!("artifact of {size} bytes exceeds the cache size limit");
+        }
+        // Write to a temporary file first, so that concurrent readers never observe a partially
+        // written artifact
+        let mut file = tempfile::NamedTempFile::new_in(&self.config.dir)
+            .context("failed to create temporary file")?;
+        file.write_all(&Sha256::digest(artifact))
+            .and_then(|()| file.write_all(artifact))
+            .context("failed to write artifact")?;
+        file.persist(path).context("failed to persist artifact")?;
+        Ok(())
+    }
+
+    /// Removes the oldest artifacts until the total size of the cache is within its limit
+    fn evict(&self) -> anyhow::Result<()> {
+        let mut artifacts = Vec::new();
+        let mut total: u64 = 0;
+        for entry in fs::read_dir(&self.config.dir).context("failed to read cache directory")? {
+            let entry = entry.context("failed to read cache directory entry")?;
+            let path = entry.path();
+            if !matches!(
+                path.extension().and_then(|ext| ext.to_str()),
+                Some(MODULE_EXTENSION | COMPONENT_EXTENSION)
+            ) {
+                continue;
+            }
+            let metadata = entry
+                .metadata()
+                .context("failed to read artifact metadata")?;
+            total = total.saturating_add(metadata.len());
+            artifacts.push((metadata.modified().ok(), metadata.len(), path));
+        }
+        if total <= self.config.max_size {
+            return Ok(());
+        }
+        artifacts.sort_unstable();
+        for (_, size, path) in artifacts {
+            if total <= self.config.max_size {
+                break;
+            }
+            debug!(path = %path.display(), "evicting compiled artifact from cache");
+            remove(&path);
+            total = total.saturating_sub(size);
+        }
+        Ok(())
+    }
+}
+
+/// Creates `dir` accessible only to the current user, or verifies that an existing `dir` is owned
+/// by the current user and not accessible to any other user, since artifacts loaded from it are
+/// executed as native code
+#[cfg(unix)]
+fn create_private_dir(dir: &Path) -> anyhow::Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+
+    fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)
+        .context("failed to create directory")?;
+    let metadata = fs::metadata(dir).context("failed to read directory metadata")?;
+    let uid = nix::unistd::geteuid().as_raw();
+    if metadata.uid() != uid {
+        bail!(
+            "directory is owned by user `{}`, expected current user `{uid}`",
+            metadata.uid()
+        );
+    }
+    let mode = metadata.mode() & 0o777;
+    if mode & 0o077 != 0 {
+        bail!("directory must not be accessible by other users, but its mode is `{mode:o}`");
+    }
+    Ok(())
+}
+
+/// Creates `dir`. File ownership and permissions are not checked on this platform
+#[cfg(not(unix))]
+fn create_private_dir(dir: &Path) -> anyhow::Result<()> {
+    fs::create_dir_all(dir).context("failed to create directory")
+}
+
+/// Reads the artifact at `path`, returning it only if its checksum matches
+fn load(path: &Path) -> Option<Vec<u8>> {
+    let mut artifact = match fs::read(path) {
+        Ok(artifact) => artifact,
+        Err(err) => {
+            trace!(path = %path.display(), ?err, "compiled artifact not cached");
+            return None;
+        }
+    };
+    if artifact.len() < CHECKSUM_LEN
+        || Sha256::digest(&artifact[CHECKSUM_LEN..]).as_slice() != &artifact[..CHECKSUM_LEN]
+    {
+        warn!(path = %path.display(), "cached artifact checksum mismatch, discarding");
+        remove(path);
+        return None;
+    }
+    debug!(path = %path.display(), "using cached compiled artifact");
+    artifact.drain(..CHECKSUM_LEN);
+    Some(artifact)
+}
+
+fn remove(path: &Path) {
+    if let Err(err) = fs::remove_file(path) {
+        warn!(path = %path.display(), ?err, "failed to remove cached artifact");
+    }
+}
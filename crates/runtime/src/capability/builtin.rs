@@ -490,6 +490,16 @@ pub trait Messaging {
     async fn publish(&self, msg: messaging::types::BrokerMessage) -> anyhow::Result<()>;
 }
 
+#[async_trait]
+/// `wasmcloud:messaging/handler` implementation
+pub trait MessagingHandler {
+    /// Handle `wasmcloud:messaging/handler.handle-message`
+    async fn handle_message(
+        &self,
+        msg: messaging::types::BrokerMessage,
+    ) -> anyhow::Result<Result<(), String>>;
+}
+
 #[async_trait]
 /// `wasi:http/outgoing-handler` implementation
 pub trait OutgoingHttp {
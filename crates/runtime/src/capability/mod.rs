@@ -5,7 +5,7 @@ pub mod provider;
 
 pub use builtin::{
     ActorIdentifier, Blobstore, Bus, IncomingHttp, KeyValueAtomic, KeyValueReadWrite, Logging,
-    Messaging, OutgoingHttp, OutgoingHttpRequest, TargetEntity, TargetInterface,
+    Messaging, MessagingHandler, OutgoingHttp, OutgoingHttpRequest, TargetEntity, TargetInterface,
 };
 
 #[allow(clippy::doc_markdown)]
@@ -47,6 +47,38 @@ pub use bindgen::wasmcloud::{
 pub use bindgen::Interfaces;
 pub use wasmtime_wasi_http::bindings::http;
 
+impl From<wasmcloud_compat::messaging::BrokerMessage> for messaging::types::BrokerMessage {
+    fn from(
+        wasmcloud_compat::messaging::BrokerMessage {
+            subject,
+            body,
+            reply_to,
+        }: wasmcloud_compat::messaging::BrokerMessage,
+    ) -> Self {
+        Self {
+            subject,
+            body,
+            reply_to,
+        }
+    }
+}
+
+impl From<messaging::types::BrokerMessage> for wasmcloud_compat::messaging::BrokerMessage {
+    fn from(
+        messaging::types::BrokerMessage {
+            subject,
+            body,
+            reply_to,
+        }: messaging::types::BrokerMessage,
+    ) -> Self {
+        Self {
+            subject,
+            body,
+            reply_to,
+        }
+    }
+}
+
 fn format_opt<T>(opt: &Option<T>) -> &'static str {
     if opt.is_some() {
         "set"
@@ -17,7 +17,13 @@ pub mod runtime;
 /// wasmCloud I/O functionality
 pub mod io;
 
-pub use actor::{Actor, Config as ActorConfig, Instance as ActorInstance};
+/// On-disk cache of compiled actors
+mod cache;
+
+pub use actor::{
+    Actor, Config as ActorConfig, Instance as ActorInstance, InstancePool, LimitExceeded,
+};
+pub use cache::CompilationCacheConfig;
 pub use runtime::*;
 
 pub use async_trait::async_trait;
@@ -1,4 +1,5 @@
-use crate::actor::ModuleConfig;
+use crate::actor::{ModuleConfig, EPOCH_TICK};
+use crate::cache::{CompilationCache, CompilationCacheConfig};
 use crate::capability::{
     builtin, Blobstore, Bus, IncomingHttp, KeyValueAtomic, KeyValueReadWrite, Logging, Messaging,
     OutgoingHttp,
@@ -8,10 +9,81 @@ use crate::ActorConfig;
 use core::fmt;
 use core::fmt::Debug;
 
-use std::sync::Arc;
+use std::sync::{mpsc, Arc};
+use std::thread;
 
 use anyhow::Context;
 
+/// Configuration of the pooling instance allocator, which preallocates memories, tables and
+/// instances for a fixed number of concurrently running actor instances up front
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PoolingAllocatorConfig {
+    /// Maximum number of concurrently running actor instances
+    pub max_instances: u32,
+    /// Maximum size of a linear memory of an actor instance, in bytes
+    pub max_memory_size: usize,
+    /// Maximum number of elements in a table of an actor instance
+    pub max_table_elements: u32,
+}
+
+impl Default for PoolingAllocatorConfig {
+    fn default() -> Self {
+        Self {
+            max_instances: 1000,
+            max_memory_size: 64 << 20,
+            max_table_elements: 10_000,
+        }
+    }
+}
+
+impl PoolingAllocatorConfig {
+    /// Maximum number of core instances, which includes adapter and shim modules, in an actor
+    const CORE_INSTANCES_PER_ACTOR: u32 = 20;
+
+    /// Maximum number of tables in an actor
+    const TABLES_PER_ACTOR: u32 = 20;
+
+    /// Maximum number of linear memories in an actor
+    const MEMORIES_PER_ACTOR: u32 = 2;
+
+    fn allocation_strategy(self) -> wasmtime::InstanceAllocationStrategy {
+        let memory_pages = self.max_memory_size.div_ceil(1 << 16);
+        let mut pooling = wasmtime::PoolingAllocationConfig::default();
+        pooling
+            .total_component_instances(self.max_instances)
+            .total_core_instances(
+                self.max_instances
+                    .saturating_mul(Self::CORE_INSTANCES_PER_ACTOR),
+            )
+            .total_tables(self.max_instances.saturating_mul(Self::TABLES_PER_ACTOR))
+            .total_memories(self.max_instances.saturating_mul(Self::MEMORIES_PER_ACTOR))
+            .total_stacks(self.max_instances)
+            .memory_pages(memory_pages.try_into().unwrap_or(u64::MAX))
+            .table_elements(self.max_table_elements);
+        wasmtime::InstanceAllocationStrategy::Pooling(pooling)
+    }
+}
+
+/// Increments the epoch of an engine every [`EPOCH_TICK`] until dropped, which interrupts actor
+/// instances exceeding their execution deadline
+struct EpochTicker {
+    _stop: mpsc::Sender<()>,
+}
+
+impl EpochTicker {
+    fn spawn(engine: wasmtime::Engine) -> std::io::Result<Self> {
+        let (stop, stopped) = mpsc::channel();
+        thread::Builder::new()
+            .name("wasmcloud-epoch-ticker".into())
+            .spawn(move || {
+                while let Err(mpsc::RecvTimeoutError::Timeout) = stopped.recv_timeout(EPOCH_TICK) {
+                    engine.increment_epoch();
+                }
+            })?;
+        Ok(Self { _stop: stop })
+    }
+}
+
 /// [`RuntimeBuilder`] used to configure and build a [Runtime]
 #[derive(Clone, Default)]
 pub struct RuntimeBuilder {
@@ -19,6 +91,8 @@ pub struct RuntimeBuilder {
     handler: builtin::HandlerBuilder,
     actor_config: ActorConfig,
     module_config: ModuleConfig,
+    compilation_cache: Option<CompilationCacheConfig>,
+    pooling_allocator: Option<PoolingAllocatorConfig>,
 }
 
 impl RuntimeBuilder {
@@ -33,10 +107,14 @@ impl RuntimeBuilder {
             handler: builtin::HandlerBuilder::default(),
             actor_config: ActorConfig::default(),
             module_config: ModuleConfig::default(),
+            compilation_cache: None,
+            pooling_allocator: None,
         }
     }
 
-    /// Set a custom [`ActorConfig`] to use for all actor instances
+    /// Set a custom [`ActorConfig`] to use for all actor instances, including the resource limits
+    /// enforced for each of them, unless an actor is compiled with
+    /// [`Actor::new_with_config`](crate::Actor::new_with_config)
     #[must_use]
     pub fn actor_config(self, actor_config: ActorConfig) -> Self {
         Self {
@@ -54,6 +132,26 @@ impl RuntimeBuilder {
         }
     }
 
+    /// Persist compiled actors on disk as configured by [`CompilationCacheConfig`], so that
+    /// subsequent runtimes using the same engine configuration do not need to compile them again
+    #[must_use]
+    pub fn compilation_cache(self, compilation_cache: CompilationCacheConfig) -> Self {
+        Self {
+            compilation_cache: Some(compilation_cache),
+            ..self
+        }
+    }
+
+    /// Preallocate resources for actor instances using the pooling instance allocator configured by
+    /// [`PoolingAllocatorConfig`], instead of allocating them on demand
+    #[must_use]
+    pub fn pooling_allocator(self, pooling_allocator: PoolingAllocatorConfig) -> Self {
+        Self {
+            pooling_allocator: Some(pooling_allocator),
+            ..self
+        }
+    }
+
     /// Set a [`Blobstore`] handler to use for all actor instances unless overriden for the instance
     #[must_use]
     pub fn blobstore(self, blobstore: Arc<impl Blobstore + Sync + Send + 'static>) -> Self {
@@ -142,15 +240,32 @@ impl RuntimeBuilder {
     ///
     /// # Errors
     ///
-    /// Fails if the configuration is not valid
-    pub fn build(self) -> anyhow::Result<Runtime> {
+    /// Fails if the configuration is not valid, the compilation cache directory cannot be
+    /// created or the pooling allocator cannot reserve its resources
+    pub fn build(mut self) -> anyhow::Result<Runtime> {
+        if let Some(pooling_allocator) = self.pooling_allocator {
+            self.engine_config
+                .allocation_strategy(pooling_allocator.allocation_strategy());
+        }
+        // Actors may be configured with an execution time limit independently of the runtime, so
+        // epoch interruption is always enabled
+        self.engine_config.epoch_interruption(true);
         let engine =
             wasmtime::Engine::new(&self.engine_config).context("failed to construct engine")?;
+        let epoch_ticker =
+            EpochTicker::spawn(engine.clone()).context("failed to spawn epoch ticker")?;
+        let compilation_cache = self
+            .compilation_cache
+            .map(|config| CompilationCache::new(config, &engine))
+            .transpose()
+            .context("failed to open compilation cache")?;
         Ok(Runtime {
             engine,
             handler: self.handler,
             actor_config: self.actor_config,
             module_config: self.module_config,
+            compilation_cache,
+            _epoch_ticker: Arc::new(epoch_ticker),
         })
     }
 }
@@ -170,6 +285,8 @@ pub struct Runtime {
     pub(crate) handler: builtin::HandlerBuilder,
     pub(crate) actor_config: ActorConfig,
     pub(crate) module_config: ModuleConfig,
+    pub(crate) compilation_cache: Option<CompilationCache>,
+    _epoch_ticker: Arc<EpochTicker>,
 }
 
 impl Debug for Runtime {
@@ -178,6 +295,7 @@ impl Debug for Runtime {
             .field("handler", &self.handler)
             .field("actor_config", &self.actor_config)
             .field("module_config", &self.module_config)
+            .field("compilation_cache", &self.compilation_cache)
             .field("runtime", &"wasmtime")
             .finish_non_exhaustive()
     }
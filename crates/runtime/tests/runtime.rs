@@ -1,5 +1,6 @@
 use std::collections::{BTreeMap, HashMap};
 use std::io::Cursor;
+use std::num::NonZeroUsize;
 use std::path::Path;
 use std::pin::Pin;
 use std::str::FromStr;
@@ -22,9 +23,11 @@ use wasmcloud_runtime::capability::provider::{
 };
 use wasmcloud_runtime::capability::{
     self, guest_config, messaging, IncomingHttp, KeyValueAtomic, KeyValueReadWrite, Messaging,
-    OutgoingHttp,
+    MessagingHandler, OutgoingHttp,
+};
+use wasmcloud_runtime::{
+    Actor, ActorConfig, CompilationCacheConfig, LimitExceeded, PoolingAllocatorConfig, Runtime,
 };
-use wasmcloud_runtime::{Actor, Runtime};
 
 static LOGGER: Lazy<()> = Lazy::new(|| {
     tracing_subscriber::registry()
@@ -70,7 +73,7 @@ impl capability::Bus for Handler {
             ("keyvalue", "wasmcloud:keyvalue") => {
                 Ok(capability::TargetEntity::Link(Some("keyvalue".into())))
             }
-            ("messaging", "wasmcloud:messaging") => {
+            ("messaging" | "default", "wasmcloud:messaging") => {
                 Ok(capability::TargetEntity::Link(Some("messaging".into())))
             }
             ("", "foobar-component-command-preview2") => Ok(capability::TargetEntity::Actor(
@@ -421,6 +424,7 @@ fn new_runtime(
     published: Arc<Mutex<Vec<messaging::types::BrokerMessage>>>,
     sent: Arc<Mutex<Vec<capability::OutgoingHttpRequest>>>,
     config: HashMap<String, Vec<u8>>,
+    actor_config: ActorConfig,
 ) -> Runtime {
     let handler = Arc::new(Handler {
         blobstore: Arc::clone(&blobstore),
@@ -439,6 +443,7 @@ fn new_runtime(
         .logging(Arc::clone(&handler))
         .messaging(Arc::clone(&handler))
         .outgoing_http(Arc::clone(&handler))
+        .actor_config(actor_config)
         .build()
         .expect("failed to construct runtime")
 }
@@ -449,9 +454,22 @@ struct RunResult {
     all_config: HashMap<String, Vec<u8>>,
 }
 
-async fn run(wasm: impl AsRef<Path>) -> anyhow::Result<RunResult> {
-    const BODY: &str = r#"{"min":42,"max":4242,"port":42424,"config_key":"test-config-key"}"#;
+const BUILTINS_BODY: &str = r#"{"min":42,"max":4242,"port":42424,"config_key":"test-config-key"}"#;
+
+fn builtins_request() -> http::Request<Box<dyn AsyncRead + Send + Sync + Unpin>> {
+    let req: Box<dyn AsyncRead + Send + Sync + Unpin> = Box::new(Cursor::new(BUILTINS_BODY));
+    http::Request::builder()
+        .method("POST")
+        .uri("/foo?bar=baz")
+        .header("accept", "*/*")
+        .header("content-length", BUILTINS_BODY.len())
+        .header("host", "fake:42")
+        .header("test-header", "test-value")
+        .body(req)
+        .expect("failed to construct request")
+}
 
+async fn run(wasm: impl AsRef<Path>) -> anyhow::Result<RunResult> {
     let wasm = fs::read(wasm).await.context("failed to read Wasm")?;
 
     let keyvalue = Arc::new(MemoryKeyValue::from(HashMap::from([(
@@ -478,6 +496,7 @@ async fn run(wasm: impl AsRef<Path>) -> anyhow::Result<RunResult> {
             Arc::clone(&published),
             Arc::clone(&sent),
             config.clone(),
+            ActorConfig::default(),
         );
         let actor = Actor::new(&rt, wasm).expect("failed to construct actor");
         actor.claims().expect("claims missing");
@@ -486,21 +505,11 @@ async fn run(wasm: impl AsRef<Path>) -> anyhow::Result<RunResult> {
             .stderr(stderr())
             .await
             .context("failed to set stderr")?;
-        let req: Box<dyn AsyncRead + Send + Sync + Unpin> = Box::new(Cursor::new(BODY));
-        let req = http::Request::builder()
-            .method("POST")
-            .uri("/foo?bar=baz")
-            .header("accept", "*/*")
-            .header("content-length", BODY.len())
-            .header("host", "fake:42")
-            .header("test-header", "test-value")
-            .body(req)
-            .expect("failed to construct request");
         actor
             .into_incoming_http()
             .await
             .context("failed to instantiate `wasi:http/incoming-handler`")?
-            .handle(req)
+            .handle(builtins_request())
             .await
             .context("failed to call `wasi:http/incoming-handler.handle`")?
     };
@@ -778,3 +787,320 @@ async fn builtins_component() -> anyhow::Result<()> {
     );
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn messaging_handler_module() -> anyhow::Result<()> {
+    init();
+
+    let wasm = fs::read(test_actors::RUST_MESSAGING_RECEIVER_SMITHY_SIGNED)
+        .await
+        .context("failed to read Wasm")?;
+    let published = Arc::default();
+    let rt = new_runtime(
+        Arc::default(),
+        Arc::new(MemoryKeyValue::from(HashMap::default())),
+        Arc::default(),
+        Arc::clone(&published),
+        Arc::default(),
+        HashMap::default(),
+        ActorConfig::default(),
+    );
+    let actor = Actor::new(&rt, wasm).context("failed to construct actor")?;
+    // The actor echoes the message to its `reply_to` subject
+    actor
+        .as_messaging_handler()
+        .await
+        .context("failed to instantiate `wasmcloud:messaging/handler`")?
+        .handle_message(messaging::types::BrokerMessage {
+            subject: "test-subject".into(),
+            body: Some(b"test-body".to_vec()),
+            reply_to: Some("test-reply".into()),
+        })
+        .await
+        .context("failed to call `wasmcloud:messaging/handler.handle-message`")?
+        .map_err(|e| anyhow::anyhow!(e))
+        .context("actor failed to handle message")?;
+
+    let mut published = Arc::try_unwrap(published).unwrap().into_inner().into_iter();
+    match (published.next(), published.next()) {
+        (
+            Some(messaging::types::BrokerMessage {
+                subject,
+                reply_to,
+                body,
+            }),
+            None,
+        ) => {
+            ensure!(subject == "test-reply");
+            ensure!(reply_to.is_none());
+            ensure!(body.as_deref() == Some(b"test-body".as_slice()));
+        }
+        (None, None) => bail!("no messages published"),
+        _ => bail!("too many messages published"),
+    }
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn compilation_cache() -> anyhow::Result<()> {
+    init();
+
+    let dir = tempfile::tempdir().context("failed to create temporary directory")?;
+    let wasm = fs::read(test_actors::RUST_BUILTINS_MODULE_REACTOR_SIGNED)
+        .await
+        .context("failed to read Wasm")?;
+    let rt = Runtime::builder()
+        .compilation_cache(CompilationCacheConfig::new(dir.path()))
+        .build()
+        .context("failed to construct runtime")?;
+
+    let cached = || -> anyhow::Result<Vec<std::path::PathBuf>> {
+        Ok(std::fs::read_dir(dir.path())?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<_, _>>()?)
+    };
+
+    Actor::new(&rt, &wasm).context("failed to compile actor")?;
+    let artifacts = cached()?;
+    ensure!(artifacts.len() == 1, "expected a single cached artifact");
+    let artifact = std::fs::read(&artifacts[0])?;
+
+    // Loading from the cache must not rewrite the artifact
+    Actor::new(&rt, &wasm).context("failed to load cached actor")?;
+    ensure!(std::fs::read(&artifacts[0])? == artifact);
+
+    // A corrupted artifact is discarded and replaced by a freshly compiled one
+    std::fs::write(&artifacts[0], b"corrupted")?;
+    Actor::new(&rt, &wasm).context("failed to compile actor with corrupted cache")?;
+    ensure!(cached()? == artifacts);
+    ensure!(std::fs::read(&artifacts[0])? == artifact);
+
+    // Artifacts exceeding the size limit are not cached
+    let dir = tempfile::tempdir().context("failed to create temporary directory")?;
+    let rt = Runtime::builder()
+        .compilation_cache(CompilationCacheConfig {
+            dir: dir.path().to_path_buf(),
+            max_size: 1024,
+        })
+        .build()
+        .context("failed to construct runtime")?;
+    Actor::new(&rt, &wasm).context("failed to compile actor")?;
+    ensure!(std::fs::read_dir(dir.path())?.next().is_none());
+
+    // Directories accessible by other users are rejected, since cached artifacts are loaded as
+    // native code
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().context("failed to create temporary directory")?;
+        std::fs::set_permissions(dir.path(), std::fs::Permissions::from_mode(0o777))?;
+        ensure!(Runtime::builder()
+            .compilation_cache(CompilationCacheConfig::new(dir.path()))
+            .build()
+            .is_err());
+    }
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn resource_limits() -> anyhow::Result<()> {
+    init();
+
+    let wasm = fs::read(test_actors::RUST_BUILTINS_MODULE_REACTOR_SIGNED)
+        .await
+        .context("failed to read Wasm")?;
+    let rt = Runtime::builder()
+        .pooling_allocator(PoolingAllocatorConfig {
+            max_instances: 2,
+            ..Default::default()
+        })
+        .actor_config(ActorConfig {
+            max_memory_size: Some(32 << 20),
+            max_execution_time: Some(Duration::from_secs(10)),
+            ..Default::default()
+        })
+        .build()
+        .context("failed to construct runtime")?;
+    let actor = Actor::new(&rt, &wasm).context("failed to compile actor")?;
+    let _first = actor.instantiate().await.context("failed to instantiate")?;
+    let _second = actor.instantiate().await.context("failed to instantiate")?;
+
+    // The minimum memory of the module exceeds the limit
+    let rt = Runtime::builder()
+        .actor_config(ActorConfig {
+            max_memory_size: Some(1 << 16),
+            ..Default::default()
+        })
+        .build()
+        .context("failed to construct runtime")?;
+    let err = Actor::new(&rt, &wasm)
+        .context("failed to compile actor")?
+        .instantiate()
+        .await
+        .expect_err("instantiation exceeding the memory limit should fail");
+    ensure!(LimitExceeded::from_error(&err) == Some(LimitExceeded::MemorySize(1 << 16)));
+
+    // The limits of an actor take precedence over those of the runtime
+    let actor = Actor::new_with_config(
+        &rt,
+        &wasm,
+        ActorConfig {
+            max_instances: Some(0),
+            ..Default::default()
+        },
+    )
+    .context("failed to compile actor")?;
+    let err = actor
+        .instantiate()
+        .await
+        .expect_err("instantiation exceeding the instance limit should fail");
+    ensure!(LimitExceeded::from_error(&err) == Some(LimitExceeded::Instances));
+
+    // Components create a core instance for the actor and one for each adapter
+    let wasm = fs::read(test_actors::RUST_BUILTINS_COMPONENT_REACTOR_PREVIEW2_SIGNED)
+        .await
+        .context("failed to read Wasm")?;
+    let rt = Runtime::new().context("failed to construct runtime")?;
+    let config = ActorConfig {
+        max_instances: Some(1),
+        ..Default::default()
+    };
+    let err = Actor::new_with_config(&rt, &wasm, config)
+        .context("failed to compile actor")?
+        .instantiate()
+        .await
+        .expect_err("instantiation exceeding the instance limit should fail");
+    ensure!(LimitExceeded::from_error(&err) == Some(LimitExceeded::Instances));
+    let actor = Actor::new(&rt, &wasm).context("failed to compile actor")?;
+    let _instance = actor.instantiate().await.context("failed to instantiate")?;
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn instance_pool() -> anyhow::Result<()> {
+    init();
+
+    let wasm = fs::read(test_actors::RUST_BUILTINS_COMPONENT_REACTOR_PREVIEW2_SIGNED)
+        .await
+        .context("failed to read Wasm")?;
+    let size = NonZeroUsize::new(1).context("invalid pool size")?;
+
+    let rt = Runtime::new().context("failed to construct runtime")?;
+    let actor = Actor::new(&rt, &wasm).context("failed to compile actor")?;
+    ensure!(actor.instance_pool(size).is_none());
+
+    let keyvalue = Arc::new(MemoryKeyValue::from(HashMap::from([(
+        "".into(),
+        HashMap::from([("foo".into(), MemoryKeyValueEntry::Blob(b"bar".to_vec()))]),
+    )])));
+    let rt = new_runtime(
+        Arc::default(),
+        keyvalue,
+        Arc::default(),
+        Arc::default(),
+        Arc::default(),
+        HashMap::from([("test-config-key".to_string(), b"test-config-value".to_vec())]),
+        ActorConfig {
+            instance_pool: true,
+            ..Default::default()
+        },
+    );
+    let actor = Actor::new(&rt, &wasm).context("failed to compile actor")?;
+    // Without a configured pool size, the pool holds up to the maximum number of instances
+    let pool = actor
+        .instance_pool(size)
+        .context("instance pool should be enabled")?;
+    ensure!(pool.size() == size);
+    // The pool is filled lazily, so the first instance is instantiated on demand
+    ensure!(pool.idle() == 0);
+    let instance = pool.instance().await.context("failed to take instance")?;
+    ensure!(!instance.is_preinstantiated());
+
+    // Taken instances are replaced in the background, up to the size of the pool
+    for _ in 0..500 {
+        if pool.idle() == 1 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    ensure!(pool.idle() == 1);
+
+    // The invocation is handled by the instance instantiated ahead of time by the pool
+    let mut instance = pool.instance().await.context("failed to take instance")?;
+    ensure!(instance.is_preinstantiated());
+    instance
+        .stderr(stderr())
+        .await
+        .context("failed to set stderr")?;
+    let res = instance
+        .into_incoming_http()
+        .await
+        .context("failed to instantiate `wasi:http/incoming-handler`")?
+        .handle(builtins_request())
+        .await
+        .context("failed to call `wasi:http/incoming-handler.handle`")?;
+    ensure!(res.status().as_u16() == 200);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn instance_pool_concurrent_refill() -> anyhow::Result<()> {
+    init();
+
+    let wasm = fs::read(test_actors::RUST_BUILTINS_COMPONENT_REACTOR_PREVIEW2_SIGNED)
+        .await
+        .context("failed to read Wasm")?;
+    let size = NonZeroUsize::new(2).context("invalid pool size")?;
+    let max_instances = NonZeroUsize::new(32).context("invalid instance count")?;
+
+    let keyvalue: HashMap<String, HashMap<String, MemoryKeyValueEntry>> = HashMap::new();
+    let rt = new_runtime(
+        Arc::default(),
+        Arc::new(MemoryKeyValue::from(keyvalue)),
+        Arc::default(),
+        Arc::default(),
+        Arc::default(),
+        HashMap::default(),
+        ActorConfig {
+            instance_pool: true,
+            instance_pool_size: Some(size),
+            ..Default::default()
+        },
+    );
+    let actor = Actor::new(&rt, &wasm).context("failed to compile actor")?;
+    // The configured pool size bounds the pool below the maximum number of instances
+    let pool = actor
+        .instance_pool(max_instances)
+        .context("instance pool should be enabled")?;
+    ensure!(pool.size() == size);
+
+    // Every concurrent take triggers a refill, but no more instances than fit in the pool may be
+    // instantiated ahead of time
+    let instances =
+        futures::future::try_join_all((0..max_instances.get()).map(|_| pool.instance()))
+            .await
+            .context("failed to take instances")?;
+    drop(instances);
+    for _ in 0..500 {
+        if pool.idle() == size.get() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    ensure!(pool.idle() == size.get());
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    ensure!(pool.idle() == size.get());
+
+    // Taking an instance frees its slot, which is refilled
+    let instance = pool.instance().await.context("failed to take instance")?;
+    ensure!(instance.is_preinstantiated());
+    for _ in 0..500 {
+        if pool.idle() == size.get() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    ensure!(pool.idle() == size.get());
+    Ok(())
+}
@@ -3,6 +3,12 @@
 #[cfg(feature = "otel")]
 pub mod context;
 
+#[cfg(feature = "otel")]
+mod metrics;
+
+#[cfg(feature = "otel")]
+pub use metrics::configure_metrics;
+
 use std::env;
 use std::io::{IsTerminal, StderrLock, Write};
 
@@ -147,73 +153,93 @@ pub fn configure_tracing(
 
     let level_filter = get_level_filter(log_level_override);
 
-    let exporter = otel_config
-        .traces_exporter
-        .as_ref()
-        .map(|s| s.to_ascii_lowercase());
-
-    // Check for unsupported exporters
-    if let Some(ref exporter) = exporter {
-        if exporter != "otlp" {
-            bail!("unsupported OTEL exporter: '{exporter}'");
-        }
-    }
+    let export_traces = otlp_exporter_enabled(otel_config.traces_exporter.as_deref())?;
+    // Logs follow the traces exporter, unless configured explicitly
+    let export_logs = match otel_config.logs_exporter.as_deref() {
+        Some(exporter) => otlp_exporter_enabled(Some(exporter))?,
+        None => export_traces,
+    };
 
-    // At this point, we just need a boolean, since we only support otlp
-    let export_otel = exporter.is_some();
+    let resource = otel_resource(service_name, otel_config);
+    let endpoint = &otel_config.exporter_otlp_endpoint;
 
     // NOTE: this logic would be simpler if we could conditionally/imperatively construct and add
     // layers, but due to the dynamic types, this is not possible
-    let res = match (export_otel, use_structured_logging) {
-        (true, true) => {
-            let exporter_endpoint = otel_config.exporter_otlp_endpoint.clone();
-            let normalized_service_name = service_name.to_kebab_case();
-            let layered = base_reg
-                .with(level_filter)
-                .with(get_json_log_layer()?)
-                .with(get_otel_tracing_layer(
-                    &exporter_endpoint,
-                    normalized_service_name.clone(),
-                )?)
-                .with(get_otel_logging_layer(
-                    &exporter_endpoint,
-                    normalized_service_name,
-                )?);
-            tracing::subscriber::set_global_default(layered)
-        }
-        (true, false) => {
-            let exporter_endpoint = otel_config.exporter_otlp_endpoint.clone();
-            let normalized_service_name = service_name.to_kebab_case();
-            let layered = base_reg
-                .with(level_filter)
-                .with(get_plaintext_log_layer()?)
-                .with(get_otel_tracing_layer(
-                    &exporter_endpoint,
-                    normalized_service_name.clone(),
-                )?)
-                .with(get_otel_logging_layer(
-                    &exporter_endpoint,
-                    normalized_service_name,
-                )?);
-            tracing::subscriber::set_global_default(layered)
-        }
-        (false, true) => {
-            let layered = base_reg.with(level_filter).with(get_json_log_layer()?);
-            tracing::subscriber::set_global_default(layered)
-        }
-        (false, false) => {
-            let layered = base_reg.with(level_filter).with(get_plaintext_log_layer()?);
-            tracing::subscriber::set_global_default(layered)
-        }
+    let res = if use_structured_logging {
+        let layered = base_reg
+            .with(level_filter)
+            .with(get_json_log_layer()?)
+            .with(
+                export_traces
+                    .then(|| get_otel_tracing_layer(endpoint, resource.clone()))
+                    .transpose()?,
+            )
+            .with(
+                export_logs
+                    .then(|| get_otel_logging_layer(endpoint, resource))
+                    .transpose()?,
+            );
+        tracing::subscriber::set_global_default(layered)
+    } else {
+        let layered = base_reg
+            .with(level_filter)
+            .with(get_plaintext_log_layer()?)
+            .with(
+                export_traces
+                    .then(|| get_otel_tracing_layer(endpoint, resource.clone()))
+                    .transpose()?,
+            )
+            .with(
+                export_logs
+                    .then(|| get_otel_logging_layer(endpoint, resource))
+                    .transpose()?,
+            );
+        tracing::subscriber::set_global_default(layered)
     };
 
     res.map_err(|e| anyhow::anyhow!(e).context("Logger/tracer was already created"))
 }
 
+/// Returns whether the OTLP exporter is enabled by `exporter`, which is either unset, `otlp` or
+/// `none`
+#[cfg(feature = "otel")]
+fn otlp_exporter_enabled(exporter: Option<&str>) -> anyhow::Result<bool> {
+    match exporter.map(str::to_ascii_lowercase).as_deref() {
+        None | Some("none") => Ok(false),
+        Some("otlp") => Ok(true),
+        Some(exporter) => bail!("unsupported OTEL exporter: '{exporter}'"),
+    }
+}
+
+/// Returns the OTEL resource describing `service_name`, which includes the configured resource
+/// attributes
+#[cfg(feature = "otel")]
+pub(crate) fn otel_resource(
+    service_name: &str,
+    otel_config: &OtelConfig,
+) -> opentelemetry_sdk::Resource {
+    opentelemetry_sdk::Resource::new(
+        otel_config
+            .resource_attributes
+            .iter()
+            .map(|(k, v)| opentelemetry::KeyValue::new(k.clone(), v.clone()))
+            .chain([opentelemetry::KeyValue::new(
+                "service.name",
+                service_name.to_kebab_case(),
+            )]),
+    )
+}
+
+/// Returns the sampler of the OTEL tracer
+#[cfg(feature = "otel")]
+fn otel_sampler() -> opentelemetry_sdk::trace::Sampler {
+    opentelemetry_sdk::trace::Sampler::AlwaysOn
+}
+
 #[cfg(feature = "otel")]
 fn get_otel_tracing_layer<S>(
     exporter_endpoint: &Option<String>,
-    service_name: String,
+    resource: opentelemetry_sdk::Resource,
 ) -> anyhow::Result<impl Layer<S>>
 where
     S: Subscriber,
@@ -232,14 +258,12 @@ where
         .with_exporter(builder)
         .with_trace_config(
             opentelemetry_sdk::trace::config()
-                .with_sampler(opentelemetry_sdk::trace::Sampler::AlwaysOn)
+                .with_sampler(otel_sampler())
                 .with_id_generator(opentelemetry_sdk::trace::RandomIdGenerator::default())
                 .with_max_events_per_span(64)
                 .with_max_attributes_per_span(16)
                 .with_max_events_per_span(16)
-                .with_resource(opentelemetry_sdk::Resource::new(vec![
-                    opentelemetry::KeyValue::new("service.name", service_name),
-                ])),
+                .with_resource(resource),
         )
         .install_batch(opentelemetry_sdk::runtime::Tokio)
         .context("failed to create OTEL tracer")?;
@@ -250,7 +274,7 @@ where
 #[cfg(feature = "otel")]
 fn get_otel_logging_layer<S>(
     exporter_endpoint: &Option<String>,
-    service_name: String,
+    resource: opentelemetry_sdk::Resource,
 ) -> anyhow::Result<impl Layer<S>>
 where
     S: Subscriber,
@@ -267,12 +291,7 @@ where
         .context("failed to create OTEL log exporter")?;
 
     let log_provider = opentelemetry_sdk::logs::LoggerProvider::builder()
-        .with_config(opentelemetry_sdk::logs::Config::default().with_resource(
-            opentelemetry_sdk::Resource::new(vec![opentelemetry::KeyValue::new(
-                "service.name",
-                service_name,
-            )]),
-        ))
+        .with_config(opentelemetry_sdk::logs::Config::default().with_resource(resource))
         .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
         .build();
 
@@ -285,7 +304,187 @@ where
         LOG_PROVIDER.get().unwrap(),
     );
 
-    Ok(log_layer)
+    Ok(TraceCorrelationLayer {
+        inner: log_layer,
+        sampler: otel_sampler(),
+    })
+}
+
+/// Wraps a log layer, which emits events as OTEL log records, to correlate the records with the
+/// span the event occurred in. The log bridge only consults the current OTEL context, which the
+/// tracing layer does not maintain
+#[cfg(feature = "otel")]
+struct TraceCorrelationLayer<L> {
+    inner: L,
+    /// The sampler of the tracer, which decides whether the span is sampled if the tracer has not
+    /// done so yet
+    sampler: opentelemetry_sdk::trace::Sampler,
+}
+
+#[cfg(feature = "otel")]
+impl<L> TraceCorrelationLayer<L> {
+    /// Returns the OTEL context of the span with `id`, if the span is tracked by the tracing layer
+    fn span_context<S>(
+        &self,
+        id: &tracing::span::Id,
+        ctx: &tracing_subscriber::layer::Context<'_, S>,
+    ) -> Option<opentelemetry::Context>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        use opentelemetry::trace::{
+            SamplingDecision, SpanContext, SpanKind, TraceContextExt, TraceFlags, TraceState,
+        };
+        use opentelemetry_sdk::trace::ShouldSample;
+
+        let span = ctx.span(id)?;
+        let mut extensions = span.extensions_mut();
+        let tracing_opentelemetry::OtelData { parent_cx, builder } = extensions.get_mut()?;
+        let span_id = builder.span_id?;
+        let (trace_id, parent_flags) = if parent_cx.has_active_span() {
+            let parent = parent_cx.span();
+            let parent = parent.span_context();
+            (parent.trace_id(), parent.trace_flags())
+        } else {
+            (builder.trace_id?, TraceFlags::default())
+        };
+        // Record the decision on the span, so that the tracer exports the span if and only if
+        // the correlated records are marked as sampled
+        let sampling_result = builder.sampling_result.get_or_insert_with(|| {
+            self.sampler.should_sample(
+                Some(&*parent_cx),
+                trace_id,
+                &builder.name,
+                builder.span_kind.as_ref().unwrap_or(&SpanKind::Internal),
+                builder.attributes.as_deref().unwrap_or(&[]),
+                builder.links.as_deref().unwrap_or(&[]),
+            )
+        });
+        let (trace_flags, trace_state) = match sampling_result.decision {
+            SamplingDecision::RecordAndSample => (
+                parent_flags | TraceFlags::SAMPLED,
+                sampling_result.trace_state.clone(),
+            ),
+            SamplingDecision::RecordOnly => (
+                parent_flags & !TraceFlags::SAMPLED,
+                sampling_result.trace_state.clone(),
+            ),
+            SamplingDecision::Drop => (TraceFlags::default(), TraceState::default()),
+        };
+        Some(parent_cx.with_remote_span_context(SpanContext::new(
+            trace_id,
+            span_id,
+            trace_flags,
+            false,
+            trace_state,
+        )))
+    }
+}
+
+#[cfg(feature = "otel")]
+impl<S, L> Layer<S> for TraceCorrelationLayer<L>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    L: Layer<S>,
+{
+    fn on_register_dispatch(&self, subscriber: &tracing::Dispatch) {
+        self.inner.on_register_dispatch(subscriber);
+    }
+
+    fn on_layer(&mut self, subscriber: &mut S) {
+        self.inner.on_layer(subscriber);
+    }
+
+    fn register_callsite(
+        &self,
+        metadata: &'static tracing::Metadata<'static>,
+    ) -> tracing::subscriber::Interest {
+        self.inner.register_callsite(metadata)
+    }
+
+    fn enabled(
+        &self,
+        metadata: &tracing::Metadata<'_>,
+        ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) -> bool {
+        self.inner.enabled(metadata, ctx)
+    }
+
+    fn on_new_span(
+        &self,
+        attrs: &tracing::span::Attributes<'_>,
+        id: &tracing::span::Id,
+        ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        self.inner.on_new_span(attrs, id, ctx);
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        self.inner.max_level_hint()
+    }
+
+    fn on_record(
+        &self,
+        span: &tracing::span::Id,
+        values: &tracing::span::Record<'_>,
+        ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        self.inner.on_record(span, values, ctx);
+    }
+
+    fn on_follows_from(
+        &self,
+        span: &tracing::span::Id,
+        follows: &tracing::span::Id,
+        ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        self.inner.on_follows_from(span, follows, ctx);
+    }
+
+    fn event_enabled(
+        &self,
+        event: &Event<'_>,
+        ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) -> bool {
+        self.inner.event_enabled(event, ctx)
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: tracing_subscriber::layer::Context<'_, S>) {
+        let cx = ctx
+            .event_span(event)
+            .and_then(|span| self.span_context(&span.id(), &ctx));
+        let _guard = cx.map(opentelemetry::Context::attach);
+        self.inner.on_event(event, ctx);
+    }
+
+    fn on_enter(&self, id: &tracing::span::Id, ctx: tracing_subscriber::layer::Context<'_, S>) {
+        self.inner.on_enter(id, ctx);
+    }
+
+    fn on_exit(&self, id: &tracing::span::Id, ctx: tracing_subscriber::layer::Context<'_, S>) {
+        self.inner.on_exit(id, ctx);
+    }
+
+    fn on_close(&self, id: tracing::span::Id, ctx: tracing_subscriber::layer::Context<'_, S>) {
+        self.inner.on_close(id, ctx);
+    }
+
+    fn on_id_change(
+        &self,
+        old: &tracing::span::Id,
+        new: &tracing::span::Id,
+        ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        self.inner.on_id_change(old, new, ctx);
+    }
+
+    unsafe fn downcast_raw(&self, id: std::any::TypeId) -> Option<*const ()> {
+        if id == std::any::TypeId::of::<Self>() {
+            Some(std::ptr::addr_of!(*self).cast())
+        } else {
+            self.inner.downcast_raw(id)
+        }
+    }
 }
 
 fn get_plaintext_log_layer<S>() -> anyhow::Result<impl Layer<S>>
@@ -356,3 +555,150 @@ fn wasi_level_to_tracing_level(level: &Level) -> LevelFilter {
         Level::Trace => LevelFilter::TRACE,
     }
 }
+
+#[cfg(all(test, feature = "otel"))]
+mod test {
+    use super::*;
+
+    use std::sync::{Arc, Mutex};
+
+    use opentelemetry::trace::{SpanContext, TraceContextExt, TracerProvider as _};
+    use opentelemetry_sdk::trace::{Sampler, TracerProvider};
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    /// Records the hooks it is called with and the OTEL span context current during events
+    #[derive(Clone, Default)]
+    struct Recorder {
+        hooks: Arc<Mutex<Vec<&'static str>>>,
+        events: Arc<Mutex<Vec<SpanContext>>>,
+    }
+
+    impl<S: Subscriber> Layer<S> for Recorder {
+        fn on_new_span(
+            &self,
+            _attrs: &tracing::span::Attributes<'_>,
+            _id: &tracing::span::Id,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            self.hooks.lock().unwrap().push("new_span");
+        }
+
+        fn on_record(
+            &self,
+            _span: &tracing::span::Id,
+            _values: &tracing::span::Record<'_>,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            self.hooks.lock().unwrap().push("record");
+        }
+
+        fn on_event(&self, _event: &Event<'_>, _ctx: tracing_subscriber::layer::Context<'_, S>) {
+            self.hooks.lock().unwrap().push("event");
+            self.events.lock().unwrap().push(
+                opentelemetry::Context::current()
+                    .span()
+                    .span_context()
+                    .clone(),
+            );
+        }
+
+        fn on_enter(
+            &self,
+            _id: &tracing::span::Id,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            self.hooks.lock().unwrap().push("enter");
+        }
+
+        fn on_exit(
+            &self,
+            _id: &tracing::span::Id,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            self.hooks.lock().unwrap().push("exit");
+        }
+
+        fn on_close(
+            &self,
+            _id: tracing::span::Id,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            self.hooks.lock().unwrap().push("close");
+        }
+    }
+
+    /// Emits an event within a span and returns the span context the tracer assigned the span,
+    /// along with the recorder
+    fn trace_event(sampler: Sampler) -> (SpanContext, Recorder) {
+        let provider = TracerProvider::builder()
+            .with_config(opentelemetry_sdk::trace::config().with_sampler(sampler.clone()))
+            .build();
+        let recorder = Recorder::default();
+        let subscriber = Registry::default()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")))
+            .with(TraceCorrelationLayer {
+                inner: recorder.clone(),
+                sampler,
+            });
+        let span_context = tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("test", field = tracing::field::Empty);
+            let _enter = span.enter();
+            span.record("field", "value");
+            tracing::info!("test event");
+            span.context().span().span_context().clone()
+        });
+        (span_context, recorder)
+    }
+
+    #[test]
+    fn correlate_sampled_span() {
+        let (span_context, recorder) = trace_event(Sampler::AlwaysOn);
+        assert!(span_context.is_valid());
+        assert!(span_context.is_sampled());
+        let events = recorder.events.lock().unwrap();
+        let [event] = events.as_slice() else {
+            panic!("expected a single event, got {events:?}");
+        };
+        assert_eq!(event.trace_id(), span_context.trace_id());
+        assert_eq!(event.span_id(), span_context.span_id());
+        assert!(event.is_sampled());
+    }
+
+    #[test]
+    fn correlate_unsampled_span() {
+        let (span_context, recorder) = trace_event(Sampler::AlwaysOff);
+        assert!(!span_context.is_sampled());
+        let events = recorder.events.lock().unwrap();
+        let [event] = events.as_slice() else {
+            panic!("expected a single event, got {events:?}");
+        };
+        assert_eq!(event.span_id(), span_context.span_id());
+        assert!(!event.is_sampled());
+    }
+
+    #[test]
+    fn forward_hooks() {
+        let (_, recorder) = trace_event(Sampler::AlwaysOn);
+        assert_eq!(
+            *recorder.hooks.lock().unwrap(),
+            ["new_span", "enter", "record", "event", "exit", "close"]
+        );
+    }
+
+    #[test]
+    fn event_outside_span() {
+        let recorder = Recorder::default();
+        let subscriber = Registry::default()
+            .with(tracing_opentelemetry::layer())
+            .with(TraceCorrelationLayer {
+                inner: recorder.clone(),
+                sampler: Sampler::AlwaysOn,
+            });
+        tracing::subscriber::with_default(subscriber, || tracing::info!("test event"));
+        let events = recorder.events.lock().unwrap();
+        let [event] = events.as_slice() else {
+            panic!("expected a single event, got {events:?}");
+        };
+        assert!(!event.is_valid());
+    }
+}
@@ -0,0 +1,227 @@
+use core::convert::Infallible;
+
+use anyhow::{bail, Context};
+use http_body_util::Full;
+use hyper::body::Bytes;
+use hyper::service::service_fn;
+use hyper::{header, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::metrics::reader::{DefaultAggregationSelector, DefaultTemporalitySelector};
+use opentelemetry_sdk::metrics::{MeterProvider, PeriodicReader};
+use prometheus::{Encoder, TextEncoder};
+use tokio::net::TcpListener;
+use tracing::{debug, info, warn};
+use wasmcloud_core::OtelConfig;
+
+use crate::otel_resource;
+
+/// Host the Prometheus exporter serves metrics on, unless configured otherwise
+const DEFAULT_PROMETHEUS_HOST: &str = "localhost";
+
+/// Port the Prometheus exporter serves metrics on, unless configured otherwise
+const DEFAULT_PROMETHEUS_PORT: u16 = 9464;
+
+/// Configures the global OTEL meter provider, which exports the metrics recorded by all meters
+/// using the comma-separated exporters in `OTEL_METRICS_EXPORTER`:
+/// - `otlp` periodically pushes metrics to the OTLP endpoint
+/// - `prometheus` serves metrics to be scraped on `/metrics`
+///
+/// Metrics are not exported if no exporter is configured.
+///
+/// # Errors
+///
+/// This will return an error if an exporter is not supported or fails to be created, or if the
+/// Prometheus exporter address cannot be bound
+#[allow(clippy::module_name_repetitions)]
+pub async fn configure_metrics(service_name: &str, otel_config: &OtelConfig) -> anyhow::Result<()> {
+    let Some(exporters) = otel_config.metrics_exporter.as_ref() else {
+        return Ok(());
+    };
+
+    let mut provider =
+        MeterProvider::builder().with_resource(otel_resource(service_name, otel_config));
+    for exporter in exporters.split(',').map(str::trim) {
+        match exporter.to_ascii_lowercase().as_str() {
+            "otlp" => {
+                let mut builder = opentelemetry_otlp::new_exporter()
+                    .http()
+                    .with_protocol(opentelemetry_otlp::Protocol::HttpBinary);
+                if let Some(ref endpoint) = otel_config.exporter_otlp_endpoint {
+                    builder = builder.with_endpoint(endpoint);
+                }
+                let exporter = builder
+                    .build_metrics_exporter(
+                        Box::new(DefaultAggregationSelector::new()),
+                        Box::new(DefaultTemporalitySelector::new()),
+                    )
+                    .context("failed to create OTEL metrics exporter")?;
+                provider = provider.with_reader(
+                    PeriodicReader::builder(exporter, opentelemetry_sdk::runtime::Tokio).build(),
+                );
+            }
+            "prometheus" => {
+                let registry = prometheus::Registry::new();
+                let exporter = opentelemetry_prometheus::exporter()
+                    .with_registry(registry.clone())
+                    .build()
+                    .context("failed to create Prometheus metrics exporter")?;
+                provider = provider.with_reader(exporter);
+                serve_prometheus(
+                    registry,
+                    otel_config
+                        .exporter_prometheus_host
+                        .as_deref()
+                        .unwrap_or(DEFAULT_PROMETHEUS_HOST),
+                    otel_config
+                        .exporter_prometheus_port
+                        .unwrap_or(DEFAULT_PROMETHEUS_PORT),
+                )
+                .await?;
+            }
+            "none" => {}
+            _ => bail!("unsupported OTEL metrics exporter: '{exporter}'"),
+        }
+    }
+    opentelemetry::global::set_meter_provider(provider.build());
+    Ok(())
+}
+
+/// Serves the metrics gathered in `registry` in the Prometheus text format on `/metrics`
+async fn serve_prometheus(
+    registry: prometheus::Registry,
+    host: &str,
+    port: u16,
+) -> anyhow::Result<()> {
+    let listener = TcpListener::bind((host, port))
+        .await
+        .with_context(|| format!("failed to bind Prometheus exporter on `{host}:{port}`"))?;
+    info!(host, port, "serving Prometheus metrics");
+    serve(listener, registry);
+    Ok(())
+}
+
+/// Serves scrapes of the metrics gathered in `registry` on connections accepted by `listener`
+/// in a new task
+fn serve(listener: TcpListener, registry: prometheus::Registry) {
+    tokio::spawn(async move {
+        loop {
+            let stream = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(err) => {
+                    warn!(?err, "failed to accept Prometheus scrape connection");
+                    continue;
+                }
+            };
+            let registry = registry.clone();
+            tokio::spawn(async move {
+                let service = service_fn(|request| {
+                    let response = scrape(&registry, &request);
+                    async move { Ok::<_, Infallible>(response) }
+                });
+                if let Err(err) = hyper::server::conn::http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await
+                {
+                    debug!(?err, "failed to serve Prometheus scrape connection");
+                }
+            });
+        }
+    });
+}
+
+fn scrape<T>(registry: &prometheus::Registry, request: &Request<T>) -> Response<Full<Bytes>> {
+    if request.uri().path() != "/metrics" {
+        let mut response = Response::new(Full::default());
+        *response.status_mut() = StatusCode::NOT_FOUND;
+        return response;
+    }
+    let encoder = TextEncoder::new();
+    let mut buf = Vec::new();
+    if let Err(err) = encoder.encode(&registry.gather(), &mut buf) {
+        warn!(?err, "failed to encode Prometheus metrics");
+        let mut response = Response::new(Full::default());
+        *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+        return response;
+    }
+    let mut response = Response::new(Full::new(Bytes::from(buf)));
+    if let Ok(content_type) = encoder.format_type().parse() {
+        response
+            .headers_mut()
+            .insert(header::CONTENT_TYPE, content_type);
+    }
+    response
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use http_body_util::BodyExt;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    fn registry() -> prometheus::Registry {
+        let registry = prometheus::Registry::new();
+        let counter = prometheus::IntCounter::new("test_invocations_total", "Test invocations")
+            .expect("failed to create counter");
+        counter.inc_by(3);
+        registry
+            .register(Box::new(counter))
+            .expect("failed to register counter");
+        registry
+    }
+
+    fn request(path: &str) -> Request<()> {
+        Request::get(path)
+            .body(())
+            .expect("failed to build request")
+    }
+
+    #[tokio::test]
+    async fn scrape_metrics() {
+        let registry = registry();
+        let response = scrape(&registry, &request("/metrics"));
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response
+                .headers()
+                .get(header::CONTENT_TYPE)
+                .map(|v| v.as_bytes()),
+            Some(TextEncoder::new().format_type().as_bytes())
+        );
+        let body = response
+            .into_body()
+            .collect()
+            .await
+            .expect("failed to read body")
+            .to_bytes();
+        let body = String::from_utf8_lossy(&body);
+        assert!(body.contains("test_invocations_total 3"), "{body}");
+
+        let response = scrape(&registry, &request("/other"));
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn serve_metrics() {
+        let listener = TcpListener::bind(("127.0.0.1", 0))
+            .await
+            .expect("failed to bind listener");
+        let addr = listener.local_addr().expect("failed to get address");
+        serve(listener, registry());
+
+        let mut stream = TcpStream::connect(addr).await.expect("failed to connect");
+        stream
+            .write_all(b"GET /metrics HTTP/1.1\r\nhost: localhost\r\nconnection: close\r\n\r\n")
+            .await
+            .expect("failed to write request");
+        let mut response = String::new();
+        stream
+            .read_to_string(&mut response)
+            .await
+            .expect("failed to read response");
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+        assert!(response.contains("test_invocations_total 3"), "{response}");
+    }
+}
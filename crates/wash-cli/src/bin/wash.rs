@@ -26,6 +26,7 @@ use wash_lib::cli::get::GetCommand;
 use wash_lib::cli::inspect::InspectCliCommand;
 use wash_lib::cli::label::LabelHostCommand;
 use wash_lib::cli::link::LinkCommand;
+use wash_lib::cli::policy::PolicyCommand;
 use wash_lib::cli::registry::{RegistryCommand, RegistryPullCommand, RegistryPushCommand};
 use wash_lib::cli::scale::ScaleCommand;
 use wash_lib::cli::spy::SpyCommand;
@@ -73,6 +74,7 @@ Iterate:
   call         Invoke a wasmCloud actor
   ctl          Interact with a wasmCloud control interface (deprecated, use above commands)
   label        Label (or un-label) a host with a key=value label pair
+  policy       Query policy decisions made by hosts
 
 Publish:
   pull         Pull an artifact from an OCI compliant registry
@@ -209,6 +211,9 @@ enum CliCommand {
     /// Label (or un-label) a host
     #[clap(name = "label", alias = "tag")]
     Label(LabelHostCommand),
+    /// Query policy decisions made by hosts
+    #[clap(name = "policy", subcommand)]
+    Policy(PolicyCommand),
     /// Update an actor running in a host to a newer version
     #[clap(name = "update", subcommand)]
     Update(UpdateCommand),
@@ -303,6 +308,7 @@ async fn main() {
         CliCommand::Label(label_cli) => {
             common::label_cmd::handle_command(label_cli, output_kind).await
         }
+        CliCommand::Policy(policy_cli) => wash_lib::cli::policy::handle_command(policy_cli).await,
         CliCommand::Update(update_cli) => {
             common::update_cmd::handle_command(update_cli, output_kind).await
         }
@@ -1,6 +1,6 @@
 use std::{collections::HashMap, path::PathBuf};
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use clap::Parser;
 use serde_json::json;
 
@@ -88,7 +88,7 @@ pub async fn handle_command(command: BuildCommand) -> Result<CommandOutput> {
                 )?;
                 config.common.path.join(signed_path)
             } else {
-                build_project(&config, sign_config)?
+                build_project(&config, sign_config).await?
             };
 
             let json_output = HashMap::from([
@@ -107,12 +107,54 @@ pub async fn handle_command(command: BuildCommand) -> Result<CommandOutput> {
                 json_output,
             ))
         }
-        _ => {
-            // Until providers and interfaces have build support, this codepath won't be exercised
-            let path = build_project(&config, None)?;
+        TypeConfig::Provider(ref provider_config) => {
+            if command.sign_only {
+                bail!("--sign-only is not supported for providers, the provider archive is signed as it is created");
+            }
+
+            let sign_config = if command.build_only {
+                None
+            } else {
+                Some(SignConfig {
+                    keys_directory: command
+                        .keys_directory
+                        .clone()
+                        .or(Some(provider_config.key_directory.to_path_buf())),
+                    issuer: command.issuer,
+                    subject: command.subject,
+                    disable_keygen: command.disable_keygen,
+                })
+            };
+
+            let provider_path = build_project(&config, sign_config).await?;
+
+            let json_output = HashMap::from([
+                ("provider_path".to_string(), json!(provider_path)),
+                ("built".to_string(), json!(true)),
+                ("signed".to_string(), json!(!command.build_only)),
+            ]);
+            Ok(CommandOutput::new(
+                if command.build_only {
+                    format!("Provider built and can be found at {provider_path:?}")
+                } else {
+                    format!("Provider built and signed and can be found at {provider_path:?}")
+                },
+                json_output,
+            ))
+        }
+        TypeConfig::Interface(_) => {
+            if command.sign_only {
+                bail!("--sign-only is not supported for interfaces, WIT packages are not signed");
+            }
+
+            let interface_path = build_project(&config, None).await?;
             Ok(CommandOutput::new(
-                format!("Built artifact can be found at {path:?}"),
-                HashMap::from([("path".to_string(), json!(path))]),
+                format!("Interface built and can be found at {interface_path:?}"),
+                HashMap::from([
+                    ("interface_path".to_string(), json!(interface_path)),
+                    ("built".to_string(), json!(true)),
+                    ("signed".to_string(), json!(false)),
+                ]),
             ))
         }
     }
@@ -108,7 +108,7 @@ pub async fn write_artifact(
 ) -> Result<String> {
     let file_extension = match validate_artifact(artifact).await? {
         SupportedArtifacts::Par => PROVIDER_ARCHIVE_FILE_EXTENSION,
-        SupportedArtifacts::Wasm => WASM_FILE_EXTENSION,
+        SupportedArtifacts::Wasm | SupportedArtifacts::Wit => WASM_FILE_EXTENSION,
     };
     // Output to provided file, or use artifact_name.file_extension
     let outfile = output.unwrap_or(format!(
@@ -302,6 +302,14 @@ fn prompt_for_context() -> Result<WashContext> {
         Ok(s) => Some(s),
         _ => None,
     };
+    let ctl_caller_seed = match user_question(
+        "Enter the seed of the key that signs control interface requests to identify you to hosts, if applicable",
+        &Some(String::new()),
+    ) {
+        Ok(s) if s.is_empty() => None,
+        Ok(s) => Some(s),
+        _ => None,
+    };
     let ctl_timeout = user_question(
         "What should the control interface timeout be (in milliseconds)?",
         &Some(DEFAULT_NATS_TIMEOUT_MS.to_string()),
@@ -366,6 +374,7 @@ fn prompt_for_context() -> Result<WashContext> {
         ctl_jwt,
         ctl_seed,
         ctl_credsfile: ctl_credsfile.map(PathBuf::from),
+        ctl_caller_seed,
         ctl_timeout: ctl_timeout.parse()?,
         lattice,
         js_domain,
@@ -13,11 +13,14 @@ use wash_lib::generate::emoji;
 use wash_lib::{
     actor::{scale_actor, start_actor, StartActorArgs},
     build::{build_project, SignConfig},
-    cli::dev::run_dev_loop,
+    cli::dev::{
+        dev_project_config, reload_provider, run_dev_loop, DevArtifact, DEV_PROVIDER_LINK_NAME,
+    },
     cli::CommandOutput,
     config::downloads_dir,
-    id::{ModuleId, ServerId},
-    parser::get_config,
+    id::{ModuleId, ServerId, ServiceId},
+    parser::{get_config, TypeConfig},
+    provider::{start_provider, StartProviderArgs},
 };
 use wasmcloud_control_interface::Host;
 
@@ -258,7 +261,8 @@ pub async fn handle_command(
     );
 
     // Build the project
-    let artifact_path = build_project(&project_cfg, sign_cfg.clone())
+    let artifact_path = build_project(&dev_project_config(&project_cfg), sign_cfg.clone())
+        .await
         .context("failed to build project")?
         .canonicalize()
         .context("failed to canonicalize path")?;
@@ -267,38 +271,89 @@ pub async fn handle_command(
         artifact_path.display()
     );
 
-    // Since we're using the actor from file on disk, the ref should be the file path (canonicalized) on disk as URI
-    let actor_ref = format!("file://{}", artifact_path.display());
-    let actor_id;
+    // Since we're using the artifact from file on disk, the ref should be the file path (canonicalized) on disk as URI
+    let artifact_ref = format!("file://{}", artifact_path.display());
 
-    // Attempt to find or create the actor, scaling any existing actors to zero if it exists
+    // Attempt to find or create the artifact, reusing any existing instance if it exists
     let inventory = ctl_client.get_host_inventory(&host.id).await.or_else(|e| {
         bail!(
             "failed to retrieve host inventory for host [{}]: {e}",
             &host.id
         )
     })?;
-    if let Some(existing_actor) = inventory
-        .actors
-        .into_iter()
-        .find(|a| a.image_ref == Some(actor_ref.clone()))
-    {
-        actor_id = existing_actor.id;
-        scale_actor(&ctl_client, &host.id, &actor_ref, 1, None).await?;
-    } else {
-        // Start the actor for the first time
-        actor_id = start_actor(StartActorArgs {
-            ctl_client: &ctl_client,
-            host_id: &host.id,
-            actor_ref: &actor_ref,
-            count: 1,
-            skip_wait: false,
-            timeout_ms: None,
-        })
-        .await?
-        .actor_id
-        .ok_or_else(|| anyhow!("failed to do thing"))?;
-    }
+    let host_id = ServerId::from_str(&host.id)?;
+    let mut artifact = match &project_cfg.project_type {
+        TypeConfig::Actor(_) => {
+            let actor_id = if let Some(existing_actor) = inventory
+                .actors
+                .into_iter()
+                .find(|a| a.image_ref == Some(artifact_ref.clone()))
+            {
+                scale_actor(&ctl_client, &host.id, &artifact_ref, 1, None).await?;
+                existing_actor.id
+            } else {
+                // Start the actor for the first time
+                start_actor(StartActorArgs {
+                    ctl_client: &ctl_client,
+                    host_id: &host.id,
+                    actor_ref: &artifact_ref,
+                    count: 1,
+                    skip_wait: false,
+                    timeout_ms: None,
+                })
+                .await?
+                .actor_id
+                .ok_or_else(|| anyhow!("failed to do thing"))?
+            };
+            DevArtifact::Actor {
+                actor_id: ModuleId::from_str(&actor_id)?,
+                actor_ref: artifact_ref,
+            }
+        }
+        TypeConfig::Provider(provider_config) => {
+            let contract_id = provider_config.capability_id.clone();
+            let provider_id = if let Some(existing_provider) =
+                inventory.providers.into_iter().find(|p| {
+                    p.image_ref == Some(artifact_ref.clone())
+                        && p.link_name == DEV_PROVIDER_LINK_NAME
+                }) {
+                // Restart the existing provider so that the freshly built archive is used
+                reload_provider(
+                    &ctl_client,
+                    &host_id,
+                    &ServiceId::from_str(&existing_provider.id)?,
+                    &artifact_ref,
+                    DEV_PROVIDER_LINK_NAME,
+                    &contract_id,
+                )
+                .await?
+            } else {
+                // Start the provider for the first time
+                let started = start_provider(StartProviderArgs {
+                    ctl_client: &ctl_client,
+                    host_id: &host.id,
+                    provider_ref: &artifact_ref,
+                    link_name: DEV_PROVIDER_LINK_NAME,
+                    annotations: None,
+                    config_json: None,
+                    skip_wait: false,
+                    timeout_ms: None,
+                })
+                .await?
+                .context("provider start event was not received")?;
+                ServiceId::from_str(&started.provider_id)?
+            };
+            DevArtifact::Provider {
+                provider_id,
+                provider_ref: artifact_ref,
+                link_name: DEV_PROVIDER_LINK_NAME.to_string(),
+                contract_id,
+            }
+        }
+        TypeConfig::Interface(_) => {
+            bail!("`wash dev` does not support interface projects, use `wash build` instead")
+        }
+    };
 
     // Set up a oneshot channel to remove
     let (stop_tx, mut stop_rx) = mpsc::channel::<()>(1);
@@ -358,9 +413,8 @@ pub async fn handle_command(
                 pause_watch.store(true, Ordering::SeqCst);
                 run_dev_loop(
                     &project_cfg,
-                    ModuleId::from_str(&actor_id)?,
-                    &actor_ref,
-                    ServerId::from_str(&host.id)?,
+                    &mut artifact,
+                    host_id.clone(),
                     &ctl_client,
                     sign_cfg.clone(),
                 ).await?;
@@ -10,7 +10,7 @@ use tokio::process::Command;
 use tracing::{error, warn};
 use wash_lib::cli::{CommandOutput, OutputKind};
 use wash_lib::config::{
-    cfg_dir, create_nats_client_from_opts, DEFAULT_NATS_HOST, DEFAULT_NATS_PORT,
+    cfg_dir, create_nats_client_from_opts, ctl_caller_key, DEFAULT_NATS_HOST, DEFAULT_NATS_PORT,
 };
 use wash_lib::id::ServerId;
 use wash_lib::start::{nats_pid_path, NATS_SERVER_BINARY, WADM_PID};
@@ -53,6 +53,15 @@ pub struct DownCommand {
     #[clap(long = "ctl-jwt", env = WASMCLOUD_CTL_JWT, requires = "ctl_seed")]
     pub ctl_jwt: Option<String>,
 
+    /// Seed file or literal of the key used to sign requests to stop hosts, identifying the caller
+    /// to hosts for policy decisions
+    #[clap(
+        long = "ctl-caller-seed",
+        env = "WASH_CTL_CALLER_SEED",
+        hide_env_values = true
+    )]
+    pub ctl_caller_seed: Option<String>,
+
     #[clap(long = "host-id")]
     pub host_id: Option<ServerId>,
 
@@ -87,7 +96,12 @@ pub async fn handle_down(cmd: DownCommand, output_kind: OutputKind) -> Result<Co
     )
     .await
     {
-        let (hosts, hosts_remain) = stop_hosts(client, &cmd.lattice, &cmd.host_id, cmd.all).await?;
+        let caller_key = match &cmd.ctl_caller_seed {
+            Some(seed) => Some(ctl_caller_key(seed).await?),
+            None => None,
+        };
+        let (hosts, hosts_remain) =
+            stop_hosts(client, &cmd.lattice, caller_key, &cmd.host_id, cmd.all).await?;
         out_json.insert("hosts_stopped".to_string(), json!(hosts));
         out_text.push_str("✅ wasmCloud hosts stopped successfully\n");
         if hosts_remain {
@@ -140,13 +154,17 @@ pub async fn handle_down(cmd: DownCommand, output_kind: OutputKind) -> Result<Co
 async fn stop_hosts(
     nats_client: Client,
     lattice: &str,
+    caller_key: Option<std::sync::Arc<nkeys::KeyPair>>,
     host_id: &Option<ServerId>,
     all: bool,
 ) -> Result<(Vec<String>, bool)> {
-    let client = wasmcloud_control_interface::ClientBuilder::new(nats_client)
+    let mut builder = wasmcloud_control_interface::ClientBuilder::new(nats_client)
         .lattice(lattice)
-        .auction_timeout(std::time::Duration::from_secs(2))
-        .build();
+        .auction_timeout(std::time::Duration::from_secs(2));
+    if let Some(key) = caller_key {
+        builder = builder.caller_key(key);
+    }
+    let client = builder.build();
 
     let hosts = client.get_hosts().await.map_err(|e| anyhow!(e))?;
 
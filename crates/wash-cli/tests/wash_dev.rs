@@ -2,15 +2,24 @@ mod common;
 
 use common::{
     find_open_port, init, start_nats, test_dir_with_subfolder, wait_for_no_hosts, wait_for_no_nats,
+    TestWashInstance, ECHO_OCI_REF, PROVIDER_HTTPSERVER_OCI_REF,
 };
 
+use std::collections::HashMap;
+#[cfg(target_family = "unix")]
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use anyhow::{anyhow, bail};
 #[cfg(target_family = "unix")]
 use anyhow::{Context, Result};
+use nkeys::KeyPair;
 use serial_test::serial;
 use tokio::{process::Command, sync::RwLock, time::Duration};
+use wash_lib::build::{sign_provider_archive, SignConfig};
+use wash_lib::cli::dev::{dev_revision, reload_provider, DEV_PROVIDER_LINK_NAME};
+use wash_lib::parser::{CommonConfig, ProviderConfig, RegistryConfig};
+use wasmcloud_control_interface::ClientBuilder;
 
 #[tokio::test]
 #[serial]
@@ -107,3 +116,180 @@ async fn integration_dev_hello_actor_serial() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+#[serial]
+#[cfg(target_family = "unix")]
+async fn integration_dev_reload_provider_serial() -> Result<()> {
+    let wash = TestWashInstance::create().await?;
+
+    let provider_id = wash
+        .start_provider(PROVIDER_HTTPSERVER_OCI_REF)
+        .await?
+        .provider_id
+        .context("provider ID missing from start output")?;
+    let actor_id = wash
+        .start_actor(ECHO_OCI_REF)
+        .await?
+        .actor_id
+        .context("actor ID missing from start output")?;
+
+    let nc = async_nats::connect(format!("127.0.0.1:{}", wash.nats_port))
+        .await
+        .context("failed to connect to NATS")?;
+    let ctl_client = ClientBuilder::new(nc).build();
+
+    let address = format!("127.0.0.1:{}", find_open_port().await?);
+    let ack = ctl_client
+        .advertise_link(
+            &actor_id,
+            &provider_id,
+            "wasmcloud:httpserver",
+            DEV_PROVIDER_LINK_NAME,
+            HashMap::from([("ADDRESS".to_string(), address.clone())]),
+        )
+        .await
+        .map_err(|e| anyhow!(e))?;
+    assert!(ack.accepted, "link was not accepted: {}", ack.error);
+
+    let mut events = ctl_client
+        .events_receiver(vec!["linkdef_set".to_string()])
+        .await
+        .map_err(|e| anyhow!(e))?;
+
+    let reloaded_provider_id = reload_provider(
+        &ctl_client,
+        &wash.host_id.parse()?,
+        &provider_id.parse()?,
+        PROVIDER_HTTPSERVER_OCI_REF,
+        DEV_PROVIDER_LINK_NAME,
+        "wasmcloud:httpserver",
+    )
+    .await?;
+    assert_eq!(reloaded_provider_id.as_ref(), provider_id);
+
+    // The link must be re-advertised to the restarted provider, with its original values
+    let event = tokio::time::timeout(Duration::from_secs(10), events.recv())
+        .await
+        .context("timed out waiting for the link to be re-advertised")?
+        .context("lattice event stream ended")?;
+    let event = serde_json::to_value(event)?;
+    assert_eq!(event["data"]["actor_id"], actor_id.as_str());
+    assert_eq!(event["data"]["provider_id"], provider_id.as_str());
+    assert_eq!(event["data"]["link_name"], DEV_PROVIDER_LINK_NAME);
+    assert_eq!(event["data"]["values"]["ADDRESS"], address.as_str());
+
+    let links = ctl_client.query_links().await.map_err(|e| anyhow!(e))?;
+    assert!(
+        links
+            .iter()
+            .any(|ld| ld.actor_id == actor_id && ld.provider_id == provider_id),
+        "link is missing after the provider was reloaded"
+    );
+
+    Ok(())
+}
+
+/// Packages `script` as the native binary of a provider archive written to `dir`, signed with a
+/// new [`dev_revision`]
+#[cfg(target_family = "unix")]
+async fn sign_script_provider(
+    dir: &Path,
+    script: &str,
+    issuer: &KeyPair,
+    subject: &KeyPair,
+) -> Result<(PathBuf, i32)> {
+    let bin = dir.join("provider.sh");
+    tokio::fs::write(&bin, script)
+        .await
+        .context("failed to write provider binary")?;
+    let revision = dev_revision();
+    let common_config = CommonConfig {
+        name: "reload".into(),
+        version: semver::Version::new(0, 1, 0),
+        revision,
+        path: dir.to_path_buf(),
+        wasm_bin_name: None,
+        registry: RegistryConfig::default(),
+    };
+    let provider_config = ProviderConfig {
+        capability_id: "wasmcloud:testing".into(),
+        vendor: "test".into(),
+        destination: Some(dir.join("reload.par")),
+        ..Default::default()
+    };
+    let sign_config = SignConfig {
+        keys_directory: None,
+        issuer: Some(issuer.seed()?),
+        subject: Some(subject.seed()?),
+        disable_keygen: true,
+    };
+    let target = format!("{}-{}", std::env::consts::ARCH, std::env::consts::OS);
+    let par = sign_provider_archive(
+        &common_config,
+        &provider_config,
+        sign_config,
+        &[(target, bin)],
+    )
+    .await?;
+    Ok((par, revision))
+}
+
+#[tokio::test]
+#[serial]
+#[cfg(target_family = "unix")]
+async fn integration_dev_reload_local_provider_serial() -> Result<()> {
+    let wash = TestWashInstance::create().await?;
+    let dir = test_dir_with_subfolder("dev_reload_local_provider");
+
+    let issuer = KeyPair::new_account();
+    let subject = KeyPair::new_service();
+    let (par, _) = sign_script_provider(
+        &dir,
+        "#!/bin/sh\n# build 1\nexec sleep 3600\n",
+        &issuer,
+        &subject,
+    )
+    .await?;
+    let provider_ref = format!("file://{}", par.display());
+    let provider_id = wash
+        .start_provider(&provider_ref)
+        .await?
+        .provider_id
+        .context("provider ID missing from start output")?;
+    assert_eq!(provider_id, subject.public_key());
+
+    // Rebuild the archive in place with a different binary, like the dev loop does
+    let rebuilt = "#!/bin/sh\n# build 2\nexec sleep 3600\n";
+    let (_, revision) = sign_script_provider(&dir, rebuilt, &issuer, &subject).await?;
+
+    let nc = async_nats::connect(format!("127.0.0.1:{}", wash.nats_port))
+        .await
+        .context("failed to connect to NATS")?;
+    let ctl_client = ClientBuilder::new(nc).build();
+    let reloaded_provider_id = reload_provider(
+        &ctl_client,
+        &wash.host_id.parse()?,
+        &provider_id.parse()?,
+        &provider_ref,
+        DEV_PROVIDER_LINK_NAME,
+        "wasmcloud:testing",
+    )
+    .await?;
+    assert_eq!(reloaded_provider_id.as_ref(), provider_id);
+
+    // The host caches provider binaries by subject and revision, so the rebuilt binary must have
+    // been cached and started under the new revision
+    let cached = std::env::temp_dir()
+        .join("wasmcloudcache")
+        .join(subject.public_key())
+        .join(revision.to_string())
+        .join(format!("wasmcloud_testing_{DEV_PROVIDER_LINK_NAME}"));
+    let cached = tokio::fs::read_to_string(&cached)
+        .await
+        .with_context(|| format!("rebuilt provider binary missing at [{}]", cached.display()))?;
+    assert_eq!(cached, rebuilt);
+
+    std::fs::remove_dir_all(dir)?;
+    Ok(())
+}
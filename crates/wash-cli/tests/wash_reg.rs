@@ -12,6 +12,7 @@ use std::{
 use anyhow::{Context, Result};
 
 use serde_json::json;
+use wash_lib::registry::{validate_artifact, SupportedArtifacts};
 
 use crate::common::{init, set_test_file_content};
 
@@ -239,6 +240,88 @@ fn integration_reg_push_comprehensive() {
     remove_dir_all(push_dir).unwrap();
 }
 
+// NOTE: This test will fail without a local docker registry running
+#[tokio::test]
+async fn integration_reg_push_pull_wit_package() -> Result<()> {
+    const SUBFOLDER: &str = "push_pull_wit_package";
+    let project_dir = test_dir_with_subfolder(SUBFOLDER);
+
+    //===== Build a WIT package from an interface project
+    set_test_file_content(
+        &project_dir.join("wasmcloud.toml"),
+        r#"
+        language = "other"
+        type = "interface"
+        name = "greeting"
+        version = "0.1.0"
+
+        [interface]
+        destination = "./build/greeting.wasm"
+        "#,
+    )
+    .await?;
+    std::fs::create_dir(project_dir.join("wit")).context("failed to create WIT dir")?;
+    set_test_file_content(
+        &project_dir.join("wit").join("greeting.wit"),
+        r#"
+package washtest:greeting@0.1.0;
+
+interface greeter {
+    greet: func(name: string) -> string;
+}
+"#,
+    )
+    .await?;
+
+    let build = wash()
+        .args(["build"])
+        .current_dir(&project_dir)
+        .output()
+        .context("failed to build WIT package")?;
+    assert!(build.status.success());
+    let wit_package = project_dir.join("build/greeting.wasm");
+    let wit_package_bytes = std::fs::read(&wit_package).context("failed to read WIT package")?;
+
+    //===== Push the WIT package and pull it back down
+    let wit_package_url = &format!("{LOCAL_REGISTRY}/greeting:0.1.0");
+    let push = wash()
+        .args([
+            "reg",
+            "push",
+            wit_package_url,
+            wit_package.to_str().unwrap(),
+            "--insecure",
+        ])
+        .output()
+        .context("failed to push WIT package")?;
+    assert!(push.status.success());
+
+    let pulled_wit_package = test_dir_file(SUBFOLDER, "pulled_greeting.wasm");
+    let pull = wash()
+        .args([
+            "reg",
+            "pull",
+            wit_package_url,
+            "--destination",
+            pulled_wit_package.to_str().unwrap(),
+            "--insecure",
+        ])
+        .output()
+        .context("failed to pull WIT package")?;
+    assert!(pull.status.success());
+
+    let pulled_bytes =
+        std::fs::read(&pulled_wit_package).context("failed to read pulled WIT package")?;
+    assert_eq!(pulled_bytes, wit_package_bytes);
+    assert!(matches!(
+        validate_artifact(&pulled_bytes).await?,
+        SupportedArtifacts::Wit
+    ));
+
+    remove_dir_all(project_dir).unwrap();
+    Ok(())
+}
+
 // NOTE: This test will fail without a local docker registry running
 #[tokio::test]
 #[cfg_attr(
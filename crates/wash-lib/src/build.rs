@@ -9,16 +9,20 @@ use std::{
 };
 
 use anyhow::{anyhow, bail, Context, Result};
+use nkeys::KeyPairType;
+use provider_archive::ProviderArchive;
 use tracing::{debug, info, warn};
 use wasm_encoder::{Encode, Section};
 use wit_bindgen_core::Files;
 use wit_bindgen_go::Opts as WitBindgenGoOpts;
-use wit_component::{ComponentEncoder, StringEncoding};
+use wit_component::{ComponentEncoder, DecodedWasm, StringEncoding};
 use wit_parser::{Resolve, WorldId};
 
 use crate::{
     cli::{
         claims::{sign_file, ActorMetadata, GenerateCommon, SignCommand},
+        extract_keypair,
+        par::convert_error,
         OutputKind,
     },
     parser::{
@@ -52,35 +56,36 @@ pub struct SignConfig {
 
 /// Using a [ProjectConfig], usually parsed from a `wasmcloud.toml` file, build the project
 /// with the installed language toolchain. This will delegate to [build_actor] when the project is an actor,
-/// or return an error when trying to build providers or interfaces. This functionality is planned in a future release.
+/// [build_provider] when the project is a capability provider, or [build_interface] when the project is an interface.
 ///
-/// This function returns the path to the compiled artifact, a signed Wasm module, signed provider archive, or compiled
-/// interface library file.
+/// This function returns the path to the compiled artifact, a signed Wasm module, signed provider archive, or encoded
+/// WIT package.
 ///
 /// # Usage
 /// ```no_run
+/// # async fn build() -> anyhow::Result<()> {
 /// use wash_lib::{build::build_project, parser::get_config};
 /// let config = get_config(None, Some(true))?;
-/// let artifact_path = build_project(config)?;
+/// let artifact_path = build_project(&config, None).await?;
 /// println!("Here is the signed artifact: {}", artifact_path.to_string_lossy());
+/// # Ok(())
+/// # }
 /// ```
 /// # Arguments
 /// * `config`: [ProjectConfig] for required information to find, build, and sign an actor
 /// * `signing`: Optional [SignConfig] with information for signing the project artifact. If omitted, the artifact will only be built
 /// * `adapter_bytes`: Optional [&[u8]] bytes that represent a wasm component adapter that should be used, if present.
-pub fn build_project(config: &ProjectConfig, signing: Option<SignConfig>) -> Result<PathBuf> {
+pub async fn build_project(config: &ProjectConfig, signing: Option<SignConfig>) -> Result<PathBuf> {
     match &config.project_type {
         TypeConfig::Actor(actor_config) => {
             build_actor(actor_config, &config.language, &config.common, signing)
         }
-        TypeConfig::Provider(_provider_config) => {
-            bail!(
-                "wash build has not been implemented for providers yet. Please use `make` for now!"
-            )
+        TypeConfig::Provider(provider_config) => {
+            build_provider(provider_config, &config.language, &config.common, signing).await
+        }
+        TypeConfig::Interface(interface_config) => {
+            build_interface(interface_config, &config.common)
         }
-        TypeConfig::Interface(_interface_config) => bail!(
-            "wash build has not be implemented for interfaces yet. Please use `make` for now!"
-        ),
     }
 }
 
@@ -170,6 +175,172 @@ pub fn build_actor(
     }
 }
 
+/// Builds a wasmCloud capability provider using the installed language toolchain for the native target
+/// and any configured cross targets, then packages the binaries into a signed provider archive
+/// with the capability ID, vendor, name, version and revision of the project.
+///
+/// # Arguments
+/// * `provider_config`: [ProviderConfig] for required information to find, build, and sign a provider
+/// * `language_config`: [LanguageConfig] specifying which language the provider is written in
+/// * `common_config`: [CommonConfig] specifying common parameters like [CommonConfig::name] and [CommonConfig::version]
+/// * `signing`: Optional [SignConfig] with information for signing the provider archive. If omitted, the provider
+///    will only be built and the path to the native binary is returned
+pub async fn build_provider(
+    provider_config: &ProviderConfig,
+    language_config: &LanguageConfig,
+    common_config: &CommonConfig,
+    signing_config: Option<SignConfig>,
+) -> Result<PathBuf> {
+    let binaries = match language_config {
+        LanguageConfig::Rust(rust_config) => {
+            build_rust_provider(common_config, rust_config, provider_config)?
+        }
+        LanguageConfig::TinyGo(_) | LanguageConfig::Other(_) => {
+            bail!("wash build only supports Rust providers, please use `wash par create` to package providers written in other languages")
+        }
+    };
+
+    match signing_config {
+        Some(cfg) => sign_provider_archive(common_config, provider_config, cfg, &binaries).await,
+        // The native target is always built first
+        None => Ok(binaries[0].1.clone()),
+    }
+}
+
+/// Package the provider binaries at `binaries` (pairs of provider archive target, e.g. `x86_64-linux`,
+/// and binary path) into a provider archive signed using the provided configuration
+pub async fn sign_provider_archive(
+    common_config: &CommonConfig,
+    provider_config: &ProviderConfig,
+    signing_config: SignConfig,
+    binaries: &[(String, PathBuf)],
+) -> Result<PathBuf> {
+    let (_, native_binary) = binaries
+        .first()
+        .context("at least one provider binary is required to create a provider archive")?;
+
+    let mut par = ProviderArchive::new(
+        &provider_config.capability_id,
+        &common_config.name,
+        &provider_config.vendor,
+        Some(common_config.revision),
+        Some(common_config.version.to_string()),
+    );
+    for (target, binary) in binaries {
+        let bytes = tokio::fs::read(binary)
+            .await
+            .with_context(|| format!("failed to read provider binary [{}]", binary.display()))?;
+        par.add_library(target, &bytes).map_err(convert_error)?;
+    }
+
+    let issuer = extract_keypair(
+        signing_config.issuer,
+        Some(native_binary.to_string_lossy().to_string()),
+        signing_config.keys_directory.clone(),
+        KeyPairType::Account,
+        signing_config.disable_keygen,
+        OutputKind::Json,
+    )?;
+    let subject = extract_keypair(
+        signing_config.subject,
+        Some(native_binary.to_string_lossy().to_string()),
+        signing_config.keys_directory,
+        KeyPairType::Service,
+        signing_config.disable_keygen,
+        OutputKind::Json,
+    )?;
+
+    let destination = provider_config
+        .destination
+        .clone()
+        .map(|p| {
+            if p.is_absolute() {
+                p
+            } else {
+                common_config.path.join(p)
+            }
+        })
+        .unwrap_or_else(|| {
+            common_config
+                .path
+                .join(format!("build/{}.par.gz", common_config.name))
+        });
+    if let Some(p) = destination.parent() {
+        fs::create_dir_all(p)?;
+    }
+
+    par.write(&destination, &issuer, &subject, true)
+        .await
+        .map_err(convert_error)
+        .with_context(|| {
+            format!(
+                "failed to write provider archive to [{}]",
+                destination.display()
+            )
+        })?;
+
+    // Compressed archives are always written with a `.gz` extension
+    Ok(if destination.extension().unwrap_or_default() == "gz" {
+        destination
+    } else {
+        let mut file_name = destination.as_os_str().to_owned();
+        file_name.push(".gz");
+        PathBuf::from(file_name)
+    })
+}
+
+/// Builds a wasmCloud interface by resolving the dependencies listed in `deps.toml` (if present) with `wit-deps`,
+/// validating the WIT package and encoding it as a WIT package binary, which can be pushed to an OCI registry.
+///
+/// # Arguments
+/// * `interface_config`: [InterfaceConfig] for required information to find and encode the WIT package
+/// * `common_config`: [CommonConfig] specifying common parameters like [CommonConfig::name] and [CommonConfig::path]
+pub fn build_interface(
+    interface_config: &InterfaceConfig,
+    common_config: &CommonConfig,
+) -> Result<PathBuf> {
+    let wit_dir = if interface_config.wit_dir.is_absolute() {
+        interface_config.wit_dir.clone()
+    } else {
+        common_config.path.join(&interface_config.wit_dir)
+    };
+    if !wit_dir.is_dir() {
+        bail!(
+            "expected WIT directory at [{}] is missing",
+            wit_dir.display()
+        );
+    }
+
+    if wit_dir.join("deps.toml").is_file() {
+        resolve_wit_deps(interface_config, &wit_dir)?;
+    }
+
+    let wit_package_bytes = encode_wit_package(&wit_dir)?;
+
+    let destination = interface_config
+        .destination
+        .clone()
+        .map(|p| {
+            if p.is_absolute() {
+                p
+            } else {
+                common_config.path.join(p)
+            }
+        })
+        .unwrap_or_else(|| {
+            common_config
+                .path
+                .join(format!("build/{}.wasm", common_config.name))
+        });
+    if let Some(p) = destination.parent() {
+        fs::create_dir_all(p)?;
+    }
+    fs::write(&destination, wit_package_bytes)
+        .with_context(|| format!("failed to write WIT package to [{}]", destination.display()))?;
+
+    Ok(destination)
+}
+
 /// Sign the component at `actor_wasm_path` using the provided configuration
 pub fn sign_actor_wasm(
     common_config: &CommonConfig,
@@ -295,6 +466,106 @@ fn build_rust_actor(
     Ok(common_config.path.join(&copied_wasm_file))
 }
 
+/// Builds a rust provider for the native target and all configured cross targets, returning
+/// pairs of provider archive target and binary path. The native target is always first.
+fn build_rust_provider(
+    common_config: &CommonConfig,
+    rust_config: &RustConfig,
+    provider_config: &ProviderConfig,
+) -> Result<Vec<(String, PathBuf)>> {
+    let metadata = cargo_metadata::MetadataCommand::new()
+        .current_dir(&common_config.path)
+        .exec()?;
+    // Relative target paths are relative to the project directory
+    let target_path = rust_config
+        .target_path
+        .as_ref()
+        .map(|target_path| common_config.path.join(target_path))
+        .unwrap_or_else(|| PathBuf::from(metadata.target_directory.as_path()));
+    let bin_name = provider_config.bin_name(common_config);
+
+    let native_target = format!("{}-{}", std::env::consts::ARCH, std::env::consts::OS);
+    let native_binary = target_path
+        .join("release")
+        .join(format!("{bin_name}{}", std::env::consts::EXE_SUFFIX));
+    let mut builds = vec![(None, native_target, native_binary)];
+    for rust_target in &provider_config.cross_targets {
+        let exe_suffix = if rust_target.contains("-windows") {
+            ".exe"
+        } else {
+            ""
+        };
+        builds.push((
+            Some(rust_target.as_str()),
+            rust_target_to_par_target(rust_target)?,
+            target_path
+                .join(rust_target)
+                .join("release")
+                .join(format!("{bin_name}{exe_suffix}")),
+        ));
+    }
+
+    let mut binaries = Vec::with_capacity(builds.len());
+    for (rust_target, par_target, binary) in builds {
+        let mut command = match rust_config.cargo_path.as_ref() {
+            Some(path) => process::Command::new(path),
+            None => process::Command::new("cargo"),
+        };
+        command.current_dir(&common_config.path);
+        command.args(["build", "--release", "--bin", bin_name.as_str()]);
+        if let Some(rust_target) = rust_target {
+            command.args(["--target", rust_target]);
+        }
+
+        let result = command.status().map_err(|e| {
+            if e.kind() == ErrorKind::NotFound {
+                anyhow!("{:?} command is not found", command.get_program())
+            } else {
+                anyhow!(e)
+            }
+        })?;
+
+        if !result.success() {
+            bail!(
+                "Compiling provider for [{par_target}] failed: {}",
+                result.to_string()
+            )
+        }
+
+        if !binary.exists() {
+            bail!(
+                "Could not find compiled provider binary, please ensure {} exists",
+                binary.display()
+            );
+        }
+        binaries.push((par_target, binary));
+    }
+
+    Ok(binaries)
+}
+
+/// Convert a Rust target triple (e.g. `aarch64-unknown-linux-gnu`) into the `ARCH-OS` format
+/// used for targets in provider archives (e.g. `aarch64-linux`)
+fn rust_target_to_par_target(rust_target: &str) -> Result<String> {
+    let arch = rust_target
+        .split('-')
+        .next()
+        .filter(|arch| !arch.is_empty())
+        .with_context(|| format!("invalid Rust target triple [{rust_target}]"))?;
+    let os = if rust_target.contains("-linux") {
+        "linux"
+    } else if rust_target.contains("-darwin") {
+        "macos"
+    } else if rust_target.contains("-windows") {
+        "windows"
+    } else if rust_target.contains("-freebsd") {
+        "freebsd"
+    } else {
+        bail!("unsupported operating system in Rust target triple [{rust_target}]")
+    };
+    Ok(format!("{arch}-{os}"))
+}
+
 /// Builds a tinygo actor and returns the path to the file.
 fn build_tinygo_actor(
     common_config: &CommonConfig,
@@ -573,6 +844,71 @@ fn convert_wit_dir_to_world(
     Ok((resolve, world_id))
 }
 
+/// Lock and fetch the dependencies listed in the `deps.toml` manifest of a WIT directory using `wit-deps`
+fn resolve_wit_deps(interface_config: &InterfaceConfig, wit_dir: impl AsRef<Path>) -> Result<()> {
+    let wit_dir = wit_dir.as_ref();
+    let mut command = match interface_config.wit_deps_path.as_ref() {
+        Some(path) => process::Command::new(path),
+        None => process::Command::new("wit-deps"),
+    };
+
+    let result = command
+        .arg("--manifest")
+        .arg(wit_dir.join("deps.toml"))
+        .arg("--lock")
+        .arg(wit_dir.join("deps.lock"))
+        .arg("--deps")
+        .arg(wit_dir.join("deps"))
+        .arg("lock")
+        .status()
+        .map_err(|e| {
+            if e.kind() == ErrorKind::NotFound {
+                anyhow!("{:?} command is not found", command.get_program())
+            } else {
+                anyhow!(e)
+            }
+        })?;
+
+    if !result.success() {
+        bail!("Resolving WIT dependencies failed: {}", result.to_string())
+    }
+    info!(
+        "successfully resolved WIT dependencies @ [{}]",
+        wit_dir.display()
+    );
+
+    Ok(())
+}
+
+/// Parse and validate the WIT package (and its dependencies) in the provided directory,
+/// returning the package encoded as a WIT package binary
+fn encode_wit_package(wit_dir: impl AsRef<Path>) -> Result<Vec<u8>> {
+    let mut resolve = wit_parser::Resolve::default();
+    let (package_id, _paths) = resolve.push_dir(wit_dir.as_ref()).with_context(|| {
+        format!(
+            "failed to parse WIT package @ [{}]",
+            wit_dir.as_ref().display()
+        )
+    })?;
+
+    let wit_package_bytes = wit_component::encode(None, &resolve, package_id)
+        .context("failed to encode WIT package")?;
+
+    // Ensure that the encoded package can be read back as a WIT package
+    match wit_component::decode(&wit_package_bytes)
+        .context("failed to decode encoded WIT package")?
+    {
+        DecodedWasm::WitPackage(..) => {}
+        DecodedWasm::Component(..) => bail!("encoded WIT package was decoded as a component"),
+    }
+    info!(
+        "successfully encoded WIT package [{}]",
+        resolve.packages[package_id].name
+    );
+
+    Ok(wit_package_bytes)
+}
+
 /// Embed required component metadata to a given WebAssembly binary
 fn embed_wasm_component_metadata(
     project_path: impl AsRef<Path>,
@@ -650,27 +986,6 @@ fn parse_custom_command(command: &str) -> Result<(&str, Vec<&str>)> {
     Ok((command, args))
 }
 
-/// Placeholder for future functionality for building providers
-#[allow(unused)]
-fn build_provider(
-    _provider_config: ProviderConfig,
-    _language_config: LanguageConfig,
-    _common_config: CommonConfig,
-    _no_sign: bool,
-) -> Result<()> {
-    Ok(())
-}
-
-/// Placeholder for future functionality for building interfaces
-#[allow(unused)]
-fn build_interface(
-    _interface_config: InterfaceConfig,
-    _language_config: LanguageConfig,
-    _common_config: CommonConfig,
-) -> Result<()> {
-    Ok(())
-}
-
 #[cfg(test)]
 mod tests {
     use std::collections::HashSet;
@@ -690,7 +1005,7 @@ mod tests {
         parser::{ActorConfig, CommonConfig, WasmTarget},
     };
 
-    use super::{generate_tinygo_bindgen, sign_actor_wasm, SignConfig};
+    use super::{encode_wit_package, generate_tinygo_bindgen, sign_actor_wasm, SignConfig};
 
     const MODULE_WAT: &str = "(module)";
     const COMPONENT_BASIC_WIT: &str = r#"
@@ -924,6 +1239,104 @@ world downstream {
         Ok(())
     }
 
+    /// Ensure that WIT packages which depend on packages in the `deps` directory
+    /// are encoded as WIT package binaries that can be decoded again
+    #[test]
+    fn encode_wit_package_with_deps() -> Result<()> {
+        let project_dir = tempfile::tempdir()?;
+
+        // Set up the WIT directory, including a dependency
+        let wit_dir = project_dir.path().join("wit");
+        let dep_dir = wit_dir.join("deps").join("upstream");
+        std::fs::create_dir_all(&dep_dir).context("failed to create WIT deps dir")?;
+        std::fs::write(
+            dep_dir.join("types.wit"),
+            r#"
+package washlib:upstream;
+
+interface types {
+    record greeting {
+        message: string,
+    }
+}
+"#,
+        )
+        .context("failed to write dependency WIT file")?;
+        std::fs::write(
+            wit_dir.join("interface.wit"),
+            r#"
+package washlib:greeting@0.1.0;
+
+interface greeter {
+    use washlib:upstream/types.{greeting};
+    greet: func(name: string) -> greeting;
+}
+"#,
+        )
+        .context("failed to write test WIT file")?;
+
+        let wit_package_bytes =
+            encode_wit_package(&wit_dir).context("failed to encode WIT package")?;
+
+        let wit_component::DecodedWasm::WitPackage(resolve, package_id) =
+            wit_component::decode(&wit_package_bytes).context("failed to decode WIT package")?
+        else {
+            panic!("encoded WIT package should decode as a WIT package");
+        };
+        assert_eq!(
+            resolve.packages[package_id].name.to_string(),
+            "washlib:greeting@0.1.0"
+        );
+        assert!(resolve.packages[package_id]
+            .interfaces
+            .contains_key("greeter"));
+
+        Ok(())
+    }
+
+    /// Ensure that invalid WIT packages are rejected
+    #[test]
+    fn encode_wit_package_invalid() -> Result<()> {
+        let project_dir = tempfile::tempdir()?;
+        let wit_dir = project_dir.path().join("wit");
+        std::fs::create_dir(&wit_dir).context("failed to create WIT dir")?;
+        std::fs::write(
+            wit_dir.join("interface.wit"),
+            r#"
+package washlib:greeting;
+
+interface greeter {
+    greet: func(name: unknown-type) -> string;
+}
+"#,
+        )
+        .context("failed to write test WIT file")?;
+
+        assert!(encode_wit_package(&wit_dir).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn can_convert_rust_target_to_par_target() {
+        for (rust_target, par_target) in [
+            ("x86_64-unknown-linux-gnu", "x86_64-linux"),
+            ("aarch64-unknown-linux-musl", "aarch64-linux"),
+            ("aarch64-apple-darwin", "aarch64-macos"),
+            ("x86_64-pc-windows-gnu", "x86_64-windows"),
+            ("x86_64-unknown-freebsd", "x86_64-freebsd"),
+        ] {
+            assert_eq!(
+                super::rust_target_to_par_target(rust_target)
+                    .expect("should be able to convert rust target"),
+                par_target
+            );
+        }
+
+        assert!(super::rust_target_to_par_target("wasm32-unknown-unknown").is_err());
+        assert!(super::rust_target_to_par_target("").is_err());
+    }
+
     #[test]
     fn can_parse_custom_command() {
         let cargo_component_build = "cargo component build --release --target wasm32-wasi";
@@ -1,21 +1,27 @@
-use std::{path::PathBuf, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    time::Duration,
+};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use async_nats::jetstream::{
     consumer::{pull::Config as ConsumerConfig, AckPolicy, DeliverPolicy},
     stream::Config,
 };
 use clap::{Parser, Subcommand};
 use futures::TryStreamExt;
+use nkeys::KeyPair;
 use tokio::io::{stdin, stdout, AsyncReadExt, AsyncWriteExt};
 use tokio::time::Instant;
-use wasmcloud_core::Invocation;
+use wascap::jwt;
+use wasmcloud_core::{Invocation, InvocationResponse};
 
 use super::{CliConnectionOpts, CommandOutput};
 use crate::config::WashConnectionOptions;
 use crate::{
-    capture::{ReadCapture, WriteCapture},
-    id::{ModuleId, ServiceId},
+    capture::{ReadCapture, SerializableMessage, WriteCapture},
+    id::{ClusterSeed, ModuleId, ServiceId},
     spier::{ObservedInvocation, ObservedMessage},
 };
 
@@ -36,6 +42,18 @@ pub struct CaptureCommand {
     #[clap(name = "window_size", long = "window-size", default_value = "60")]
     pub window_size_minutes: u64,
 
+    /// When enabling capture, also record replies to invocations so that `wash capture replay
+    /// --publish` can compare them against the responses of a replay. Replies are sent to NATS
+    /// inboxes, so this records all `_INBOX.>` traffic on the NATS server, of which only replies to
+    /// captured invocations are kept in a capture. It cannot be enabled for more than one lattice
+    /// on the same server at a time
+    #[clap(
+        name = "capture_replies",
+        long = "capture-replies",
+        requires = "enable"
+    )]
+    pub capture_replies: bool,
+
     #[clap(flatten)]
     pub opts: CliConnectionOpts,
 
@@ -67,16 +85,83 @@ pub struct CaptureReplayCommand {
     #[clap(name = "interactive", long = "interactive")]
     pub interactive: bool,
 
+    /// Re-publish the captured invocations onto the RPC subjects of the target lattice rather than
+    /// only printing them. If the capture contains the original replies (see `--capture-replies`),
+    /// the replayed responses are compared against them
+    #[clap(name = "publish", long = "publish")]
+    pub publish: bool,
+
+    /// Cluster seed used to re-sign replayed invocations so that they pass the antiforgery checks
+    /// of the target hosts. Defaults to the cluster seed of the current context. If no seed is
+    /// available, invocations are replayed with their original signatures
+    #[clap(
+        short = 'c',
+        long = "cluster-seed",
+        env = "WASMCLOUD_CLUSTER_SEED",
+        value_parser,
+        requires = "publish"
+    )]
+    pub cluster_seed: Option<ClusterSeed>,
+
+    /// Connection options for the lattice that invocations are replayed against
+    #[clap(flatten)]
+    pub opts: CliConnectionOpts,
+
     /// The file path to the capture file to read from
     #[clap(name = "capturefile")]
     pub capture_file_path: PathBuf,
 }
 
+/// Connection information for re-publishing captured invocations
+struct ReplayTarget {
+    nats: async_nats::Client,
+    lattice: String,
+    cluster_key: Option<KeyPair>,
+    timeout: Duration,
+}
+
+/// The result of replaying a single invocation
+enum ReplayOutcome<'a> {
+    /// The replayed response is identical to the captured reply
+    Matched,
+    /// The replayed response differs from the captured reply
+    Differed {
+        captured: &'a InvocationResponse,
+        replayed: InvocationResponse,
+    },
+    /// No reply was captured for the invocation, so nothing could be compared
+    NoCapturedReply,
+    /// The invocation could not be replayed
+    Failed(anyhow::Error),
+}
+
 pub async fn handle_replay_command(cmd: CaptureReplayCommand) -> Result<CommandOutput> {
     let capture = ReadCapture::load(cmd.capture_file_path).await?;
 
+    let target = if cmd.publish {
+        let wco: WashConnectionOptions = cmd.opts.try_into()?;
+        let cluster_key = cmd
+            .cluster_seed
+            .or_else(|| wco.ctx.cluster_seed.clone())
+            .map(|seed| KeyPair::from_seed(&seed))
+            .transpose()
+            .context("invalid cluster seed")?;
+        let lattice = wco.get_lattice();
+        let timeout = Duration::from_millis(wco.timeout_ms);
+        Some(ReplayTarget {
+            nats: wco.into_nats_client().await?,
+            lattice,
+            cluster_key,
+            timeout,
+        })
+    } else {
+        None
+    };
+
+    let replies = captured_replies(&capture.messages);
+
     let filtered = capture.messages.into_iter().filter_map(|msg| {
-        let mut inv: Invocation = rmp_serde::from_slice(&msg.payload).ok()?;
+        let inv: Invocation = rmp_serde::from_slice(&msg.payload).ok()?;
 
         if let Some(actor_id) = &cmd.actor_id {
             if (inv.origin.is_actor() && inv.origin.public_key != actor_id.as_ref())
@@ -94,25 +179,21 @@ pub async fn handle_replay_command(cmd: CaptureReplayCommand) -> Result<CommandO
             }
         }
 
-        let body = inv.msg;
-        inv.msg = Vec::new();
-        let from = inv.origin.public_key.clone();
-        let to = inv.target.public_key.clone();
-
-        Some((
-            ObservedInvocation {
-                invocation: inv,
-                timestamp: chrono::Local::now(),
-                from,
-                to,
-                message: ObservedMessage::parse(body),
-            },
-            msg.published,
-        ))
+        Some((inv, msg.published))
     });
 
     let mut out = stdout();
-    for (msg, published) in filtered {
+    let (mut replayed, mut matched, mut differed, mut no_reply, mut failed) = (0, 0, 0, 0, 0);
+    for (inv, published) in filtered {
+        let mut observed = inv.clone();
+        let body = std::mem::take(&mut observed.msg);
+        let msg = ObservedInvocation {
+            from: observed.origin.public_key.clone(),
+            to: observed.target.public_key.clone(),
+            invocation: observed,
+            timestamp: chrono::Local::now(),
+            message: ObservedMessage::parse(body),
+        };
         println!(
             r#"
 [{}]
@@ -127,13 +208,159 @@ Message: {}"#,
             msg.invocation.operation,
             msg.message
         );
+
+        if let Some(target) = &target {
+            let captured = replies.get(&inv.id);
+            replayed += 1;
+            match replay_invocation(target, inv, captured).await {
+                ReplayOutcome::Matched => {
+                    matched += 1;
+                    println!("Replay: response matches the captured reply");
+                }
+                ReplayOutcome::Differed { captured, replayed } => {
+                    differed += 1;
+                    println!(
+                        "Replay: response differs from the captured reply\nCaptured: {}\nReplayed: {}",
+                        display_response(captured),
+                        display_response(&replayed)
+                    );
+                }
+                ReplayOutcome::NoCapturedReply => {
+                    no_reply += 1;
+                    println!("Replay: invocation sent, no captured reply to compare against");
+                }
+                ReplayOutcome::Failed(e) => {
+                    failed += 1;
+                    println!("Replay: failed to replay invocation: {e:#}");
+                }
+            }
+        }
+
         if cmd.interactive {
             out.write_all(b"Press Enter to continue...").await.unwrap();
             out.flush().await.unwrap();
             stdin().read_exact(&mut [0]).await.unwrap();
         }
     }
-    Ok(CommandOutput::default())
+
+    if target.is_none() {
+        return Ok(CommandOutput::default());
+    }
+
+    Ok(CommandOutput::new(
+        format!(
+            "Replayed {replayed} invocations: {matched} matched, {differed} differed, {no_reply} without a captured reply, {failed} failed"
+        ),
+        [
+            ("replayed".to_string(), replayed.into()),
+            ("matched".to_string(), matched.into()),
+            ("differed".to_string(), differed.into()),
+            ("no_captured_reply".to_string(), no_reply.into()),
+            ("failed".to_string(), failed.into()),
+        ]
+        .into(),
+    ))
+}
+
+/// Publishes a captured invocation to the target lattice and compares the response with the
+/// captured reply, if there is one
+async fn replay_invocation<'a>(
+    target: &ReplayTarget,
+    mut inv: Invocation,
+    captured: Option<&'a InvocationResponse>,
+) -> ReplayOutcome<'a> {
+    // Chunked invocation bodies live in the object store of the original lattice
+    if inv.msg.is_empty() && inv.content_length > 0 {
+        return ReplayOutcome::Failed(anyhow::anyhow!(
+            "invocation body was chunked and is not part of the capture"
+        ));
+    }
+    if let Some(cluster_key) = &target.cluster_key {
+        if let Err(e) = resign_invocation(&mut inv, cluster_key) {
+            return ReplayOutcome::Failed(e);
+        }
+    }
+    let subject = if inv.target.is_actor() {
+        format!("wasmbus.rpc.{}.{}", target.lattice, inv.target.public_key)
+    } else {
+        format!(
+            "wasmbus.rpc.{}.{}.{}",
+            target.lattice, inv.target.public_key, inv.target.link_name
+        )
+    };
+
+    let response = async {
+        let payload = rmp_serde::to_vec_named(&inv).context("failed to encode invocation")?;
+        let request = async_nats::Request::new()
+            .payload(payload.into())
+            .timeout(Some(target.timeout));
+        let res = target
+            .nats
+            .send_request(subject, request)
+            .await
+            .context("invocation request failed")?;
+        rmp_serde::from_slice::<InvocationResponse>(&res.payload)
+            .context("failed to decode invocation response")
+    }
+    .await;
+
+    match response {
+        Ok(response) => compare_response(response, captured),
+        Err(e) => ReplayOutcome::Failed(e),
+    }
+}
+
+/// Returns the invocation replies in a capture, keyed by the ID of the invocation they answer.
+///
+/// Replies are only present if the capture was taken with `--capture-replies`. The reply subject
+/// of a captured invocation is the ACK subject of the capture stream rather than the inbox of the
+/// original request, so replies are matched to invocations by ID
+fn captured_replies(messages: &[SerializableMessage]) -> HashMap<String, InvocationResponse> {
+    messages
+        .iter()
+        .filter(|msg| !msg.subject.starts_with("wasmbus.rpc."))
+        .filter_map(|msg| rmp_serde::from_slice::<InvocationResponse>(&msg.payload).ok())
+        .filter(|response| !response.invocation_id.is_empty())
+        .map(|response| (response.invocation_id.clone(), response))
+        .collect()
+}
+
+/// Compares the response of a replayed invocation with the captured reply, if there is one
+fn compare_response(
+    replayed: InvocationResponse,
+    captured: Option<&InvocationResponse>,
+) -> ReplayOutcome<'_> {
+    match captured {
+        None => ReplayOutcome::NoCapturedReply,
+        Some(captured) if replayed.msg == captured.msg && replayed.error == captured.error => {
+            ReplayOutcome::Matched
+        }
+        Some(captured) => ReplayOutcome::Differed { captured, replayed },
+    }
+}
+
+/// Replaces the claims of an invocation with claims signed by the given cluster key, so that the
+/// invocation passes antiforgery checks on hosts of a different cluster
+fn resign_invocation(inv: &mut Invocation, cluster_key: &KeyPair) -> Result<()> {
+    let claims = jwt::Claims::<jwt::Invocation>::new(
+        cluster_key.public_key(),
+        inv.id.clone(),
+        &inv.target_url(),
+        &inv.origin_url(),
+        &inv.hash(),
+    );
+    inv.encoded_claims = claims
+        .encode(cluster_key)
+        .map_err(|e| anyhow::anyhow!("{e}"))
+        .context("failed to sign invocation claims")?;
+    Ok(())
+}
+
+fn display_response(response: &InvocationResponse) -> String {
+    match &response.error {
+        Some(error) => format!("error: {error}"),
+        None => ObservedMessage::parse(response.msg.clone()).to_string(),
+    }
 }
 
 /// Handles the spy command, printing all output to stdout until the command is interrupted
@@ -153,6 +380,7 @@ pub async fn handle_command(cmd: CaptureCommand) -> Result<CommandOutput> {
             js_context,
             wco.lattice.as_deref().unwrap_or("default"),
             window_size,
+            cmd.capture_replies,
         )
         .await;
     } else if cmd.disable {
@@ -171,6 +399,7 @@ pub async fn enable(
     ctx: async_nats::jetstream::Context,
     lattice_id: &str,
     window_size: Duration,
+    capture_replies: bool,
 ) -> Result<CommandOutput> {
     // Until we get concrete errors, we should check for the stream and if it exists return a nice message that we're already enabled
     if ctx.get_stream(CAPTURE_STREAM_NAME).await.is_ok() {
@@ -179,13 +408,17 @@ pub async fn enable(
             format!("Capture is already enabled for lattice {lattice_id}"),
         ));
     }
+    let mut subjects = vec![format!("wasmbus.rpc.{}.>", lattice_id)];
+    if capture_replies {
+        subjects.push("_INBOX.>".to_string());
+    }
     ctx.create_stream(Config {
         name: stream_name(lattice_id),
         storage: async_nats::jetstream::stream::StorageType::File,
         max_age: window_size,
         // This needs to be set or it breaks invocations
         no_ack: true,
-        subjects: vec![format!("wasmbus.rpc.{}.>", lattice_id)],
+        subjects,
         ..Default::default()
     })
     .await
@@ -252,6 +485,7 @@ pub async fn capture(
         lattice_id
     );
     let mut capture = WriteCapture::start(inventory, &filename).await?;
+    let mut replies = ReplyFilter::default();
 
     loop {
         tokio::select! {
@@ -279,7 +513,9 @@ pub async fn capture(
                     }
                 }
                 if let Ok(m) = msg.try_into() {
-                    capture.add_message(m).await?;
+                    if replies.keep(&m) {
+                        capture.add_message(m).await?;
+                    }
                 }
             }
         }
@@ -322,6 +558,217 @@ async fn get_all_inventory(
     futures::future::join_all(futs).await.into_iter().collect()
 }
 
+/// Tracks the IDs of the invocations in a capture, so that only replies to them are kept from the
+/// `_INBOX.>` traffic recorded with `--capture-replies`. Replies are published after the
+/// invocation they answer, so the invocation is always seen first
+#[derive(Default)]
+struct ReplyFilter {
+    invocation_ids: HashSet<String>,
+}
+
+impl ReplyFilter {
+    /// Returns whether `msg` should be kept in the capture, which is the case for all invocations
+    /// and for replies to invocations seen before
+    fn keep(&mut self, msg: &SerializableMessage) -> bool {
+        if msg.subject.starts_with("wasmbus.rpc.") {
+            if let Ok(inv) = rmp_serde::from_slice::<Invocation>(&msg.payload) {
+                self.invocation_ids.insert(inv.id);
+            }
+            return true;
+        }
+        rmp_serde::from_slice::<InvocationResponse>(&msg.payload)
+            .is_ok_and(|response| self.invocation_ids.contains(&response.invocation_id))
+    }
+}
+
 fn stream_name(lattice_id: &str) -> String {
     format!("{}-{lattice_id}", CAPTURE_STREAM_NAME)
 }
+
+#[cfg(test)]
+mod test {
+    use nkeys::KeyPair;
+    use wasmcloud_core::{Invocation, InvocationResponse, WasmCloudEntity};
+
+    use super::{
+        captured_replies, compare_response, resign_invocation, ReplayOutcome, ReplyFilter,
+    };
+    use crate::capture::{ReadCapture, SerializableMessage, WriteCapture};
+
+    fn message(subject: &str, reply: Option<&str>, payload: Vec<u8>) -> SerializableMessage {
+        SerializableMessage {
+            subject: subject.to_string(),
+            reply: reply.map(String::from),
+            length: payload.len(),
+            payload: payload.into(),
+            description: None,
+            published: time::OffsetDateTime::now_utc(),
+        }
+    }
+
+    fn invocation() -> Invocation {
+        let actor = KeyPair::new_module();
+        let provider = KeyPair::new_service();
+        Invocation::new(
+            &KeyPair::new_cluster(),
+            &KeyPair::new_server(),
+            WasmCloudEntity {
+                public_key: actor.public_key(),
+                ..Default::default()
+            },
+            WasmCloudEntity {
+                public_key: provider.public_key(),
+                link_name: "default".to_string(),
+                contract_id: "wasmcloud:keyvalue".to_string(),
+            },
+            "wasmcloud:keyvalue/KeyValue.Get",
+            b"hello".to_vec(),
+            Default::default(),
+        )
+        .expect("should be able to create invocation")
+    }
+
+    fn response(invocation_id: &str, msg: &[u8]) -> InvocationResponse {
+        InvocationResponse {
+            msg: msg.to_vec(),
+            invocation_id: invocation_id.to_string(),
+            content_length: msg.len() as u64,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn replay_compares_captured_replies() {
+        let inv = invocation();
+        let unanswered = invocation();
+
+        let tempdir = tempfile::tempdir().unwrap();
+        let tarball = tempdir.path().join("capture.washcapture");
+        let mut capture = WriteCapture::start(Vec::new(), &tarball)
+            .await
+            .expect("Should be able to start a capture");
+        // As captured from JetStream: the reply subject of the invocation is the ACK subject of
+        // the capture stream, and the reply is published to the inbox of the original request
+        for msg in [
+            message(
+                &format!("wasmbus.rpc.default.{}.default", inv.target.public_key),
+                Some("$JS.ACK.wash-capture-default.consumer.1.1.1.0.0"),
+                rmp_serde::to_vec_named(&inv).unwrap(),
+            ),
+            message(
+                "_INBOX.abc123.xyz",
+                None,
+                rmp_serde::to_vec_named(&response(&inv.id, b"world")).unwrap(),
+            ),
+            message(
+                &format!(
+                    "wasmbus.rpc.default.{}.default",
+                    unanswered.target.public_key
+                ),
+                Some("$JS.ACK.wash-capture-default.consumer.1.2.2.0.0"),
+                rmp_serde::to_vec_named(&unanswered).unwrap(),
+            ),
+        ] {
+            capture
+                .add_message(msg)
+                .await
+                .expect("Should be able to add a message");
+        }
+        capture
+            .finish()
+            .await
+            .expect("Should be able to finish a capture");
+
+        let capture = ReadCapture::load(&tarball)
+            .await
+            .expect("Should be able to load a capture");
+        let replies = captured_replies(&capture.messages);
+        assert_eq!(replies.len(), 1, "Should only find the captured reply");
+
+        assert!(matches!(
+            compare_response(response(&inv.id, b"world"), replies.get(&inv.id)),
+            ReplayOutcome::Matched
+        ));
+        match compare_response(response(&inv.id, b"mars"), replies.get(&inv.id)) {
+            ReplayOutcome::Differed { captured, replayed } => {
+                assert_eq!(captured.msg, b"world");
+                assert_eq!(replayed.msg, b"mars");
+            }
+            _ => panic!("Should report a differing response"),
+        }
+        assert!(matches!(
+            compare_response(
+                response(&unanswered.id, b"world"),
+                replies.get(&unanswered.id)
+            ),
+            ReplayOutcome::NoCapturedReply
+        ));
+    }
+
+    #[test]
+    fn reply_filter_keeps_replies_to_captured_invocations() {
+        let inv = invocation();
+        let mut replies = ReplyFilter::default();
+
+        // Replies published before their invocation was captured are unrelated inbox traffic
+        assert!(!replies.keep(&message(
+            "_INBOX.abc123.xyz",
+            None,
+            rmp_serde::to_vec_named(&response(&inv.id, b"world")).unwrap(),
+        )));
+        assert!(replies.keep(&message(
+            &format!("wasmbus.rpc.default.{}.default", inv.target.public_key),
+            Some("_INBOX.abc123.xyz"),
+            rmp_serde::to_vec_named(&inv).unwrap(),
+        )));
+        assert!(replies.keep(&message(
+            "_INBOX.abc123.xyz",
+            None,
+            rmp_serde::to_vec_named(&response(&inv.id, b"world")).unwrap(),
+        )));
+        assert!(!replies.keep(&message(
+            "_INBOX.def456.xyz",
+            None,
+            rmp_serde::to_vec_named(&response("unknown", b"world")).unwrap(),
+        )));
+        assert!(!replies.keep(&message(
+            "_INBOX.def456.xyz",
+            None,
+            br#"{"accepted":true,"error":""}"#.to_vec(),
+        )));
+    }
+
+    #[test]
+    fn resigned_invocation_passes_antiforgery() {
+        let original_cluster = KeyPair::new_cluster();
+        let replay_cluster = KeyPair::new_cluster();
+        let host = KeyPair::new_server();
+        let actor = KeyPair::new_module();
+        let provider = KeyPair::new_service();
+
+        let mut inv = Invocation::new(
+            &original_cluster,
+            &host,
+            WasmCloudEntity {
+                public_key: actor.public_key(),
+                ..Default::default()
+            },
+            WasmCloudEntity {
+                public_key: provider.public_key(),
+                link_name: "default".to_string(),
+                contract_id: "wasmcloud:keyvalue".to_string(),
+            },
+            "wasmcloud:keyvalue/KeyValue.Get",
+            b"hello".to_vec(),
+            Default::default(),
+        )
+        .expect("should be able to create invocation");
+
+        let valid_issuers = vec![replay_cluster.public_key()];
+        assert!(inv.validate_antiforgery(&valid_issuers).is_err());
+
+        resign_invocation(&mut inv, &replay_cluster).expect("should be able to re-sign");
+        inv.validate_antiforgery(&valid_issuers)
+            .expect("re-signed invocation should pass antiforgery checks");
+    }
+}
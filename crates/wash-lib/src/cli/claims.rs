@@ -1568,7 +1568,9 @@ mod test {
             project_config.project_type,
             TypeConfig::Provider(ProviderConfig {
                 capability_id: "wasmcloud:httpserver".into(),
-                vendor: "wayne-industries".into()
+                vendor: "wayne-industries".into(),
+                key_directory: PathBuf::from("./keys"),
+                ..ProviderConfig::default()
             })
         );
 
@@ -1,50 +1,234 @@
-use anyhow::Result;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, Context, Result};
 use console::style;
 use wasmcloud_control_interface::Client;
 
 use crate::{
     actor::update_actor,
     build::{build_project, SignConfig},
+    common::boxed_err_to_anyhow,
     generate::emoji,
-    id::{ModuleId, ServerId},
+    id::{ModuleId, ServerId, ServiceId},
     parser::{ProjectConfig, TypeConfig},
+    provider::{start_provider, stop_provider, StartProviderArgs},
 };
 
+/// The link name that `wash dev` uses when running capability providers
+pub const DEV_PROVIDER_LINK_NAME: &str = "default";
+
+/// How long to wait for a provider to shut down before restarting it
+const PROVIDER_STOP_TIMEOUT_MS: u64 = 10_000;
+
+/// Returns a provider archive revision for a `wash dev` build, which is greater than the revision
+/// of any earlier build.
+///
+/// Hosts cache provider binaries by subject and revision, so a provider archive rebuilt with the
+/// revision of the project would be started from the binary cached for the previous build.
+pub fn dev_revision() -> i32 {
+    static LAST_REVISION: AtomicI32 = AtomicI32::new(0);
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| now.as_secs());
+    let now = i32::try_from(now).unwrap_or(i32::MAX);
+    let next = |last: i32| now.max(last.saturating_add(1));
+    let last = LAST_REVISION
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| Some(next(last)))
+        .unwrap_or_else(|last| last);
+    next(last)
+}
+
+/// Returns the configuration to build `project_cfg` with in the dev loop, which signs provider
+/// archives with a [`dev_revision`]
+pub fn dev_project_config(project_cfg: &ProjectConfig) -> ProjectConfig {
+    let mut project_cfg = project_cfg.clone();
+    if let TypeConfig::Provider(_) = project_cfg.project_type {
+        project_cfg.common.revision = dev_revision();
+    }
+    project_cfg
+}
+
+/// The artifact that is running in the lattice on behalf of the dev loop
+#[derive(Debug, Clone)]
+pub enum DevArtifact {
+    /// An actor, started from a `file://` reference
+    Actor {
+        actor_id: ModuleId,
+        actor_ref: String,
+    },
+    /// A capability provider, started from a `file://` reference to a provider archive
+    Provider {
+        provider_id: ServiceId,
+        provider_ref: String,
+        link_name: String,
+        contract_id: String,
+    },
+}
+
 /// Perform a single execution of the dev loop for an artifact
 pub async fn run_dev_loop(
     project_cfg: &ProjectConfig,
-    actor_id: ModuleId,
-    actor_ref: &str,
+    artifact: &mut DevArtifact,
     host_id: ServerId,
     ctl_client: &Client,
     sign_cfg: Option<SignConfig>,
 ) -> Result<()> {
-    let built_artifact_path = build_project(project_cfg, sign_cfg)?.canonicalize()?;
+    let built_artifact_path = build_project(&dev_project_config(project_cfg), sign_cfg)
+        .await?
+        .canonicalize()?;
 
     // Restart the artifact so that changes can be observed
-    match project_cfg.project_type {
-        TypeConfig::Interface(_) | TypeConfig::Provider(_) => {
+    match (&project_cfg.project_type, artifact) {
+        (
+            TypeConfig::Actor(_),
+            DevArtifact::Actor {
+                actor_id,
+                actor_ref,
+            },
+        ) => {
             eprintln!(
                 "{} {}",
-                emoji::WARN,
-                style("`wash build` interfaces and providers are not yet supported, skipping...")
-                    .bold(),
+                emoji::RECYCLE,
+                style(format!(
+                    "restarting actor @ [{}]...",
+                    built_artifact_path.display()
+                ))
+                .bold(),
             );
+
+            update_actor(ctl_client, &host_id, actor_id, actor_ref).await?;
         }
-        TypeConfig::Actor(_) => {
+        (
+            TypeConfig::Provider(_),
+            DevArtifact::Provider {
+                provider_id,
+                provider_ref,
+                link_name,
+                contract_id,
+            },
+        ) => {
             eprintln!(
                 "{} {}",
                 emoji::RECYCLE,
                 style(format!(
-                    "restarting actor @ [{}]...",
+                    "restarting provider @ [{}]...",
                     built_artifact_path.display()
                 ))
                 .bold(),
             );
 
-            update_actor(ctl_client, &host_id, &actor_id, actor_ref).await?;
+            *provider_id = reload_provider(
+                ctl_client,
+                &host_id,
+                provider_id,
+                provider_ref,
+                link_name,
+                contract_id,
+            )
+            .await?;
+        }
+        (TypeConfig::Interface(_), _) => {
+            eprintln!(
+                "{} {}",
+                emoji::WARN,
+                style("`wash dev` does not support interfaces, skipping...").bold(),
+            );
+        }
+        (_, artifact) => {
+            bail!("project type does not match the running dev artifact {artifact:?}");
         }
     }
 
     Ok(())
 }
+
+/// Restart a running capability provider from a (freshly built) provider archive reference.
+///
+/// The link definitions for the provider are saved before it is stopped and re-advertised once
+/// the new instance has started, so that linked actors keep working across reloads. Returns the ID
+/// of the newly started provider, which only differs from `provider_id` if the archive was signed
+/// with a different subject key. Rebuilt archives must be signed with a new revision, such as a
+/// [`dev_revision`], for the host to start the rebuilt binary.
+pub async fn reload_provider(
+    ctl_client: &Client,
+    host_id: &ServerId,
+    provider_id: &ServiceId,
+    provider_ref: &str,
+    link_name: &str,
+    contract_id: &str,
+) -> Result<ServiceId> {
+    let links = ctl_client
+        .query_links()
+        .await
+        .map_err(boxed_err_to_anyhow)
+        .context("failed to query link definitions")?
+        .into_iter()
+        .filter(|ld| ld.provider_id == provider_id.as_ref() && ld.link_name == link_name)
+        .collect::<Vec<_>>();
+
+    stop_provider(
+        ctl_client,
+        host_id,
+        provider_id,
+        link_name,
+        contract_id,
+        PROVIDER_STOP_TIMEOUT_MS,
+        false,
+    )
+    .await
+    .with_context(|| format!("failed to stop provider [{provider_id}]"))?;
+
+    let started = start_provider(StartProviderArgs {
+        ctl_client,
+        host_id,
+        provider_ref,
+        link_name,
+        annotations: None,
+        config_json: None,
+        skip_wait: false,
+        timeout_ms: None,
+    })
+    .await?
+    .context("provider start event was not received")?;
+    let new_provider_id: ServiceId = started
+        .provider_id
+        .parse()
+        .context("started provider has an invalid ID")?;
+
+    for ld in links {
+        let res = ctl_client
+            .advertise_link(
+                &ld.actor_id,
+                &new_provider_id,
+                &ld.contract_id,
+                &ld.link_name,
+                ld.values,
+            )
+            .await
+            .map_err(boxed_err_to_anyhow);
+        match res {
+            Ok(ack) if ack.accepted => {}
+            Ok(ack) => eprintln!(
+                "{} {}",
+                emoji::WARN,
+                style(format!(
+                    "failed to re-establish link to actor [{}]: {}",
+                    ld.actor_id, ack.error
+                ))
+                .bold(),
+            ),
+            Err(e) => eprintln!(
+                "{} {}",
+                emoji::WARN,
+                style(format!(
+                    "failed to re-establish link to actor [{}]: {e}",
+                    ld.actor_id
+                ))
+                .bold(),
+            ),
+        }
+    }
+
+    Ok(new_provider_id)
+}
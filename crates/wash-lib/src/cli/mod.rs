@@ -41,6 +41,7 @@ pub mod label;
 pub mod link;
 pub mod output;
 pub mod par;
+pub mod policy;
 pub mod registry;
 pub mod scale;
 pub mod spy;
@@ -160,6 +161,15 @@ pub struct CliConnectionOpts {
     #[clap(long = "ctl-credsfile", env = "WASH_CTL_CREDS", hide_env_values = true)]
     pub ctl_credsfile: Option<PathBuf>,
 
+    /// Seed file or literal of the key used to sign control interface requests, identifying the
+    /// caller to hosts for policy decisions. Requests are unsigned if not set
+    #[clap(
+        long = "ctl-caller-seed",
+        env = "WASH_CTL_CALLER_SEED",
+        hide_env_values = true
+    )]
+    pub ctl_caller_seed: Option<String>,
+
     /// JS domain for wasmcloud control interface. Defaults to None
     #[clap(
         long = "js-domain",
@@ -200,6 +210,7 @@ impl Default for CliConnectionOpts {
             ctl_jwt: None,
             ctl_seed: None,
             ctl_credsfile: None,
+            ctl_caller_seed: None,
             js_domain: None,
             lattice: Some(DEFAULT_LATTICE.to_string()),
             timeout_ms: DEFAULT_NATS_TIMEOUT_MS,
@@ -218,6 +229,7 @@ impl TryFrom<CliConnectionOpts> for WashConnectionOptions {
             ctl_jwt,
             ctl_seed,
             ctl_credsfile,
+            ctl_caller_seed,
             js_domain,
             lattice,
             timeout_ms,
@@ -242,6 +254,7 @@ impl TryFrom<CliConnectionOpts> for WashConnectionOptions {
             ctl_jwt,
             ctl_seed,
             ctl_credsfile,
+            ctl_caller_seed,
             js_domain,
             lattice,
             timeout_ms,
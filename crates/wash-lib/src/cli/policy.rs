@@ -0,0 +1,293 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+use anyhow::Result;
+use async_nats::jetstream::consumer::{pull::Config as ConsumerConfig, AckPolicy, DeliverPolicy};
+use clap::{Parser, Subcommand};
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use term_table::{row::Row, table_cell::TableCell, Table};
+
+use super::{CliConnectionOpts, CommandOutput};
+use crate::config::WashConnectionOptions;
+
+/// The stream name `wash policy` reads policy decisions from, unless another is given
+pub const DEFAULT_POLICY_AUDIT_STREAM: &str = "wasmcloud_policy_audit";
+
+/// How long to wait for another decision before assuming the end of the stream was reached
+const MAX_TIME_WITHOUT_MESSAGE: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum PolicyCommand {
+    /// Query recent policy decisions that denied a request
+    #[clap(name = "denials")]
+    Denials(PolicyDenialsCommand),
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct PolicyDenialsCommand {
+    #[clap(flatten)]
+    pub opts: CliConnectionOpts,
+
+    /// Name of the JetStream stream that hosts retain policy decisions in, as configured with the
+    /// host's `--policy-audit-stream`
+    #[clap(long = "stream", default_value = DEFAULT_POLICY_AUDIT_STREAM)]
+    pub stream: String,
+
+    /// Only show denials from the given number of minutes up to now
+    #[clap(long = "since-minutes", default_value = "60")]
+    pub since_minutes: u64,
+
+    /// Only show denials of requests targeting the actor or provider with this public key
+    #[clap(long = "target")]
+    pub target: Option<String>,
+
+    /// Maximum number of denials to show, most recent first
+    #[clap(long = "limit", default_value = "20")]
+    pub limit: usize,
+}
+
+/// A policy decision, as published by a host in the data of a `com.wasmcloud.policy.decision`
+/// cloud event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyDecision {
+    #[serde(rename = "requestId")]
+    pub request_id: String,
+    pub action: String,
+    #[serde(default)]
+    pub source: PolicyDecisionEntity,
+    #[serde(default)]
+    pub caller: Option<PolicyDecisionEntity>,
+    #[serde(default)]
+    pub target: PolicyDecisionEntity,
+    pub host: PolicyDecisionEntity,
+    pub cached: bool,
+    pub permitted: bool,
+    pub message: Option<String>,
+    #[serde(rename = "latencyUs")]
+    pub latency_us: u64,
+}
+
+/// The subset of a policy request source, target, caller or host that identifies it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PolicyDecisionEntity {
+    #[serde(rename = "publicKey", default)]
+    pub public_key: Option<String>,
+    #[serde(rename = "contractId", default)]
+    pub contract_id: Option<String>,
+    #[serde(rename = "linkName", default)]
+    pub link_name: Option<String>,
+    #[serde(default)]
+    pub key: Option<String>,
+}
+
+impl PolicyDecisionEntity {
+    fn display(&self) -> String {
+        let mut out = self.public_key.clone().unwrap_or_default();
+        if let (Some(contract_id), Some(link_name)) = (&self.contract_id, &self.link_name) {
+            out.push_str(&format!(" ({contract_id}/{link_name})"));
+        }
+        if let Some(key) = &self.key {
+            out.push_str(&format!(" [{key}]"));
+        }
+        out
+    }
+}
+
+/// A policy decision together with the time the host reached it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyDecisionEvent {
+    pub time: String,
+    #[serde(rename = "data")]
+    pub decision: PolicyDecision,
+}
+
+pub async fn handle_command(command: PolicyCommand) -> Result<CommandOutput> {
+    match command {
+        PolicyCommand::Denials(cmd) => handle_denials(cmd).await,
+    }
+}
+
+async fn handle_denials(cmd: PolicyDenialsCommand) -> Result<CommandOutput> {
+    let wco: WashConnectionOptions = cmd.opts.try_into()?;
+    let nats_client = wco.clone().into_nats_client().await?;
+    let js_context = if let Some(domain) = wco.js_domain {
+        async_nats::jetstream::with_domain(nats_client, domain)
+    } else {
+        async_nats::jetstream::new(nats_client)
+    };
+
+    let start_time = time::OffsetDateTime::now_utc() - Duration::from_secs(cmd.since_minutes * 60);
+    let denials = query_denials(
+        &js_context,
+        &cmd.stream,
+        start_time,
+        cmd.target.as_deref(),
+        cmd.limit,
+    )
+    .await?;
+
+    let mut map = HashMap::new();
+    map.insert("denials".to_string(), serde_json::to_value(&denials)?);
+    Ok(CommandOutput::new(denials_table(&denials), map))
+}
+
+/// Returns up to `limit` of the most recent denied policy decisions in `stream` since
+/// `start_time`, most recent first
+pub async fn query_denials(
+    ctx: &async_nats::jetstream::Context,
+    stream: &str,
+    start_time: time::OffsetDateTime,
+    target: Option<&str>,
+    limit: usize,
+) -> Result<Vec<PolicyDecisionEvent>> {
+    let stream = ctx.get_stream(stream).await.map_err(|e| {
+        anyhow::anyhow!(
+            "Unable to find policy audit stream `{stream}`. Are hosts started with `--policy-audit-stream`? Error: {e:?}"
+        )
+    })?;
+
+    let consumer = stream
+        .create_consumer(ConsumerConfig {
+            description: Some("Wash policy denials consumer".to_string()),
+            deliver_policy: DeliverPolicy::ByStartTime { start_time },
+            ack_policy: AckPolicy::None,
+            ..Default::default()
+        })
+        .await
+        .map_err(|e| anyhow::anyhow!("{e:?}"))?;
+    let mut messages = consumer
+        .messages()
+        .await
+        .map_err(|e| anyhow::anyhow!("{e:?}"))?;
+
+    let mut denials = VecDeque::with_capacity(limit);
+    loop {
+        let msg = match tokio::time::timeout(MAX_TIME_WITHOUT_MESSAGE, messages.try_next()).await {
+            Err(_) | Ok(Ok(None)) => break,
+            Ok(Ok(Some(msg))) => msg,
+            Ok(Err(e)) => return Err(anyhow::anyhow!("{e:?}")),
+        };
+        if let Some(event) = parse_denial(&msg.payload, target) {
+            if denials.len() == limit {
+                denials.pop_front();
+            }
+            denials.push_back(event);
+        }
+        if msg.info().map(|info| info.pending == 0).unwrap_or(false) {
+            break;
+        }
+    }
+
+    Ok(denials.into_iter().rev().collect())
+}
+
+/// Parses a policy decision cloud event, returning it only if the request was denied and it
+/// matches the target filter
+fn parse_denial(payload: &[u8], target: Option<&str>) -> Option<PolicyDecisionEvent> {
+    let event: PolicyDecisionEvent = serde_json::from_slice(payload).ok()?;
+    if event.decision.permitted {
+        return None;
+    }
+    match target {
+        Some(target) if event.decision.target.public_key.as_deref() != Some(target) => None,
+        _ => Some(event),
+    }
+}
+
+fn denials_table(denials: &[PolicyDecisionEvent]) -> String {
+    if denials.is_empty() {
+        return "No denied policy decisions found".to_string();
+    }
+
+    let mut table = Table::new();
+    super::configure_table_style(&mut table);
+    table.add_row(Row::new(vec![
+        TableCell::new("Time"),
+        TableCell::new("Action"),
+        TableCell::new("Target"),
+        TableCell::new("Requested By"),
+        TableCell::new("Message"),
+    ]));
+    for PolicyDecisionEvent { time, decision } in denials {
+        let requested_by = decision
+            .caller
+            .as_ref()
+            .unwrap_or(&decision.source)
+            .display();
+        table.add_row(Row::new(vec![
+            TableCell::new(time),
+            TableCell::new(&decision.action),
+            TableCell::new(decision.target.display()),
+            TableCell::new(requested_by),
+            TableCell::new(decision.message.as_deref().unwrap_or_default()),
+        ]));
+    }
+    table.render()
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_denial;
+
+    const ACTOR: &str = "MCUCZ7KMLQBRRWAREIBQKTJ64MMQ5YKEGTCRGPPV47N4R72W2SU3EYMU";
+
+    fn event(permitted: bool) -> Vec<u8> {
+        serde_json::to_vec(&serde_json::json!({
+            "specversion": "1.0",
+            "id": "01HKH4EFT5Z5D6E5S8N7V0A1CX",
+            "type": "com.wasmcloud.policy.decision",
+            "source": "NBZQ4UGNBGQ6YTZHBVNE6GBUFA2BZ2NXHO7F2EF4DXC2YEEWX3G4PHVH",
+            "time": "2024-01-08T12:00:00Z",
+            "datacontenttype": "application/json",
+            "data": {
+                "requestId": "01HKH4EFT5Z5D6E5S8N7V0A1CY",
+                "source": {
+                    "publicKey": null,
+                    "contractId": null,
+                    "linkName": null,
+                    "capabilities": [],
+                    "issuer": null,
+                    "issuedOn": null,
+                    "expiresAt": null,
+                    "expired": false
+                },
+                "target": {
+                    "publicKey": ACTOR,
+                    "issuer": null,
+                    "contractId": "wasmcloud:httpserver",
+                    "linkName": "default"
+                },
+                "host": {
+                    "publicKey": "NBZQ4UGNBGQ6YTZHBVNE6GBUFA2BZ2NXHO7F2EF4DXC2YEEWX3G4PHVH",
+                    "latticeId": "default",
+                    "labels": {},
+                    "clusterIssuers": []
+                },
+                "action": "put_link_definition",
+                "cached": false,
+                "permitted": permitted,
+                "message": "links to the http server are not allowed",
+                "latencyUs": 42
+            }
+        }))
+        .expect("failed to serialize event")
+    }
+
+    #[test]
+    fn parses_denials() {
+        let denial = parse_denial(&event(false), None).expect("denial should be parsed");
+        assert_eq!(denial.time, "2024-01-08T12:00:00Z");
+        assert_eq!(denial.decision.action, "put_link_definition");
+        assert_eq!(denial.decision.target.public_key.as_deref(), Some(ACTOR));
+        assert_eq!(
+            denial.decision.target.display(),
+            format!("{ACTOR} (wasmcloud:httpserver/default)")
+        );
+
+        assert!(parse_denial(&event(true), None).is_none());
+        assert!(parse_denial(&event(false), Some(ACTOR)).is_some());
+        assert!(parse_denial(&event(false), Some("MOTHERACTOR")).is_none());
+        assert!(parse_denial(b"not an event", None).is_none());
+    }
+}
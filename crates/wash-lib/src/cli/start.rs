@@ -3,7 +3,6 @@ use std::path::PathBuf;
 
 use anyhow::{bail, Context, Result};
 use clap::Parser;
-use tokio::time::Duration;
 
 use crate::{
     actor::{start_actor, ActorStartedInfo, StartActorArgs},
@@ -14,7 +13,8 @@ use crate::{
         DEFAULT_START_PROVIDER_TIMEOUT_MS,
     },
     context::default_timeout_ms,
-    wait::{wait_for_provider_start_event, FindEventOutcome, ProviderStartedInfo},
+    provider::{start_provider, StartProviderArgs},
+    wait::ProviderStartedInfo,
 };
 
 #[derive(Debug, Clone, Parser)]
@@ -248,37 +248,24 @@ pub async fn handle_start_provider(cmd: StartProviderCommand) -> Result<CommandO
         None
     };
 
-    let mut receiver = client
-        .events_receiver(vec![
-            "provider_started".to_string(),
-            "provider_start_failed".to_string(),
-        ])
-        .await
-        .map_err(boxed_err_to_anyhow)
-        .context("Failed to get lattice event channel")?;
-
-    let ack = client
-        .start_provider(
-            &host,
-            &provider_ref,
-            Some(cmd.link_name.clone()),
-            None,
-            config_json.clone(),
-        )
-        .await
-        .map_err(boxed_err_to_anyhow)
-        .with_context(|| {
-            format!(
-                "Failed to start provider {} on host {:?} with link name {} and configuration {:?}",
-                &provider_ref, &host, &cmd.link_name, &config_json
-            )
-        })?;
-
-    if !ack.accepted {
-        bail!("Start provider ack not accepted: {}", ack.error);
-    }
-
-    if cmd.skip_wait {
+    let Some(ProviderStartedInfo {
+        provider_id,
+        provider_ref,
+        host_id,
+        contract_id,
+        link_name,
+    }) = start_provider(StartProviderArgs {
+        ctl_client: &client,
+        host_id: &host,
+        provider_ref: &provider_ref,
+        link_name: &cmd.link_name,
+        annotations: None,
+        config_json,
+        skip_wait: cmd.skip_wait,
+        timeout_ms: Some(timeout_ms),
+    })
+    .await?
+    else {
         let text = format!("Start provider request received: {}", &provider_ref);
         return Ok(CommandOutput::new(
             text.clone(),
@@ -289,51 +276,21 @@ pub async fn handle_start_provider(cmd: StartProviderCommand) -> Result<CommandO
                 ("host_id".into(), host.to_string().into()),
             ]),
         ));
-    }
-
-    let event = wait_for_provider_start_event(
-        &mut receiver,
-        Duration::from_millis(timeout_ms),
-        host.to_string(),
-        provider_ref.clone(),
-    )
-    .await
-    .with_context(|| {
-        format!(
-            "Timed out waiting for start event for provider {} on host {}",
-            &provider_ref, &host
-        )
-    })?;
+    };
 
-    match event {
-        FindEventOutcome::Success(ProviderStartedInfo {
-            provider_id,
-            provider_ref,
-            host_id,
-            contract_id,
-            link_name,
-        }) => {
-            let text = format!(
-                "Provider [{}] (ref: [{}]) started on host [{}]",
-                &provider_id, &provider_ref, &host_id
-            );
-            Ok(CommandOutput::new(
-                text.clone(),
-                HashMap::from([
-                    ("result".into(), text.into()),
-                    ("provider_ref".into(), provider_ref.into()),
-                    ("provider_id".into(), provider_id.into()),
-                    ("link_name".into(), link_name.into()),
-                    ("contract_id".into(), contract_id.into()),
-                    ("host_id".into(), host_id.into()),
-                ]),
-            ))
-        }
-        FindEventOutcome::Failure(err) => Err(err).with_context(|| {
-            format!(
-                "Failed starting provider {} on host {}",
-                &provider_ref, &host
-            )
-        }),
-    }
+    let text = format!(
+        "Provider [{}] (ref: [{}]) started on host [{}]",
+        &provider_id, &provider_ref, &host_id
+    );
+    Ok(CommandOutput::new(
+        text.clone(),
+        HashMap::from([
+            ("result".into(), text.into()),
+            ("provider_ref".into(), provider_ref.into()),
+            ("provider_id".into(), provider_id.into()),
+            ("link_name".into(), link_name.into()),
+            ("contract_id".into(), contract_id.into()),
+            ("host_id".into(), host_id.into()),
+        ]),
+    ))
 }
@@ -1,7 +1,6 @@
 use anyhow::{bail, Result};
 use clap::Parser;
 use std::collections::HashMap;
-use tokio::time::Duration;
 use wasmcloud_control_interface::HostInventory;
 
 use crate::{
@@ -14,7 +13,8 @@ use crate::{
     config::WashConnectionOptions,
     context::default_timeout_ms,
     id::{validate_contract_id, ServerId},
-    wait::{wait_for_provider_stop_event, ActorStoppedInfo, FindEventOutcome, ProviderStoppedInfo},
+    provider,
+    wait::{ActorStoppedInfo, ProviderStoppedInfo},
 };
 
 #[derive(Debug, Clone, Parser)]
@@ -118,14 +118,6 @@ pub async fn stop_provider(cmd: StopProviderCommand) -> Result<CommandOutput> {
     let wco: WashConnectionOptions = cmd.opts.try_into()?;
     let client = wco.into_ctl_client(None).await?;
 
-    let mut receiver = client
-        .events_receiver(vec![
-            "provider_stopped".to_string(),
-            "provider_stop_failed".to_string(),
-        ])
-        .await
-        .map_err(boxed_err_to_anyhow)?;
-
     let (provider_id, friendly_name) = find_provider_id(&cmd.provider_id, &client).await?;
     let host_id = if let Some(host_id) = cmd.host_id {
         find_host_id(&host_id, &client).await?.0
@@ -133,69 +125,44 @@ pub async fn stop_provider(cmd: StopProviderCommand) -> Result<CommandOutput> {
         find_host_with_provider(&provider_id, &client).await?
     };
 
-    let ack = client
-        .stop_provider(
-            &host_id,
-            &provider_id,
-            &cmd.link_name,
-            &cmd.contract_id,
-            None,
-        )
-        .await
-        .map_err(boxed_err_to_anyhow)?;
+    let ProviderStoppedInfo {
+        host_id,
+        provider_id,
+        link_name,
+        contract_id,
+    } = provider::stop_provider(
+        &client,
+        &host_id,
+        &provider_id,
+        &cmd.link_name,
+        &cmd.contract_id,
+        timeout_ms,
+        cmd.skip_wait,
+    )
+    .await?;
 
-    if !ack.accepted {
-        bail!("Operation failed: {}", ack.error);
-    }
-    if cmd.skip_wait {
-        let text = format!(
+    let text = if cmd.skip_wait {
+        format!(
             "Provider {} stop request received",
             friendly_name.as_deref().unwrap_or(provider_id.as_ref())
-        );
-        return Ok(CommandOutput::new(
-            text.clone(),
-            HashMap::from([
-                ("result".into(), text.into()),
-                ("provider_id".into(), provider_id.to_string().into()),
-                ("link_name".into(), cmd.link_name.into()),
-                ("contract_id".into(), cmd.contract_id.into()),
-                ("host_id".into(), host_id.to_string().into()),
-            ]),
-        ));
-    }
-
-    let event = wait_for_provider_stop_event(
-        &mut receiver,
-        Duration::from_millis(timeout_ms),
-        host_id.to_string(),
-        provider_id.to_string(),
-    )
-    .await?;
+        )
+    } else {
+        format!(
+            "Provider [{}] stopped successfully",
+            friendly_name.as_deref().unwrap_or(provider_id.as_ref())
+        )
+    };
 
-    match event {
-        FindEventOutcome::Success(ProviderStoppedInfo {
-            host_id,
-            provider_id,
-            link_name,
-            contract_id,
-        }) => {
-            let text = format!(
-                "Provider [{}] stopped successfully",
-                friendly_name.as_deref().unwrap_or(provider_id.as_ref())
-            );
-            Ok(CommandOutput::new(
-                text.clone(),
-                HashMap::from([
-                    ("result".into(), text.into()),
-                    ("provider_id".into(), provider_id.into()),
-                    ("host_id".into(), host_id.into()),
-                    ("link_name".into(), link_name.into()),
-                    ("contract_id".into(), contract_id.into()),
-                ]),
-            ))
-        }
-        FindEventOutcome::Failure(err) => bail!("{}", err),
-    }
+    Ok(CommandOutput::new(
+        text.clone(),
+        HashMap::from([
+            ("result".into(), text.into()),
+            ("provider_id".into(), provider_id.into()),
+            ("host_id".into(), host_id.into()),
+            ("link_name".into(), link_name.into()),
+            ("contract_id".into(), contract_id.into()),
+        ]),
+    ))
 }
 
 pub async fn handle_stop_actor(cmd: StopActorCommand) -> Result<CommandOutput> {
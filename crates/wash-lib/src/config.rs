@@ -62,6 +62,10 @@ pub struct WashConnectionOptions {
     /// See https://docs.nats.io/using-nats/developer/connecting/creds for details.
     pub ctl_credsfile: Option<PathBuf>,
 
+    /// Seed file or literal of the key used to sign control interface requests, which identifies
+    /// the caller to hosts (e.g. for policy decisions)
+    pub ctl_caller_seed: Option<String>,
+
     /// JS domain for wasmcloud control interface. Defaults to None
     pub js_domain: Option<String>,
 
@@ -89,6 +93,9 @@ impl WashConnectionOptions {
         let ctl_credsfile = self
             .ctl_credsfile
             .or_else(|| self.ctx.ctl_credsfile.clone());
+        let ctl_caller_seed = self
+            .ctl_caller_seed
+            .or_else(|| self.ctx.ctl_caller_seed.clone());
 
         let auction_timeout_ms = auction_timeout_ms.unwrap_or(self.timeout_ms);
 
@@ -106,6 +113,10 @@ impl WashConnectionOptions {
             builder = builder.topic_prefix(topic_prefix);
         }
 
+        if let Some(seed) = ctl_caller_seed {
+            builder = builder.caller_key(ctl_caller_key(&seed).await?);
+        }
+
         let ctl_client = builder.build();
 
         Ok(ctl_client)
@@ -152,6 +163,16 @@ async fn extract_arg_value(arg: &str) -> Result<String> {
     }
 }
 
+/// Loads the key used to sign control interface requests from a seed file or literal
+pub async fn ctl_caller_key(seed: &str) -> Result<std::sync::Arc<nkeys::KeyPair>> {
+    let seed = extract_arg_value(seed)
+        .await
+        .context("Failed to extract control interface caller seed")?;
+    let key = nkeys::KeyPair::from_seed(seed.trim())
+        .context("Failed to create keypair from control interface caller seed")?;
+    Ok(std::sync::Arc::new(key))
+}
+
 /// Create a NATS client from NATS-related options
 pub async fn create_nats_client_from_opts(
     host: &str,
@@ -61,6 +61,10 @@ pub struct WashContext {
     #[serde_as(as = "NoneAsEmptyString")]
     pub ctl_seed: Option<String>,
     pub ctl_credsfile: Option<PathBuf>,
+    /// Seed of the key used to sign control interface requests, identifying the caller to hosts
+    #[serde(default)]
+    #[serde_as(as = "NoneAsEmptyString")]
+    pub ctl_caller_seed: Option<String>,
     /// timeout in milliseconds
     #[serde(default = "default_timeout_ms")]
     pub ctl_timeout: u64,
@@ -105,6 +109,7 @@ impl Default for WashContext {
             ctl_jwt: None,
             ctl_seed: None,
             ctl_credsfile: None,
+            ctl_caller_seed: None,
             ctl_timeout: DEFAULT_NATS_TIMEOUT_MS,
             lattice: DEFAULT_LATTICE.to_string(),
             js_domain: None,
@@ -36,6 +36,7 @@ pub mod context;
 pub mod drain;
 pub mod id;
 pub mod keys;
+pub mod provider;
 pub mod registry;
 pub mod spier;
 pub mod wait;
@@ -137,6 +137,24 @@ pub struct ProviderConfig {
     pub capability_id: String,
     /// The vendor name of the provider.
     pub vendor: String,
+    /// The directory to store the private signing keys in.
+    pub key_directory: PathBuf,
+    /// Name of the provider binary produced by the build. Defaults to the project name
+    pub bin_name: Option<String>,
+    /// Additional Rust target triples (e.g. "aarch64-unknown-linux-gnu") to cross-compile the provider
+    /// for. Each target is included in the provider archive alongside the native build.
+    pub cross_targets: Vec<String>,
+    /// File path the signed provider archive should be written to. Defaults to `./build/[name].par.gz`
+    pub destination: Option<PathBuf>,
+}
+
+impl ProviderConfig {
+    /// Helper function to get the provider binary name, falling back to the project name if not specified
+    pub fn bin_name(&self, common_config: &CommonConfig) -> String {
+        self.bin_name
+            .clone()
+            .unwrap_or_else(|| common_config.name.clone())
+    }
 }
 
 #[derive(Deserialize, Debug, PartialEq)]
@@ -145,6 +163,14 @@ struct RawProviderConfig {
     pub capability_id: String,
     /// The vendor name of the provider. Optional, defaults to 'NoVendor'.
     pub vendor: Option<String>,
+    /// The directory to store the private signing keys in. Defaults to "./keys".
+    pub key_directory: Option<PathBuf>,
+    /// Name of the provider binary produced by the build. Defaults to the project name
+    pub bin_name: Option<String>,
+    /// Additional Rust target triples to cross-compile the provider for. Defaults to none.
+    pub cross_targets: Option<Vec<String>>,
+    /// File path the signed provider archive should be written to. Defaults to `./build/[name].par.gz`
+    pub destination: Option<PathBuf>,
 }
 
 impl TryFrom<RawProviderConfig> for ProviderConfig {
@@ -154,6 +180,12 @@ impl TryFrom<RawProviderConfig> for ProviderConfig {
         Ok(Self {
             capability_id: raw_config.capability_id,
             vendor: raw_config.vendor.unwrap_or_else(|| "NoVendor".to_string()),
+            key_directory: raw_config
+                .key_directory
+                .unwrap_or_else(|| PathBuf::from("./keys")),
+            bin_name: raw_config.bin_name,
+            cross_targets: raw_config.cross_targets.unwrap_or_default(),
+            destination: raw_config.destination,
         })
     }
 }
@@ -164,6 +196,13 @@ pub struct InterfaceConfig {
     pub html_target: PathBuf,
     /// Path to codegen.toml file.
     pub codegen_config: PathBuf,
+    /// Directory containing the WIT package, and optionally a `deps.toml` file listing its dependencies.
+    pub wit_dir: PathBuf,
+    /// The path to the `wit-deps` binary used to resolve dependencies listed in `deps.toml`.
+    /// Optional, will default to search the user's `PATH` for `wit-deps` if not specified.
+    pub wit_deps_path: Option<PathBuf>,
+    /// File path the encoded WIT package should be written to. Defaults to `./build/[name].wasm`
+    pub destination: Option<PathBuf>,
 }
 #[derive(Deserialize, Debug, PartialEq)]
 
@@ -172,6 +211,12 @@ struct RawInterfaceConfig {
     pub html_target: Option<PathBuf>,
     /// Path to codegen.toml file. Optional, defaults to "./codegen.toml".
     pub codegen_config: Option<PathBuf>,
+    /// Directory containing the WIT package. Optional, defaults to "./wit".
+    pub wit_dir: Option<PathBuf>,
+    /// The path to the `wit-deps` binary. Optional, will default to search the user's `PATH` for `wit-deps` if not specified.
+    pub wit_deps_path: Option<PathBuf>,
+    /// File path the encoded WIT package should be written to. Defaults to `./build/[name].wasm`
+    pub destination: Option<PathBuf>,
 }
 
 impl TryFrom<RawInterfaceConfig> for InterfaceConfig {
@@ -185,6 +230,9 @@ impl TryFrom<RawInterfaceConfig> for InterfaceConfig {
             codegen_config: raw_config
                 .codegen_config
                 .unwrap_or_else(|| PathBuf::from("./codegen.toml")),
+            wit_dir: raw_config.wit_dir.unwrap_or_else(|| PathBuf::from("./wit")),
+            wit_deps_path: raw_config.wit_deps_path,
+            destination: raw_config.destination,
         })
     }
 }
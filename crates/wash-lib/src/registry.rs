@@ -22,6 +22,8 @@ const PROVIDER_ARCHIVE_CONFIG_MEDIA_TYPE: &str =
     "application/vnd.wasmcloud.provider.archive.config";
 const WASM_MEDIA_TYPE: &str = "application/vnd.module.wasm.content.layer.v1+wasm";
 const WASM_CONFIG_MEDIA_TYPE: &str = "application/vnd.wasmcloud.actor.archive.config";
+const WIT_PACKAGE_MEDIA_TYPE: &str = "application/vnd.wasmcloud.interface.layer.v1+wasm";
+const WIT_PACKAGE_CONFIG_MEDIA_TYPE: &str = "application/vnd.wasmcloud.interface.archive.config";
 const OCI_MEDIA_TYPE: &str = "application/vnd.oci.image.layer.v1.tar";
 
 // straight up stolen from oci_distribution::Reference
@@ -65,6 +67,8 @@ pub enum SupportedArtifacts {
     Par,
     /// WebAssembly modules
     Wasm,
+    /// WIT packages encoded as WebAssembly binaries, which describe interfaces
+    Wit,
 }
 
 // NOTE(thomastaylor312): In later refactors, we might want to consider making some sort of puller
@@ -138,7 +142,12 @@ pub async fn pull_oci_artifact(url: String, options: OciPullOptions) -> Result<V
         .pull(
             &image,
             &auth,
-            vec![PROVIDER_ARCHIVE_MEDIA_TYPE, WASM_MEDIA_TYPE, OCI_MEDIA_TYPE],
+            vec![
+                PROVIDER_ARCHIVE_MEDIA_TYPE,
+                WASM_MEDIA_TYPE,
+                WIT_PACKAGE_MEDIA_TYPE,
+                OCI_MEDIA_TYPE,
+            ],
         )
         .await?;
 
@@ -184,6 +193,7 @@ pub async fn push_oci_artifact(
             PROVIDER_ARCHIVE_MEDIA_TYPE,
             PROVIDER_ARCHIVE_CONFIG_MEDIA_TYPE,
         ),
+        SupportedArtifacts::Wit => (WIT_PACKAGE_MEDIA_TYPE, WIT_PACKAGE_CONFIG_MEDIA_TYPE),
     };
 
     let mut config_buf = vec![];
@@ -238,7 +248,10 @@ pub async fn validate_artifact(artifact: &[u8]) -> Result<SupportedArtifacts> {
         Ok(_) => Ok(SupportedArtifacts::Wasm),
         Err(_) => match validate_provider_archive(artifact).await {
             Ok(_) => Ok(SupportedArtifacts::Par),
-            Err(_) => bail!("Unsupported artifact type"),
+            Err(_) => match validate_wit_package(artifact) {
+                Ok(_) => Ok(SupportedArtifacts::Wit),
+                Err(_) => bail!("Unsupported artifact type"),
+            },
         },
     }
 }
@@ -261,3 +274,15 @@ async fn validate_provider_archive(artifact: &[u8]) -> Result<()> {
         Err(e) => bail!("Invalid provider archive: {}", e),
     }
 }
+
+/// Attempts to decode a WIT package
+/// Will fail if the artifact is not a WIT package encoded as a WebAssembly binary
+fn validate_wit_package(artifact: &[u8]) -> Result<()> {
+    match wit_component::decode(artifact) {
+        Ok(wit_component::DecodedWasm::WitPackage(..)) => Ok(()),
+        Ok(wit_component::DecodedWasm::Component(..)) => {
+            bail!("Component is not a WIT package")
+        }
+        Err(e) => bail!("Invalid WIT package: {}", e),
+    }
+}
@@ -3,8 +3,8 @@ use std::{collections::HashSet, fs, path::PathBuf};
 use claims::{assert_err, assert_ok};
 use semver::Version;
 use wash_lib::parser::{
-    get_config, ActorConfig, CommonConfig, LanguageConfig, RegistryConfig, RustConfig,
-    TinyGoConfig, TypeConfig, WasmTarget,
+    get_config, ActorConfig, CommonConfig, InterfaceConfig, LanguageConfig, ProviderConfig,
+    RegistryConfig, RustConfig, TinyGoConfig, TypeConfig, WasmTarget,
 };
 
 #[test]
@@ -105,6 +105,84 @@ fn rust_actor_with_revision() {
     );
 }
 
+#[test]
+fn rust_provider() {
+    let result = get_config(
+        Some(PathBuf::from("./tests/parser/files/rust_provider.toml")),
+        None,
+    );
+
+    let config = assert_ok!(result);
+
+    assert_eq!(config.language, LanguageConfig::Rust(RustConfig::default()));
+
+    assert_eq!(
+        config.project_type,
+        TypeConfig::Provider(ProviderConfig {
+            capability_id: "wasmcloud:httpserver".to_string(),
+            vendor: "wayne-industries".to_string(),
+            key_directory: PathBuf::from("./keys"),
+            bin_name: Some("test-provider".to_string()),
+            cross_targets: vec![
+                "aarch64-unknown-linux-gnu".to_string(),
+                "x86_64-apple-darwin".to_string(),
+            ],
+            destination: None,
+        })
+    );
+
+    assert_eq!(
+        config.common,
+        CommonConfig {
+            name: "testprovider".to_string(),
+            version: Version::parse("0.1.0").unwrap(),
+            revision: 0,
+            path: PathBuf::from("./tests/parser/files/")
+                .canonicalize()
+                .unwrap(),
+            wasm_bin_name: None,
+            registry: RegistryConfig::default(),
+        }
+    );
+}
+
+#[test]
+fn interface() {
+    let result = get_config(
+        Some(PathBuf::from("./tests/parser/files/interface.toml")),
+        None,
+    );
+
+    let config = assert_ok!(result);
+
+    assert_eq!(config.language, LanguageConfig::Other("other".to_string()));
+
+    assert_eq!(
+        config.project_type,
+        TypeConfig::Interface(InterfaceConfig {
+            html_target: PathBuf::from("./html"),
+            codegen_config: PathBuf::from("./codegen.toml"),
+            wit_dir: PathBuf::from("./interface/wit"),
+            wit_deps_path: None,
+            destination: Some(PathBuf::from("./build/interface.wasm")),
+        })
+    );
+
+    assert_eq!(
+        config.common,
+        CommonConfig {
+            name: "testinterface".to_string(),
+            version: Version::parse("0.1.0").unwrap(),
+            revision: 0,
+            path: PathBuf::from("./tests/parser/files/")
+                .canonicalize()
+                .unwrap(),
+            wasm_bin_name: None,
+            registry: RegistryConfig::default(),
+        }
+    );
+}
+
 #[test]
 fn tinygo_actor_module() {
     let result = get_config(
@@ -1,7 +1,10 @@
 #![warn(clippy::pedantic)]
 
+use core::num::{NonZeroU32, NonZeroUsize};
+
 use std::collections::{HashMap, HashSet};
 use std::env;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -10,14 +13,16 @@ use clap::Parser;
 use nkeys::KeyPair;
 use tokio::time::{timeout, timeout_at};
 use tokio::{select, signal};
-use tracing::{warn, Level as TracingLogLevel};
+use tracing::{error, warn, Level as TracingLogLevel};
 use wasmcloud_core::logging::Level as WasmcloudLogLevel;
 use wasmcloud_core::OtelConfig;
 use wasmcloud_host::oci::Config as OciConfig;
 use wasmcloud_host::url::Url;
-use wasmcloud_host::wasmbus::config::PolicyService as PolicyServiceConfig;
+use wasmcloud_host::wasmbus::config::{
+    ActorLimits, LinkPolicy, PolicyService as PolicyServiceConfig,
+};
 use wasmcloud_host::WasmbusHostConfig;
-use wasmcloud_tracing::configure_tracing;
+use wasmcloud_tracing::{configure_metrics, configure_tracing};
 
 #[derive(Debug, Parser)]
 #[allow(clippy::struct_excessive_bools)]
@@ -90,6 +95,66 @@ struct Args {
         env = "WASMCLOUD_ALLOW_FILE_LOAD"
     )]
     allow_file_load: bool,
+    /// If provided, compiled actors are persisted in this directory and reused across host restarts instead of being compiled again. The directory must be owned by the host user and not accessible to other users
+    #[clap(
+        long = "compilation-cache-dir",
+        env = "WASMCLOUD_COMPILATION_CACHE_DIR"
+    )]
+    compilation_cache_dir: Option<PathBuf>,
+    /// Maximum total size in bytes of the compiled actors persisted in `compilation_cache_dir`. Defaults to 1 GiB. Requires `compilation_cache_dir` to be set.
+    #[clap(
+        long = "compilation-cache-max-size",
+        env = "WASMCLOUD_COMPILATION_CACHE_MAX_SIZE",
+        requires = "compilation_cache_dir"
+    )]
+    compilation_cache_max_size: Option<u64>,
+    /// If provided, memories, tables and instances for this many concurrently running actor instances are preallocated up front using a pooling allocator, instead of being allocated on demand
+    #[clap(
+        long = "actor-pooled-instances",
+        env = "WASMCLOUD_ACTOR_POOLED_INSTANCES"
+    )]
+    actor_pooled_instances: Option<u32>,
+    /// If provided, limits the linear memory of every actor instance to this many bytes. Can be set or lowered per actor using the `wasmcloud.max_memory_size` actor annotation
+    #[clap(
+        long = "actor-max-memory-size",
+        env = "WASMCLOUD_ACTOR_MAX_MEMORY_SIZE"
+    )]
+    actor_max_memory_size: Option<usize>,
+    /// If provided, limits the number of elements in every table of an actor instance. Can be set or lowered per actor using the `wasmcloud.max_table_elements` actor annotation
+    #[clap(
+        long = "actor-max-table-elements",
+        env = "WASMCLOUD_ACTOR_MAX_TABLE_ELEMENTS"
+    )]
+    actor_max_table_elements: Option<u32>,
+    /// If provided, limits the number of core WebAssembly instances every actor instance may create. Can be set or lowered per actor using the `wasmcloud.max_core_instances` actor annotation
+    #[clap(
+        long = "actor-max-core-instances",
+        env = "WASMCLOUD_ACTOR_MAX_CORE_INSTANCES"
+    )]
+    actor_max_core_instances: Option<usize>,
+    /// If provided, actor invocations executing for longer than this many milliseconds are interrupted. Can be set or lowered per actor using the `wasmcloud.max_execution_time_ms` actor annotation
+    #[clap(
+        long = "actor-max-execution-time-ms",
+        env = "WASMCLOUD_ACTOR_MAX_EXECUTION_TIME_MS",
+        value_parser = parse_duration,
+    )]
+    actor_max_execution_time_ms: Option<Duration>,
+    /// Keep a pool of pre-instantiated instances of each actor, filled lazily as the actor is invoked. Every pooled instance handles a single invocation
+    #[clap(long = "actor-instance-pool", env = "WASMCLOUD_ACTOR_INSTANCE_POOL")]
+    actor_instance_pool: bool,
+    /// If provided, the maximum number of idle instances kept in the pool of each actor. Defaults to the maximum number of instances of the actor
+    #[clap(
+        long = "actor-instance-pool-size",
+        env = "WASMCLOUD_ACTOR_INSTANCE_POOL_SIZE",
+        requires = "actor_instance_pool"
+    )]
+    actor_instance_pool_size: Option<NonZeroUsize>,
+    /// Snapshot the actors and providers running on the host into the lattice data bucket whenever they change
+    #[clap(long = "snapshot-state", env = "WASMCLOUD_SNAPSHOT_STATE")]
+    snapshot_state: bool,
+    /// Restore the actors and providers recorded in the snapshot of a previous run of the host on start. Requires a stable `--host-seed`, since the snapshot is identified by the host public key
+    #[clap(long = "restore-state", env = "WASMCLOUD_RESTORE_STATE")]
+    restore_state: bool,
     /// Enable JSON structured logging from the wasmCloud host
     #[clap(
         long = "enable-structured-logging",
@@ -157,18 +222,55 @@ struct Args {
     /// Timeout in milliseconds for all RPC calls
     #[clap(long = "rpc-timeout-ms", default_value = "2000", env = "WASMCLOUD_RPC_TIMEOUT_MS", value_parser = parse_duration, hide = true)]
     rpc_timeout_ms: Duration,
+    /// Maximum number of times invocations of one of the `link_idempotent_operations`, which failed to reach the provider, are retried. Can be overridden per link using the `wasmcloud.max_retries` link definition value
+    #[clap(
+        long = "link-max-retries",
+        default_value = "0",
+        env = "WASMCLOUD_LINK_MAX_RETRIES"
+    )]
+    link_max_retries: u32,
+    /// Comma-separated list of idempotent provider operations, like `wasmcloud:keyvalue/KeyValue.Get` or `KeyValue.Get`, which may be retried. Can be overridden per link using the `wasmcloud.idempotent_operations` link definition value
+    #[clap(
+        long = "link-idempotent-operations",
+        env = "WASMCLOUD_LINK_IDEMPOTENT_OPERATIONS",
+        value_delimiter = ','
+    )]
+    link_idempotent_operations: Vec<String>,
+    /// Delay in milliseconds before the first retry of a provider invocation, which doubles with every subsequent retry
+    #[clap(long = "link-retry-backoff-ms", default_value = "100", env = "WASMCLOUD_LINK_RETRY_BACKOFF_MS", value_parser = parse_duration)]
+    link_retry_backoff_ms: Duration,
+    /// Number of consecutive provider invocations failing to reach the provider, after which further invocations over the link fail immediately. Circuit breaking is disabled if unset or 0
+    #[clap(
+        long = "link-circuit-breaker-threshold",
+        env = "WASMCLOUD_LINK_CIRCUIT_BREAKER_THRESHOLD"
+    )]
+    link_circuit_breaker_threshold: Option<u32>,
+    /// Time in milliseconds after which an open circuit breaker lets a trial invocation through
+    #[clap(long = "link-circuit-breaker-reset-ms", default_value = "30000", env = "WASMCLOUD_LINK_CIRCUIT_BREAKER_RESET_MS", value_parser = parse_duration)]
+    link_circuit_breaker_reset_ms: Duration,
     /// Optional flag to require host communication over TLS with a NATS server for RPC messages
     #[clap(long = "rpc-tls", env = "WASMCLOUD_RPC_TLS", hide = true)]
     rpc_tls: bool,
 
     /// If provided, enables policy checks on start actions and actor invocations
-    #[clap(long = "policy-topic", env = "WASMCLOUD_POLICY_TOPIC")]
+    #[clap(
+        long = "policy-topic",
+        env = "WASMCLOUD_POLICY_TOPIC",
+        group = "policy_backend"
+    )]
     policy_topic: Option<String>,
-    /// If provided, allows the host to subscribe to updates on past policy decisions. Requires `policy_topic` to be set.
+    /// If provided, enables policy checks on start actions and actor invocations, evaluated in-process against the rules in the given JSON file. The file is reloaded when the host receives SIGHUP. Conflicts with `policy_topic`.
+    #[clap(
+        long = "policy-rules-file",
+        env = "WASMCLOUD_POLICY_RULES_FILE",
+        group = "policy_backend"
+    )]
+    policy_rules_file: Option<PathBuf>,
+    /// If provided, allows the host to subscribe to updates on past policy decisions. Requires `policy_topic` or `policy_rules_file` to be set.
     #[clap(
         long = "policy-changes-topic",
         env = "WASMCLOUD_POLICY_CHANGES_TOPIC",
-        requires = "policy_topic"
+        requires = "policy_backend"
     )]
     policy_changes_topic: Option<String>,
     /// If provided, allows setting a custom timeout for requesting policy decisions. Defaults to one second. Requires `policy_topic` to be set.
@@ -179,6 +281,35 @@ struct Args {
         value_parser = parse_duration,
     )]
     policy_timeout_ms: Option<Duration>,
+    /// If provided, every policy decision is published on this subject as a CloudEvent, including whether it was served from the cache and how long it took. Requires `policy_topic` or `policy_rules_file` to be set.
+    #[clap(
+        long = "policy-audit-subject",
+        env = "WASMCLOUD_POLICY_AUDIT_SUBJECT",
+        requires = "policy_backend"
+    )]
+    policy_audit_subject: Option<String>,
+    /// The JetStream stream policy decisions published on `policy_audit_subject` are retained in, which is created if it does not exist. Defaults to the stream `wash policy denials` reads from.
+    #[clap(
+        long = "policy-audit-stream",
+        env = "WASMCLOUD_POLICY_AUDIT_STREAM",
+        default_value = "wasmcloud_policy_audit",
+        requires = "policy_audit_subject"
+    )]
+    policy_audit_stream: String,
+    /// The maximum age in seconds of the policy decisions retained in a created `policy_audit_stream`. Defaults to 7 days.
+    #[clap(
+        long = "policy-audit-max-age-secs",
+        env = "WASMCLOUD_POLICY_AUDIT_MAX_AGE_SECS",
+        requires = "policy_audit_subject"
+    )]
+    policy_audit_max_age_secs: Option<u64>,
+    /// The maximum size in bytes of a created `policy_audit_stream`, above which the oldest policy decisions are discarded. Defaults to 1 GiB.
+    #[clap(
+        long = "policy-audit-max-bytes",
+        env = "WASMCLOUD_POLICY_AUDIT_MAX_BYTES",
+        requires = "policy_audit_subject"
+    )]
+    policy_audit_max_bytes: Option<i64>,
 
     /// Used in tandem with `oci_user` and `oci_password` to override credentials for a specific OCI registry.
     #[clap(
@@ -215,6 +346,40 @@ struct Args {
         env = "OTEL_EXPORTER_OTLP_ENDPOINT"
     )]
     otel_exporter_otlp_endpoint: Option<String>,
+
+    /// Specifies which exporters to use for metrics, separated by commas. "otlp" pushes metrics to the OTLP endpoint and "prometheus" serves them to be scraped
+    #[clap(
+        long = "otel-metrics-exporter",
+        env = "OTEL_METRICS_EXPORTER",
+        value_delimiter = ','
+    )]
+    otel_metrics_exporter: Option<Vec<String>>,
+
+    /// Specifies the host to serve metrics on for the Prometheus exporter. Defaults to "localhost"
+    #[clap(
+        long = "otel-exporter-prometheus-host",
+        env = "OTEL_EXPORTER_PROMETHEUS_HOST"
+    )]
+    otel_exporter_prometheus_host: Option<String>,
+
+    /// Specifies the port to serve metrics on for the Prometheus exporter. Defaults to 9464
+    #[clap(
+        long = "otel-exporter-prometheus-port",
+        env = "OTEL_EXPORTER_PROMETHEUS_PORT"
+    )]
+    otel_exporter_prometheus_port: Option<u16>,
+
+    /// Specifies which exporter to use for logs. Only "otlp" is supported at this time. Defaults to the traces exporter
+    #[clap(long = "otel-logs-exporter", env = "OTEL_LOGS_EXPORTER")]
+    otel_logs_exporter: Option<String>,
+
+    /// Specifies resource attributes attached to exported traces, logs and metrics, separated by commas and formatted as `key=value`
+    #[clap(
+        long = "otel-resource-attributes",
+        env = "OTEL_RESOURCE_ATTRIBUTES",
+        value_delimiter = ','
+    )]
+    otel_resource_attributes: Option<Vec<String>>,
 }
 
 const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
@@ -232,10 +397,22 @@ async fn main() -> anyhow::Result<()> {
 
     let args: Args = Args::parse();
 
-    let otel_config = OtelConfig {
-        traces_exporter: args.otel_traces_exporter,
-        exporter_otlp_endpoint: args.otel_exporter_otlp_endpoint,
-    };
+    let mut otel_config = OtelConfig::default();
+    otel_config.traces_exporter = args.otel_traces_exporter;
+    otel_config.exporter_otlp_endpoint = args.otel_exporter_otlp_endpoint;
+    otel_config.metrics_exporter = args
+        .otel_metrics_exporter
+        .map(|exporters| exporters.join(","));
+    otel_config.exporter_prometheus_host = args.otel_exporter_prometheus_host;
+    otel_config.exporter_prometheus_port = args.otel_exporter_prometheus_port;
+    otel_config.logs_exporter = args.otel_logs_exporter;
+    otel_config.resource_attributes = args
+        .otel_resource_attributes
+        .unwrap_or_default()
+        .iter()
+        .map(|attribute| parse_label(attribute))
+        .collect::<anyhow::Result<_>>()
+        .context("failed to parse OTEL resource attributes")?;
     let log_level = WasmcloudLogLevel::from(args.log_level);
     if let Err(e) = configure_tracing(
         "wasmcloud-host",
@@ -245,6 +422,9 @@ async fn main() -> anyhow::Result<()> {
     ) {
         eprintln!("Failed to configure tracing: {e}");
     };
+    if let Err(e) = configure_metrics("wasmcloud-host", &otel_config).await {
+        eprintln!("Failed to configure metrics: {e:#}");
+    };
 
     let ctl_nats_url = Url::parse(&format!(
         "nats://{}:{}",
@@ -305,6 +485,18 @@ async fn main() -> anyhow::Result<()> {
         policy_topic: args.policy_topic,
         policy_changes_topic: args.policy_changes_topic,
         policy_timeout_ms: args.policy_timeout_ms,
+        policy_rules_file: args.policy_rules_file,
+        policy_audit_subject: args.policy_audit_subject,
+        policy_audit_stream: Some(args.policy_audit_stream),
+        policy_audit_max_age: args.policy_audit_max_age_secs.map(Duration::from_secs),
+        policy_audit_max_bytes: args.policy_audit_max_bytes,
+    };
+    let actor_limits = ActorLimits {
+        pooled_instances: args.actor_pooled_instances,
+        max_memory_size: args.actor_max_memory_size,
+        max_table_elements: args.actor_max_table_elements,
+        max_core_instances: args.actor_max_core_instances,
+        max_execution_time: args.actor_max_execution_time_ms,
     };
     let mut labels = args
         .label
@@ -350,23 +542,49 @@ async fn main() -> anyhow::Result<()> {
         rpc_key: rpc_key.or_else(|| nats_key.clone()),
         rpc_tls: args.rpc_tls,
         allow_file_load: args.allow_file_load,
+        compilation_cache_dir: args.compilation_cache_dir,
+        compilation_cache_max_size: args.compilation_cache_max_size,
         log_level,
         enable_structured_logging: args.enable_structured_logging,
         otel_config,
         policy_service_config,
+        actor_limits,
+        actor_instance_pool: args.actor_instance_pool,
+        actor_instance_pool_size: args.actor_instance_pool_size,
+        link_policy: LinkPolicy {
+            timeout: None,
+            max_retries: args.link_max_retries,
+            idempotent_operations: args.link_idempotent_operations,
+            retry_backoff: args.link_retry_backoff_ms,
+            circuit_breaker_threshold: args
+                .link_circuit_breaker_threshold
+                .and_then(NonZeroU32::new),
+            circuit_breaker_reset: args.link_circuit_breaker_reset_ms,
+        },
+        snapshot_state: args.snapshot_state,
+        restore_state: args.restore_state,
     }))
     .await
     .context("failed to initialize host")?;
     #[cfg(unix)]
     let deadline = {
         let mut terminate = signal::unix::signal(signal::unix::SignalKind::terminate())?;
-        select! {
-            sig = signal::ctrl_c() => {
-                sig.context("failed to wait for Ctrl-C")?;
-                None
-            },
-            _ = terminate.recv() => None,
-            deadline = host.stopped() => deadline?,
+        // Reload the policy rules file on SIGHUP
+        let mut hangup = signal::unix::signal(signal::unix::SignalKind::hangup())?;
+        loop {
+            select! {
+                sig = signal::ctrl_c() => {
+                    sig.context("failed to wait for Ctrl-C")?;
+                    break None;
+                },
+                _ = terminate.recv() => break None,
+                _ = hangup.recv() => {
+                    if let Err(err) = host.reload_policy_rules().await {
+                        error!(?err, "failed to reload policy rules");
+                    }
+                },
+                deadline = host.stopped() => break deadline?,
+            }
         }
     };
     #[cfg(not(unix))]
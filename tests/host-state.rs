@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Context, Result};
+use nkeys::KeyPair;
+use tokio::time::{sleep, timeout, Duration};
+use tokio::try_join;
+use url::Url;
+use wasmcloud_control_interface::{ClientBuilder, HostInventory};
+use wasmcloud_host::wasmbus::{Host, HostConfig};
+
+pub mod common;
+use common::copy_par;
+
+use crate::common::nats::start_nats;
+use crate::common::{assert_scale_actor, assert_start_provider, stop_server};
+
+const LATTICE: &str = "test-host-state";
+
+/// Test that a host restarted with the same host key restores the actors and providers recorded
+/// in the state snapshot of its previous run
+#[tokio::test(flavor = "multi_thread")]
+async fn host_state_restore() -> Result<()> {
+    let ((nats_server, stop_nats_tx, nats_url, nats_client),) =
+        try_join!(start_nats()).context("failed to start backing services")?;
+
+    let httpserver_provider_key = KeyPair::from_seed(test_providers::RUST_HTTPSERVER_SUBJECT)
+        .context("failed to parse `rust-httpserver` provider key")?;
+    let (httpserver_provider_url, _httpserver_provider_tmp_path) =
+        copy_par(test_providers::RUST_HTTPSERVER)
+            .await
+            .context("failed to build copied PAR")?;
+
+    let actor_url = Url::from_file_path(test_actors::RUST_BUILTINS_MODULE_REACTOR_SIGNED)
+        .map_err(|()| anyhow!("failed to construct actor ref"))?;
+
+    let ctl_client = ClientBuilder::new(nats_client.clone())
+        .lattice(LATTICE.to_string())
+        .build();
+
+    let cluster_key = Arc::new(KeyPair::new_cluster());
+    let host_key = Arc::new(KeyPair::new_server());
+    let host_config = || HostConfig {
+        ctl_nats_url: nats_url.clone(),
+        rpc_nats_url: nats_url.clone(),
+        lattice: LATTICE.into(),
+        cluster_key: Some(Arc::clone(&cluster_key)),
+        cluster_issuers: Some(vec![cluster_key.public_key()]),
+        host_key: Some(Arc::clone(&host_key)),
+        provider_shutdown_delay: Some(Duration::from_millis(300)),
+        allow_file_load: true,
+        snapshot_state: true,
+        restore_state: true,
+        ..Default::default()
+    };
+
+    let (_host, shutdown_host) = Host::new(host_config())
+        .await
+        .context("failed to initialize host")?;
+
+    assert_scale_actor(
+        &ctl_client,
+        &nats_client,
+        LATTICE,
+        &host_key,
+        &actor_url,
+        Some(HashMap::from([("app".into(), "restored".into())])),
+        2,
+    )
+    .await?;
+    assert_start_provider(
+        &ctl_client,
+        &nats_client,
+        LATTICE,
+        &host_key,
+        &httpserver_provider_key,
+        "default",
+        &httpserver_provider_url,
+        None,
+    )
+    .await?;
+
+    // Snapshots are taken after the start events are published, so wait for the snapshot to
+    // record both the actor and the provider
+    let bucket = async_nats::jetstream::new(nats_client.clone())
+        .get_key_value(format!("LATTICEDATA_{LATTICE}"))
+        .await
+        .map_err(|e| anyhow!(e).context("failed to get lattice data bucket"))?;
+    let state_key = format!("HOSTSTATE_{}", host_key.public_key());
+    timeout(Duration::from_secs(10), async {
+        loop {
+            if let Ok(Some(buf)) = bucket.get(&state_key).await {
+                let state: serde_json::Value = serde_json::from_slice(&buf)?;
+                if state["actors"].as_array().is_some_and(|a| a.len() == 1)
+                    && state["providers"].as_array().is_some_and(|p| p.len() == 1)
+                {
+                    return anyhow::Ok(());
+                }
+            }
+            sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .context("timed out waiting for host state snapshot")??;
+
+    shutdown_host.await?;
+
+    // Restart the host with the same key, which should start the actor and provider again
+    let (_host, shutdown_host) = Host::new(host_config())
+        .await
+        .context("failed to initialize restarted host")?;
+
+    let HostInventory {
+        actors, providers, ..
+    } = timeout(Duration::from_secs(30), async {
+        loop {
+            match ctl_client.get_host_inventory(&host_key.public_key()).await {
+                Ok(inventory)
+                    if inventory
+                        .actors
+                        .iter()
+                        .map(|actor| actor.instances.len())
+                        .sum::<usize>()
+                        > 0
+                        && !inventory.providers.is_empty() =>
+                {
+                    return inventory;
+                }
+                _ => sleep(Duration::from_millis(500)).await,
+            }
+        }
+    })
+    .await
+    .context("timed out waiting for host state to be restored")?;
+
+    let [actor] = actors.as_slice() else {
+        bail!("expected a single restored actor, got {actors:?}");
+    };
+    let [instance] = actor.instances.as_slice() else {
+        bail!("expected a single restored actor instance, got {actor:?}");
+    };
+    assert_eq!(instance.image_ref.as_deref(), Some(actor_url.as_str()));
+    assert_eq!(instance.max_instances, 2);
+    assert_eq!(
+        instance
+            .annotations
+            .as_ref()
+            .and_then(|annotations| annotations.get("app"))
+            .map(String::as_str),
+        Some("restored")
+    );
+    let [provider] = providers.as_slice() else {
+        bail!("expected a single restored provider, got {providers:?}");
+    };
+    assert_eq!(provider.id, httpserver_provider_key.public_key());
+    assert_eq!(provider.link_name, "default");
+    assert_eq!(
+        provider.image_ref.as_deref(),
+        Some(httpserver_provider_url.as_str())
+    );
+
+    shutdown_host.await?;
+    try_join!(stop_server(nats_server, stop_nats_tx)).context("failed to stop servers")?;
+
+    Ok(())
+}